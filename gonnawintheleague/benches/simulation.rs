@@ -0,0 +1,58 @@
+//! Times a web request's worth of simulations with one model shared by every
+//! simulation against rebuilding the model's samplers for every simulation,
+//! as run_simulation used to
+//!
+//! Both run through run_simulation_with_model, so the only difference timed
+//! is the construction of the weighted samplers
+//!
+//! Run with `cargo bench`
+
+use gonnawintheleague::{
+    run_simulation_with_model, FixtureList, HistoricalAverageModel, LeagueTable,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIMULATIONS: u32 = 16000;
+
+/// Runs the simulation the given number of times and returns how long it took
+fn time(simulations: u32, mut simulate: impl FnMut() -> u32) -> Duration {
+    let start = Instant::now();
+    for _ in 0..simulations {
+        black_box(simulate());
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let teams: Vec<String> = (1..=20).map(|i| format!("Team {i}")).collect();
+    let mut table = LeagueTable::new();
+    for (i, team) in teams.iter().enumerate() {
+        table.add_team(team.clone(), 40 - i as u32, 0).unwrap();
+    }
+    // the last five matchdays of a double round robin
+    let fixtures: Vec<_> = FixtureList::round_robin(teams.iter().map(String::as_str), true)
+        .iter()
+        .rev()
+        .take(50)
+        .cloned()
+        .collect();
+    let rng = &mut rand::rng();
+
+    let model = HistoricalAverageModel::new();
+    let shared = time(SIMULATIONS, || {
+        run_simulation_with_model("Team 4", &table, &fixtures, &model, rng).final_rank()
+    });
+    let rebuilt = time(SIMULATIONS, || {
+        let model = HistoricalAverageModel::new();
+        run_simulation_with_model("Team 4", &table, &fixtures, &model, rng).final_rank()
+    });
+
+    println!("{SIMULATIONS} simulations of {} fixtures", fixtures.len());
+    println!("  shared model:          {shared:?}");
+    println!("  model rebuilt per run: {rebuilt:?}");
+    println!(
+        "  speedup:               {:.2}x",
+        rebuilt.as_secs_f64() / shared.as_secs_f64()
+    );
+}
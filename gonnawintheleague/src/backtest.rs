@@ -0,0 +1,536 @@
+//! Backtesting of the simulator against seasons that have already been played
+//!
+//! A finished season is rewound to the end of each of its matchdays in turn and
+//! the rest of it simulated, and the predicted chance of every team finishing at
+//! or above a rank is scored against where that team really finished, so that
+//! changes to the simulator can be compared by the numbers
+
+use crate::{
+    run_simulations, run_simulations_with_model, simulate_table_with_model, LeagueTable, Match,
+    MatchMarkets, MatchResult, ScoreModel, SimulationConfig, SimulationReport, TableError,
+};
+use rand::prelude::*;
+use std::collections::BTreeSet;
+
+// the number of equal-width probability bins a calibration curve is drawn from
+const CALIBRATION_BINS: usize = 10;
+// the least chance a model is taken to give any outcome, so that a single
+// outcome it ruled out cannot make its log loss infinite
+const MIN_PROBABILITY: f64 = 1e-6;
+
+/// A past season to replay, along with how many simulations to run from each matchday
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backtest {
+    teams: Vec<String>,
+    results: Vec<MatchResult>,
+    config: SimulationConfig,
+}
+
+impl Backtest {
+    /// create a backtest of the season played between the given teams with the
+    /// given results, running 1000 simulations from each matchday
+    ///
+    /// Results are placed by the matchday of their fixture, and any without
+    /// one are taken to have been played before the first matchday
+    pub fn new<I, S>(teams: I, results: &[MatchResult]) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            teams: teams.into_iter().map(Into::into).collect(),
+            results: results.to_vec(),
+            config: SimulationConfig::new(1000),
+        }
+    }
+
+    /// Sets how the simulations from each matchday are run
+    pub fn with_config(mut self, config: SimulationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns how the simulations from each matchday are run
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Simulates the rest of the season from the end of every matchday but the
+    /// last and predicts each team's chance of finishing at or above the given
+    /// rank, alongside whether it really did
+    ///
+    /// Fails if a result names a team that is not in the season
+    pub fn run(&self, rank: u32) -> std::result::Result<BacktestReport, TableError> {
+        self.replay(rank, |table, remaining| {
+            run_simulations(&self.config, table, remaining)
+        })
+    }
+
+    /// Predicts as in run, sampling every score from the given model as
+    /// run_simulations_with_model does
+    pub fn run_with_model<M>(
+        &self,
+        rank: u32,
+        model: &M,
+    ) -> std::result::Result<BacktestReport, TableError>
+    where
+        M: ScoreModel + Clone + Send,
+    {
+        self.replay(rank, |table, remaining| {
+            run_simulations_with_model(&self.config, table, remaining, model)
+        })
+    }
+
+    /// Rewinds the season to the end of every matchday but the last, simulates
+    /// the rest of it from each with the given function and scores the
+    /// predictions made for the rank
+    fn replay<F>(
+        &self,
+        rank: u32,
+        mut simulate: F,
+    ) -> std::result::Result<BacktestReport, TableError>
+    where
+        F: FnMut(&LeagueTable, &[Match]) -> SimulationReport,
+    {
+        let final_table = LeagueTable::from_results(self.teams.iter().cloned(), &self.results)?;
+        let final_standings = final_table.standings();
+        let matchday_of = |result: &MatchResult| result.fixture().matchday().unwrap_or(0);
+        let matchdays: BTreeSet<u32> = self.results.iter().map(matchday_of).collect();
+
+        let mut predictions = Vec::new();
+        // nothing is left to predict once the last matchday is played
+        for &matchday in matchdays.iter().rev().skip(1).rev() {
+            let (played, remaining): (Vec<&MatchResult>, Vec<&MatchResult>) = self
+                .results
+                .iter()
+                .partition(|&result| matchday_of(result) <= matchday);
+            let played: Vec<MatchResult> = played.into_iter().cloned().collect();
+            let remaining: Vec<Match> = remaining
+                .into_iter()
+                .map(|result| result.fixture().clone())
+                .collect();
+            let table = LeagueTable::from_results(self.teams.iter().cloned(), &played)?;
+            let report = simulate(&table, &remaining);
+            for team in &self.teams {
+                predictions.push(Prediction {
+                    matchday,
+                    team: team.clone(),
+                    probability: report.probability(team, rank),
+                    achieved: final_standings
+                        .rank_of(team)
+                        .is_some_and(|finish| finish as u32 <= rank),
+                });
+            }
+        }
+        Ok(BacktestReport { predictions })
+    }
+}
+
+/// One team's predicted chance of reaching the rank, made at the end of a matchday
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    matchday: u32,
+    team: String,
+    probability: f64,
+    achieved: bool,
+}
+
+impl Prediction {
+    /// Returns the matchday after which the prediction was made
+    pub fn matchday(&self) -> u32 {
+        self.matchday
+    }
+
+    /// Returns the team's name
+    pub fn team(&self) -> &str {
+        &self.team
+    }
+
+    /// Returns the predicted chance of the team reaching the rank
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    /// Returns true if the team really did reach the rank
+    pub fn achieved(&self) -> bool {
+        self.achieved
+    }
+}
+
+/// Every prediction made while replaying a season, and how well they held up
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestReport {
+    predictions: Vec<Prediction>,
+}
+
+impl BacktestReport {
+    /// Returns every prediction, matchday by matchday
+    pub fn predictions(&self) -> &[Prediction] {
+        &self.predictions
+    }
+
+    /// Returns the mean squared difference between each predicted chance and
+    /// what happened, counted as 1 or 0, so lower is better and always
+    /// guessing a half scores 0.25. Zero if no predictions were made
+    pub fn brier_score(&self) -> f64 {
+        brier_score(self.predictions.iter())
+    }
+
+    /// Returns the Brier score of only the predictions made after the given matchday
+    pub fn brier_score_at(&self, matchday: u32) -> f64 {
+        brier_score(
+            self.predictions
+                .iter()
+                .filter(|prediction| prediction.matchday == matchday),
+        )
+    }
+
+    /// Returns the calibration curve of the predictions, grouping them into
+    /// ten equal bands of predicted chance and comparing each band's mean
+    /// prediction with how often its teams really reached the rank
+    ///
+    /// Bands holding no predictions are left out
+    pub fn calibration(&self) -> Vec<CalibrationBin> {
+        let mut bins: Vec<CalibrationBin> = (0..CALIBRATION_BINS)
+            .map(|bin| CalibrationBin {
+                lower: bin as f64 / CALIBRATION_BINS as f64,
+                upper: (bin + 1) as f64 / CALIBRATION_BINS as f64,
+                predicted: 0.0,
+                observed: 0.0,
+                count: 0,
+            })
+            .collect();
+        for prediction in &self.predictions {
+            // a certainty falls into the top band rather than one of its own
+            let bin = ((prediction.probability * CALIBRATION_BINS as f64) as usize)
+                .min(CALIBRATION_BINS - 1);
+            let bin = &mut bins[bin];
+            bin.predicted += prediction.probability;
+            bin.observed += f64::from(u8::from(prediction.achieved));
+            bin.count += 1;
+        }
+        bins.into_iter()
+            .filter(|bin| bin.count > 0)
+            .map(|bin| CalibrationBin {
+                predicted: bin.predicted / bin.count as f64,
+                observed: bin.observed / bin.count as f64,
+                ..bin
+            })
+            .collect()
+    }
+}
+
+/// Returns the Brier score of the given predictions, or zero if there are none
+fn brier_score<'a>(predictions: impl Iterator<Item = &'a Prediction>) -> f64 {
+    let (total, count) = predictions.fold((0.0, 0), |(total, count), prediction| {
+        let outcome = f64::from(u8::from(prediction.achieved));
+        (
+            total + (prediction.probability - outcome).powi(2),
+            count + 1,
+        )
+    });
+    if count == 0 {
+        0.0
+    } else {
+        total / f64::from(count)
+    }
+}
+
+/// One band of predicted chance in a calibration curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationBin {
+    lower: f64,
+    upper: f64,
+    predicted: f64,
+    observed: f64,
+    count: u64,
+}
+
+impl CalibrationBin {
+    /// Returns the lowest predicted chance in the band
+    pub fn lower(&self) -> f64 {
+        self.lower
+    }
+
+    /// Returns the predicted chance the band reaches up to
+    pub fn upper(&self) -> f64 {
+        self.upper
+    }
+
+    /// Returns the mean predicted chance of the predictions in the band
+    pub fn predicted(&self) -> f64 {
+        self.predicted
+    }
+
+    /// Returns the share of the predictions in the band that came true,
+    /// which matches predicted for a well calibrated simulator
+    pub fn observed(&self) -> f64 {
+        self.observed
+    }
+
+    /// Returns the number of predictions in the band
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Several score models evaluated against the same played fixtures, each
+/// drawing from a generator seeded from the same value
+///
+/// Every model is scored on the outcome of each match, by log loss and Brier
+/// score, and on the final table, by how well the order of its expected
+/// finishing positions matches the order the teams really finished in
+pub struct ModelComparison {
+    simulations: u32,
+    seed: u64,
+    models: Vec<(String, Box<dyn ScoreModel>)>,
+}
+
+impl ModelComparison {
+    /// create an empty comparison which samples each match and plays out each
+    /// season the given number of times, from generators seeded with the given seed
+    pub fn new(simulations: u32, seed: u64) -> Self {
+        Self {
+            simulations,
+            seed,
+            models: Vec::new(),
+        }
+    }
+
+    /// Adds a model to the comparison under the given name
+    pub fn with_model<M: ScoreModel + 'static>(mut self, name: &str, model: M) -> Self {
+        self.models.push((name.to_string(), Box::new(model)));
+        self
+    }
+
+    /// Returns the names of the models in the order they were added
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.models.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Scores every model on how well it predicted the given results, played
+    /// from the given table, in the order the models were added
+    ///
+    /// Fails if a result names a team that is not in the table
+    pub fn run(
+        &self,
+        current_table: &LeagueTable,
+        results: &[MatchResult],
+    ) -> std::result::Result<Vec<ModelScore>, TableError> {
+        let mut final_table = current_table.clone();
+        final_table.apply_results(results)?;
+        let final_standings = final_table.standings();
+        let fixtures: Vec<Match> = results
+            .iter()
+            .map(|result| result.fixture().clone())
+            .collect();
+
+        Ok(self
+            .models
+            .iter()
+            .map(|(name, model)| {
+                let mut rng = StdRng::seed_from_u64(self.seed);
+                let (mut log_loss, mut brier_score) = (0.0, 0.0);
+                for result in results {
+                    let markets = MatchMarkets::sampled(
+                        model.as_ref(),
+                        result.fixture(),
+                        self.simulations,
+                        &mut rng,
+                    );
+                    let (home_win, draw, away_win) = markets.outcome_probabilities();
+                    let outcome = match result.home_goals().cmp(&result.away_goals()) {
+                        std::cmp::Ordering::Greater => (1.0, 0.0, 0.0),
+                        std::cmp::Ordering::Equal => (0.0, 1.0, 0.0),
+                        std::cmp::Ordering::Less => (0.0, 0.0, 1.0),
+                    };
+                    let chance = home_win * outcome.0 + draw * outcome.1 + away_win * outcome.2;
+                    log_loss -= chance.max(MIN_PROBABILITY).ln();
+                    brier_score += (home_win - outcome.0).powi(2)
+                        + (draw - outcome.1).powi(2)
+                        + (away_win - outcome.2).powi(2);
+                }
+
+                let mut report = SimulationReport::new(current_table);
+                for _ in 0..self.simulations {
+                    report.record(&simulate_table_with_model(
+                        current_table,
+                        &fixtures,
+                        model.as_ref(),
+                        &mut rng,
+                    ));
+                }
+                let predicted: Vec<usize> = report
+                    .expected_table()
+                    .iter()
+                    .filter_map(|row| final_standings.rank_of(row.name()))
+                    .collect();
+
+                let matches = results.len().max(1) as f64;
+                ModelScore {
+                    name: name.clone(),
+                    log_loss: log_loss / matches,
+                    brier_score: brier_score / matches,
+                    rank_correlation: rank_correlation(&predicted),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Returns Spearman's rank correlation between the positions 1, 2, 3, ... and
+/// the given positions, or zero if there are fewer than two
+fn rank_correlation(positions: &[usize]) -> f64 {
+    let n = positions.len() as f64;
+    if positions.len() < 2 {
+        return 0.0;
+    }
+    let squared_differences: f64 = positions
+        .iter()
+        .zip(1..)
+        .map(|(&position, predicted)| (position as f64 - f64::from(predicted)).powi(2))
+        .sum();
+    1.0 - 6.0 * squared_differences / (n * (n * n - 1.0))
+}
+
+/// How well one model of a ModelComparison predicted the played results
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelScore {
+    name: String,
+    log_loss: f64,
+    brier_score: f64,
+    rank_correlation: f64,
+}
+
+impl ModelScore {
+    /// Returns the name the model was added under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the mean negative log of the chance the model gave each match's
+    /// real outcome, so lower is better
+    pub fn log_loss(&self) -> f64 {
+        self.log_loss
+    }
+
+    /// Returns the mean over every match of the squared differences between the
+    /// model's chances of a home win, draw and away win and what happened, from
+    /// 0 for a perfect model to 2 for one certain of the wrong outcome every time
+    pub fn brier_score(&self) -> f64 {
+        self.brier_score
+    }
+
+    /// Returns Spearman's rank correlation between the order of the model's
+    /// expected finishing positions and the real final table, 1 being the same order
+    pub fn rank_correlation(&self) -> f64 {
+        self.rank_correlation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureList, HistoricalAverageModel};
+
+    /// always predicts a 1-0 home win
+    #[derive(Clone)]
+    struct HomeWinModel;
+
+    impl ScoreModel for HomeWinModel {
+        fn sample_score(&self, _: &Match, _: &mut dyn RngCore) -> (u32, u32) {
+            (1, 0)
+        }
+    }
+
+    /// a double round robin in which the first team named wins every match 2-0
+    /// and every other match is drawn
+    fn dominated_season(teams: &[&str]) -> Vec<MatchResult> {
+        FixtureList::round_robin(teams.iter().copied(), true)
+            .into_iter()
+            .map(|fixture| {
+                let (home_goals, away_goals) = if fixture.home() == teams[0] {
+                    (2, 0)
+                } else if fixture.away() == teams[0] {
+                    (0, 2)
+                } else {
+                    (1, 1)
+                };
+                MatchResult::new(fixture, home_goals, away_goals)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backtest_season() {
+        let teams = ["Arsenal", "Chelsea", "Everton", "Fulham"];
+        let results = dominated_season(&teams);
+        let backtest =
+            Backtest::new(teams, &results).with_config(SimulationConfig::new(200).with_threads(1));
+        assert_eq!(200, backtest.config().simulations());
+        let report = backtest.run(1).unwrap();
+
+        // four teams predicted after each of the first five of six matchdays
+        assert_eq!(20, report.predictions().len());
+        let last = &report.predictions()[16];
+        assert_eq!(
+            (5, "Arsenal", true),
+            (last.matchday(), last.team(), last.achieved())
+        );
+        assert_eq!(1.0, last.probability());
+        assert_eq!(0.0, report.brier_score_at(5));
+        assert!(report.brier_score() < 0.25);
+        assert_eq!(0.0, report.brier_score_at(6));
+
+        let curve = report.calibration();
+        assert_eq!(20, curve.iter().map(CalibrationBin::count).sum::<u64>());
+        for bin in &curve {
+            assert!(bin.lower() <= bin.predicted() && bin.predicted() <= bin.upper());
+            assert!((0.0..=1.0).contains(&bin.observed()));
+        }
+
+        // a model that only ever sees home wins is certain of every prediction
+        let certain = backtest.run_with_model(1, &HomeWinModel).unwrap();
+        assert_eq!(20, certain.predictions().len());
+        assert!(certain
+            .predictions()
+            .iter()
+            .all(|prediction| [0.0, 1.0].contains(&prediction.probability())));
+        assert_ne!(report, certain);
+
+        let stranger = MatchResult::new(Match::from("Arsenal", "Barnet"), 1, 0);
+        assert!(Backtest::new(teams, &[stranger]).run(1).is_err());
+    }
+
+    #[test]
+    fn model_comparison() {
+        let teams = ["Arsenal", "Chelsea", "Everton", "Fulham"];
+        let results = dominated_season(&teams);
+        let start = LeagueTable::from_results(teams, &[]).unwrap();
+        let comparison = ModelComparison::new(200, 3)
+            .with_model("historical", HistoricalAverageModel::new())
+            .with_model("home wins", HomeWinModel);
+        assert_eq!(
+            vec!["historical", "home wins"],
+            comparison.names().collect::<Vec<_>>()
+        );
+
+        let scores = comparison.run(&start, &results).unwrap();
+        assert_eq!(2, scores.len());
+        let (historical, home_wins) = (&scores[0], &scores[1]);
+        assert_eq!("home wins", home_wins.name());
+        // only Arsenal's three home wins of the twelve matches come true
+        assert!((home_wins.brier_score() - 1.5).abs() < 1e-9);
+        assert!((home_wins.log_loss() + 0.75 * MIN_PROBABILITY.ln()).abs() < 1e-9);
+        assert!(historical.brier_score() < home_wins.brier_score());
+        assert!(historical.log_loss() < home_wins.log_loss());
+        for score in &scores {
+            assert!((-1.0..=1.0).contains(&score.rank_correlation()));
+        }
+        assert_eq!(1.0, rank_correlation(&[1, 2, 3]));
+        assert_eq!(-1.0, rank_correlation(&[3, 2, 1]));
+
+        let stranger = MatchResult::new(Match::from("Arsenal", "Barnet"), 1, 0);
+        assert!(comparison.run(&start, &[stranger]).is_err());
+    }
+}
@@ -5,33 +5,139 @@
 //! and reading data in from json files (in place of API calls, for now)
 //! 
 
-use rand::distr::weighted::WeightedIndex;
+pub mod backtest;
+pub mod model;
+pub mod provider;
+
+pub use backtest::{
+    Backtest, BacktestReport, CalibrationBin, ModelComparison, ModelScore, Prediction,
+};
+pub use model::{
+    parse_adjustments_json, parse_odds_json, parse_xg_csv, parse_xg_json, AdjustedModel,
+    BivariatePoissonModel, CongestionModel, EloModel, GoalDistribution, GoalWeights,
+    HistoricalAverageModel, MatchMarkets, MatchOdds, PoissonModel, ScoreModel, StrengthAdjustment,
+    TeamRating, TeamXg, WeightPresets,
+};
+pub use provider::{
+    load_async, ApiFootball, CachedProvider, DataProvider, DataWatcher, FootballData, JsonFiles,
+};
+
+use chrono::{DateTime, Utc};
 use rand::prelude::*;
+use rand::rngs::SmallRng;
+use rayon::prelude::*;
 use relative_path::RelativePath;
-use serde::{Deserialize, Serialize};
-use serde_json::{Result, Value};
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env::current_dir;
+use std::fmt;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter, Read};
+use std::ops::{ControlFlow, Deref, Index};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering as AtomicOrdering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 
-const NUM_POSSIBLE_GOALS: [i32; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
 const HOME_WEIGHTS: [f32; 8] = [18.8, 30.3, 24.8, 14.3, 7.0, 3.1, 1.2, 0.5];
 const AWAY_WEIGHTS: [f32; 8] = [33.8, 36.2, 19.3, 7.4, 2.3, 0.7, 0.2, 0.1];
-const FIXTURES_PATH: &str = "/data/fixtures_list.json";
-const STANDINGS_PATH: &str = "/data/standings.json";
+// the mean of the home and away weights, used for both teams at a neutral venue
+const NEUTRAL_WEIGHTS: [f32; 8] = [26.3, 33.25, 22.05, 10.85, 4.65, 1.9, 0.7, 0.3];
+const MATCH_MINUTES: u32 = 90;
+// the default score model, whose weighted samplers are built on first use and
+// then shared by every simulation rather than rebuilt for each
+static HISTORICAL_MODEL: LazyLock<HistoricalAverageModel> =
+    LazyLock::new(HistoricalAverageModel::new);
+// simulations run between checks of an adaptive batch's estimate
+const CONVERGENCE_ROUND: u32 = 500;
+// the fewest simulations an adaptive batch runs before it may stop, so that a
+// rare outcome which has not come up yet is not taken as settled
+const MIN_CONVERGED_SIMULATIONS: u32 = 2000;
+// the most fixtures enumerate_season will work through, 3^12 being about half a million run-ins
+const MAX_ENUMERATED_FIXTURES: usize = 12;
+// each league keeps its data files in a directory of its own under this one
+const DATA_DIRECTORY: &str = "/data";
+const DEFAULT_LEAGUE: &str = "premier-league";
+const FIXTURES_FILE: &str = "fixtures.json";
+const STANDINGS_FILE: &str = "standings.json";
+const FORMAT_FILE: &str = "format.json";
+const RESULTS_FILE: &str = "results.json";
 
 // Structures for managing data within simulations
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Stores individual team data to be held within the league table structure
+///
+/// Only name, pts, and goal_diff are required when reading standings data;
+/// the remaining record fields default to 0 when absent. A full record may
+/// stand in for the totals it adds up to: goal_diff may be left out when
+/// goals_for and goals_against are given, and games_played when wins, draws
+/// and losses are
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "TeamRecord")]
 pub struct Team {
     name: String,
     pts: u32,
     goal_diff: i32,
+    games_played: u32,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    goals_for: u32,
+    goals_against: u32,
+}
+
+/// A team as written in a standings file, before totals left out are worked
+/// out from the rest of its record
+#[derive(Deserialize)]
+struct TeamRecord {
+    name: String,
+    pts: u32,
+    goal_diff: Option<i32>,
+    games_played: Option<u32>,
+    #[serde(default)]
+    wins: u32,
+    #[serde(default)]
+    draws: u32,
+    #[serde(default)]
+    losses: u32,
+    goals_for: Option<u32>,
+    goals_against: Option<u32>,
+}
+
+impl TryFrom<TeamRecord> for Team {
+    type Error = String;
+
+    fn try_from(record: TeamRecord) -> std::result::Result<Self, Self::Error> {
+        let goals = record.goals_for.zip(record.goals_against);
+        let goal_diff = match (record.goal_diff, goals) {
+            (Some(goal_diff), _) => goal_diff,
+            (None, Some((goals_for, goals_against))) => goals_for as i32 - goals_against as i32,
+            (None, None) => {
+                return Err(format!(
+                    "{:?} needs goal_diff, or goals_for and goals_against",
+                    record.name
+                ))
+            }
+        };
+        Ok(Self {
+            games_played: record
+                .games_played
+                .unwrap_or(record.wins + record.draws + record.losses),
+            name: record.name,
+            pts: record.pts,
+            goal_diff,
+            wins: record.wins,
+            draws: record.draws,
+            losses: record.losses,
+            goals_for: record.goals_for.unwrap_or_default(),
+            goals_against: record.goals_against.unwrap_or_default(),
+        })
+    }
 }
 
 impl Team {
@@ -41,18 +147,179 @@ impl Team {
             name,
             pts,
             goal_diff,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the team's current points total
+    pub fn points(&self) -> u32 {
+        self.pts
+    }
+
+    /// Returns the team's current goal differential
+    pub fn goal_diff(&self) -> i32 {
+        self.goal_diff
+    }
+
+    /// Returns the number of matches the team has played
+    pub fn games_played(&self) -> u32 {
+        self.games_played
+    }
+
+    /// Returns the number of matches the team has won
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    /// Returns the number of matches the team has drawn
+    pub fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    /// Returns the number of matches the team has lost
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    /// Returns the number of goals the team has scored
+    pub fn goals_for(&self) -> u32 {
+        self.goals_for
+    }
+
+    /// Returns the number of goals the team has conceded
+    pub fn goals_against(&self) -> u32 {
+        self.goals_against
+    }
+
+    /// Adds another team's points and record to this team's
+    fn merge(&mut self, other: &Team) {
+        self.pts += other.pts;
+        self.goal_diff += other.goal_diff;
+        self.games_played += other.games_played;
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+        self.goals_for += other.goals_for;
+        self.goals_against += other.goals_against;
+    }
+
+    /// Updates pts and the team's record based on passed match
+    /// outcome data to reflect effect of simulated match on team's
+    /// table standing, awarding points on the 3-1-0 scheme
+    pub fn update(&mut self, goals_for: u32, goals_against: u32) {
+        self.update_with(goals_for, goals_against, &PointsScheme::default());
+    }
+
+    /// Updates pts and the team's record as in update, awarding points
+    /// according to the passed points scheme
+    pub fn update_with(&mut self, goals_for: u32, goals_against: u32, scheme: &PointsScheme) {
+        let mut state = self.state();
+        state.update(goals_for, goals_against, scheme);
+        self.set_state(state);
+    }
+
+    /// Returns the team's points and record without its name
+    fn state(&self) -> TeamState {
+        TeamState {
+            pts: self.pts,
+            goal_diff: self.goal_diff,
+            games_played: self.games_played,
+            wins: self.wins,
+            draws: self.draws,
+            losses: self.losses,
+            goals_for: self.goals_for,
+            goals_against: self.goals_against,
+        }
+    }
+
+    /// Replaces the team's points and record
+    fn set_state(&mut self, state: TeamState) {
+        self.pts = state.pts;
+        self.goal_diff = state.goal_diff;
+        self.games_played = state.games_played;
+        self.wins = state.wins;
+        self.draws = state.draws;
+        self.losses = state.losses;
+        self.goals_for = state.goals_for;
+        self.goals_against = state.goals_against;
+    }
+}
+
+/// A team's points and record without its name, cheap enough to copy that a
+/// simulated season can track every team in a flat list instead of cloning
+/// the whole table
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TeamState {
+    pts: u32,
+    goal_diff: i32,
+    games_played: u32,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    goals_for: u32,
+    goals_against: u32,
+}
+
+impl TeamState {
+    /// Adds one match to the record, awarding points according to the scheme
+    fn update(&mut self, goals_for: u32, goals_against: u32, scheme: &PointsScheme) {
+        self.games_played += 1;
+        self.goals_for += goals_for;
+        self.goals_against += goals_against;
+        self.goal_diff += goals_for as i32 - goals_against as i32;
+        match goals_for.cmp(&goals_against) {
+            Ordering::Equal => {
+                self.draws += 1;
+                self.pts += scheme.draw;
+            }
+            Ordering::Greater => {
+                self.wins += 1;
+                self.pts += scheme.win;
+            }
+            Ordering::Less => {
+                self.losses += 1;
+                self.pts += scheme.loss;
+            }
+        }
+    }
+}
+
+/// Number of points awarded for each match outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PointsScheme {
+    pub win: u32,
+    pub draw: u32,
+    pub loss: u32,
+}
+
+impl Default for PointsScheme {
+    /// Defaults to three points for a win
+    fn default() -> Self {
+        Self::three_for_a_win()
+    }
+}
+
+impl PointsScheme {
+    /// The modern scheme: 3 points for a win, 1 for a draw
+    pub fn three_for_a_win() -> Self {
+        Self {
+            win: 3,
+            draw: 1,
+            loss: 0,
         }
     }
 
-    /// Updates pts based on passed match outcome data
-    /// to reflect effect of simulated match on team's
-    /// table standing
-    pub fn update(&mut self, match_goal_diff: i32) {
-        self.goal_diff += match_goal_diff;
-        match match_goal_diff.cmp(&0) {
-            Ordering::Equal => self.pts += 1,
-            Ordering::Greater => self.pts += 3,
-            Ordering::Less => (),
+    /// The scheme used in England before 1981: 2 points for a win, 1 for a draw
+    pub fn two_for_a_win() -> Self {
+        Self {
+            win: 2,
+            draw: 1,
+            loss: 0,
         }
     }
 }
@@ -63,10 +330,104 @@ impl Team {
 /// simulating the scores as well as how the match goal
 /// differential is passed to the corresponding Team's
 /// update function
-#[derive(Debug, Default, Clone)]
+///
+/// The kickoff time, matchday and venue, the name of the ground, are optional
+/// and are left out of the json when not set, as is the site when the home
+/// team is at home. A match at a neutral ground is written with
+/// "site": "Neutral"
+///
+/// A match played behind closed doors, or before a reduced crowd, carries the
+/// percentage of the usual home advantage the home team keeps, as in
+/// {"home": "Millwall", "away": "Leeds", "home_advantage": 0}, and every score
+/// model scales the home advantage it applies accordingly
+///
+/// A match may also be pinned to a forced result for what-if analysis, in
+/// which case simulations apply that score instead of sampling one, or carry
+/// the live score of a match in progress, in which case simulations only
+/// sample the goals still to come
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Match {
     home: String,
     away: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kickoff: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    matchday: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    venue: Option<String>,
+    #[serde(default, skip_serializing_if = "Site::is_home")]
+    site: Site,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    home_advantage: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    forced_result: Option<(u32, u32)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    live: Option<LiveScore>,
+}
+
+/// The score of a match still being played and how far through it is
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct LiveScore {
+    home_goals: u32,
+    away_goals: u32,
+    minute: u32,
+}
+
+impl LiveScore {
+    /// create a LiveScore for a match standing at the given score after minute minutes
+    pub fn new(home_goals: u32, away_goals: u32, minute: u32) -> Self {
+        Self {
+            home_goals,
+            away_goals,
+            minute,
+        }
+    }
+
+    /// Returns the number of goals scored by the home team so far
+    pub fn home_goals(&self) -> u32 {
+        self.home_goals
+    }
+
+    /// Returns the number of goals scored by the away team so far
+    pub fn away_goals(&self) -> u32 {
+        self.away_goals
+    }
+
+    /// Returns the minutes played so far
+    pub fn minute(&self) -> u32 {
+        self.minute
+    }
+
+    /// Returns the share of the 90 minutes still to be played
+    fn remaining_fraction(&self) -> f64 {
+        f64::from(MATCH_MINUTES.saturating_sub(self.minute)) / f64::from(MATCH_MINUTES)
+    }
+}
+
+/// Whether the home team has home advantage in a match
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Site {
+    /// Played at the home team's ground
+    #[default]
+    Home,
+    /// Played at a ground belonging to neither team, such as a cup final
+    Neutral,
+}
+
+impl Site {
+    /// Returns true if the home team has home advantage
+    pub fn is_home(&self) -> bool {
+        *self == Self::Home
+    }
+
+    /// Returns the share of the usual home advantage the home team keeps at
+    /// this site, given the percentage a match keeps if it is limited
+    fn advantage_share(self, percent: Option<u32>) -> f64 {
+        match self {
+            Self::Home => percent.map_or(1.0, |percent| f64::from(percent.min(100)) / 100.0),
+            Self::Neutral => 0.0,
+        }
+    }
 }
 
 impl Match {
@@ -80,331 +441,9559 @@ impl Match {
         Self {
             home: home.to_string(),
             away: away.to_string(),
+            ..Self::default()
         }
     }
-}
 
-/// Structure for storing current standings as well as 
-/// standings generated through a simulation
-#[derive(Debug, Default, Clone)]
-pub struct LeagueTable(HashMap<String, Team>);
+    /// Sets the time the match kicks off
+    pub fn with_kickoff(mut self, kickoff: DateTime<Utc>) -> Self {
+        self.kickoff = Some(kickoff);
+        self
+    }
 
-impl LeagueTable {
-    /// create an empty LeagueTable
-    pub fn new() -> Self {
-        Self::default()
+    /// Sets the matchday, or round, the match belongs to
+    pub fn with_matchday(mut self, matchday: u32) -> Self {
+        self.matchday = Some(matchday);
+        self
     }
 
-    /// Function to print an ordered league table to stdout
-    /// 
-    /// Used in unit testing
-    pub fn print_table(&self) {
-        println!("Rank\tTeam\t\t\tPoints\t GD");
-        let mut i = 1;
-        let mut print_vector: Vec<&Team> = self.0.values().collect();
-        print_vector.sort_by(|x, y| {
-            y.pts
-                .cmp(&x.pts)
-                .then_with(|| y.goal_diff.cmp(&x.goal_diff))
-        });
-        for team in print_vector {
-            println!(
-                "{}\t{:<10}\t\t{:>5}\t{:>3}",
-                i, team.name, team.pts, team.goal_diff
-            );
-            i += 1;
-        }
+    /// Sets the ground the match is played at
+    pub fn with_venue(mut self, venue: &str) -> Self {
+        self.venue = Some(venue.to_string());
+        self
     }
 
-    /// Function to add to the table using raw data
-    pub fn add_team(&mut self, name: String, pts: u32, goals_diff: i32) {
-        self.0
-            .entry(name.clone())
-            .insert_entry(Team::new(name.clone(), pts, goals_diff));
+    /// Sets whether the home team has home advantage
+    pub fn with_site(mut self, site: Site) -> Self {
+        self.site = site;
+        self
     }
 
-    /// Function to add to the table using an externally instantiated Team struct
-    pub fn add_team_struct(&mut self, name: String, team: Team) {
-        self.0.entry(name.clone()).insert_entry(team);
+    /// Limits the home advantage to the given percentage of the usual, for a
+    /// reduced crowd, capped at 100
+    pub fn with_home_advantage(mut self, percent: u32) -> Self {
+        self.home_advantage = Some(percent.min(100));
+        self
     }
 
-    /// Function to update the data of the designated teams stored within the
-    /// LeagueTable based on simulated match data
-    /// 
-    /// The goal differential is calculated once and passed as is to the home
-    /// team and multiplied by negative 1 to the away team
-    pub fn update(&mut self, latest_match: &Match, home_goals: i32, away_goals: i32) {
-        let goal_diff = home_goals - away_goals;
-        self.0
-            .get_mut(&latest_match.home)
-            .unwrap()
-            .update(goal_diff);
-        self.0
-            .get_mut(&latest_match.away)
-            .unwrap()
-            .update(-goal_diff);
+    /// Removes the home advantage while keeping the match at the home team's
+    /// ground, for a stadium closure or sanction
+    pub fn behind_closed_doors(self) -> Self {
+        self.with_home_advantage(0)
     }
 
-    // could we do this more efficiently?
-    /// Returns the rank achieved in a single simulation by the team
-    /// whose name matches the passed &str
-    pub fn find_final_rank(&mut self, desired_team: &str) -> i32 {
-        let mut i = 1;
-        let mut ordered_vector: Vec<&Team> = self.0.values().collect();
-        ordered_vector.sort_by(|x, y| {
-            y.pts
-                .cmp(&x.pts)
-                .then_with(|| y.goal_diff.cmp(&x.goal_diff))
-        });
-        for team in ordered_vector {
-            if team.name == desired_team {
-                break;
-            } else {
-                i += 1;
-            }
-        }
+    /// Pins the match to a fixed score, skipping random sampling in simulations
+    pub fn with_forced_result(mut self, home_goals: u32, away_goals: u32) -> Self {
+        self.forced_result = Some((home_goals, away_goals));
+        self
+    }
+
+    /// Marks the match as in progress with the given live score
+    pub fn with_live_score(mut self, live: LiveScore) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    /// Returns the name of the home team
+    pub fn home(&self) -> &str {
+        &self.home
+    }
+
+    /// Returns the name of the away team
+    pub fn away(&self) -> &str {
+        &self.away
+    }
+
+    /// Returns the time the match kicks off, if known
+    pub fn kickoff(&self) -> Option<DateTime<Utc>> {
+        self.kickoff
+    }
+
+    /// Returns the matchday the match belongs to, if known
+    pub fn matchday(&self) -> Option<u32> {
+        self.matchday
+    }
+
+    /// Returns the ground the match is played at, if known
+    pub fn venue(&self) -> Option<&str> {
+        self.venue.as_deref()
+    }
+
+    /// Returns whether the home team has home advantage
+    pub fn site(&self) -> Site {
+        self.site
+    }
+
+    /// Returns the share of the usual home advantage the home team has, from
+    /// 0.0 at a neutral ground or behind closed doors up to 1.0
+    pub fn home_advantage_share(&self) -> f64 {
+        self.site.advantage_share(self.home_advantage)
+    }
+
+    /// Returns the home and away goals the match is pinned to, if any
+    pub fn forced_result(&self) -> Option<(u32, u32)> {
+        self.forced_result
+    }
 
-        i
+    /// Returns the live score if the match is in progress
+    pub fn live_score(&self) -> Option<LiveScore> {
+        self.live
     }
 }
 
+/// Stores the final score of a completed match
+///
+/// Serialized as the match's "home" and "away" names alongside
+/// "home_goals" and "away_goals"
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MatchResult {
+    #[serde(flatten)]
+    fixture: Match,
+    home_goals: u32,
+    away_goals: u32,
+}
 
-// Structures for simulation running and data tracking
-//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+impl MatchResult {
+    /// create a MatchResult from a match and its final score
+    pub fn new(fixture: Match, home_goals: u32, away_goals: u32) -> Self {
+        Self {
+            fixture,
+            home_goals,
+            away_goals,
+        }
+    }
 
-/// Simulates outcomes in all matches in the list of matches remaining in the season and 
-/// returns the rank achieved by the target team
-/// 
-/// The weights used in the distribution model for the Monte Carlo simulation 
-/// were calculated based on data from the following source:
-///    <https://fivethirtyeight.com/features/in-126-years-english-football-has-seen-13475-nil-nil-draws/>
-/// itself based on data collected by James Curley: <https://github.com/jalapic/engsoccerdata>
-/// 
-/// This simulation is based on overall historical data on the average number of 
-/// goals scored by home or away teams in the top four tiers of English Football League play.
-/// It does not take into account recent form or historical results between specific teams.
-pub fn run_simulation(
-    target_team: &str,
-    current_table: &LeagueTable,
-    match_list: &Vec<Match>,
-) -> i32 {
-    let mut simulated_table = current_table.clone();
-    let home_dist = WeightedIndex::new(HOME_WEIGHTS).unwrap();
-    let away_dist = WeightedIndex::new(AWAY_WEIGHTS).unwrap();
-    let mut rng = rand::rng();
+    /// Returns the match that was played
+    pub fn fixture(&self) -> &Match {
+        &self.fixture
+    }
 
-    for game in match_list {
-        let home_goals = NUM_POSSIBLE_GOALS[home_dist.sample(&mut rng)];
-        let away_goals = NUM_POSSIBLE_GOALS[away_dist.sample(&mut rng)];
-        simulated_table.update(game, home_goals, away_goals);
+    /// Returns the number of goals scored by the home team
+    pub fn home_goals(&self) -> u32 {
+        self.home_goals
     }
 
-    simulated_table.find_final_rank(target_team)
+    /// Returns the number of goals scored by the away team
+    pub fn away_goals(&self) -> u32 {
+        self.away_goals
+    }
 }
 
-//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Reading in data from files
+/// List of fixtures, kept in the order given
+///
+/// The same pairing of home and away teams may be listed more than once, as in
+/// leagues where teams meet a third time, so repeats are left for
+/// LeagueTable::validate_fixtures to report unless dropped with dedup
+///
+/// Derefs to a slice of matches, so it can be passed anywhere a &[Match] is expected
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct FixtureList(Vec<Match>);
 
-/// Function to read in a list of the remaining fixtures in the Premier League season
-/// from a json file and store the result in a vector
-/// 
-/// Json should take form of an array of objects, each containing two string literals
-/// labeled "home" and "away" as appropriate
-pub fn read_fixtures(fixture_list: &mut Vec<Match>) {
-    let root_dir =
-        current_dir().expect("should only be run in valid directory with appropriate permissions");
-    let fixtures_relative = RelativePath::new(FIXTURES_PATH);
-    let fixtures_full_path = fixtures_relative.to_path(&root_dir);
-    println!("fixtures path: {fixtures_full_path:?}");
-    let file = File::open(fixtures_full_path).expect("file should open if path constant valid");
-    let reader = BufReader::new(file);
-    let fixtures: Result<Value> = serde_json::from_reader(reader);
-    match fixtures {
-        Ok(list) => {
-            for i in 0..379 {
-                let catch = list.get(i);
-                match catch {
-                    None => break,
-                    Some(entry) => {
-                        fixture_list.push(Match::from(
-                            entry["home"].as_str().unwrap(),
-                            entry["away"].as_str().unwrap(),
-                        ));
-                    }
+impl FixtureList {
+    /// create an empty FixtureList
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// create a round robin schedule in which every team meets every other
+    /// team once, or twice with home and away reversed when double is true
+    ///
+    /// Fixtures are numbered by matchday using the circle method, so each team
+    /// plays at most once per matchday, with one team sitting out each matchday
+    /// when the number of teams is odd. A double round robin repeats the first
+    /// half of the season with home and away swapped
+    pub fn round_robin<I, S>(teams: I, double: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut slots: Vec<Option<String>> = teams.into_iter().map(|t| Some(t.into())).collect();
+        if slots.len() % 2 == 1 {
+            slots.push(None);
+        }
+        let num_slots = slots.len();
+        let rounds = num_slots.saturating_sub(1) as u32;
+
+        let mut fixture_list = Self::new();
+        for matchday in 1..=rounds {
+            for i in 0..num_slots / 2 {
+                if let (Some(first), Some(second)) = (&slots[i], &slots[num_slots - 1 - i]) {
+                    // the team fixed in place alternates between home and away
+                    let (home, away) = if i == 0 && matchday % 2 == 0 {
+                        (second, first)
+                    } else {
+                        (first, second)
+                    };
+                    fixture_list.push(Match::from(home, away).with_matchday(matchday));
                 }
             }
+            slots[1..].rotate_right(1);
+        }
+
+        if double {
+            let first_half = fixture_list.0.clone();
+            for fixture in first_half {
+                let matchday = fixture.matchday.unwrap_or_default() + rounds;
+                fixture_list
+                    .push(Match::from(&fixture.away, &fixture.home).with_matchday(matchday));
+            }
         }
-        Err(error) => println!("error reading file: {error:?}"),
+        fixture_list
     }
-}
 
-/// Function to read in the current standings in the Premier League from
-/// a json file and store in a LeagueTable struct
-/// 
-/// Json file should take the form of an array of objects, each of which
-/// must take the form of a Team struct in order to be read
-pub fn read_standings(current_table: &mut LeagueTable) {
-    let root_dir =
-        current_dir().expect("should only be run in valid directory with appropriate permissions");
-    let standings_relative = RelativePath::new(STANDINGS_PATH);
-    let standings_full_path = standings_relative.to_path(&root_dir);
-    println!("standings full path: {standings_full_path:?}");
-    let file = File::open(standings_full_path).expect("file should open if path constant valid");
-    let reader = BufReader::new(file);
-    let standings_data: [Team; 20] =
-        serde_json::from_reader(reader).expect("data should be correctly formatted");
-    for team in standings_data {
-        current_table.add_team_struct(team.name.to_string(), team.clone());
+    /// Adds a fixture to the end of the list
+    pub fn push(&mut self, fixture: Match) {
+        self.0.push(fixture);
     }
-}
 
-//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Unit Tests
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Drops every fixture after the first between the same home and away teams
+    ///
+    /// Returns the number of fixtures dropped
+    pub fn dedup(&mut self) -> usize {
+        let before = self.0.len();
+        let mut seen = HashSet::new();
+        self.0
+            .retain(|fixture| seen.insert((fixture.home.clone(), fixture.away.clone())));
+        before - self.0.len()
+    }
 
-    #[test]
-    fn add_one_team() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        assert_ne!(league_table.0.get("Liverpool"), None);
-        assert_eq!("Liverpool", league_table.0.get("Liverpool").unwrap().name);
+    /// Returns the fixtures scheduled for the given matchday or any later one,
+    /// so a simulation can be run as of the start of that matchday
+    ///
+    /// Fixtures without a matchday are left out
+    pub fn from_matchday(&self, matchday: u32) -> FixtureList {
+        self.filtered(|fixture| fixture.matchday.is_some_and(|day| day >= matchday))
     }
 
-    #[test]
-    fn print_league_table() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        league_table.add_team("Arsenal".to_string(), 27, 28);
-        league_table.print_table();
+    /// Returns the fixtures scheduled for the given matchday or any earlier one,
+    /// so a simulation can project the table as it will stand after that matchday
+    ///
+    /// Fixtures without a matchday are left out
+    pub fn up_to_matchday(&self, matchday: u32) -> FixtureList {
+        self.filtered(|fixture| fixture.matchday.is_some_and(|day| day <= matchday))
     }
 
-    #[test]
-    fn print_reranked_league_table() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        league_table.add_team("Arsenal".to_string(), 27, 28);
-        league_table.print_table();
+    /// Pins the fixture between the given home and away teams to a fixed score,
+    /// so simulations apply it as played rather than sampling a result
+    ///
+    /// Returns false if the list holds no such fixture
+    pub fn force_result(
+        &mut self,
+        home: &str,
+        away: &str,
+        home_goals: u32,
+        away_goals: u32,
+    ) -> bool {
+        match self
+            .0
+            .iter_mut()
+            .find(|fixture| fixture.home == home && fixture.away == away)
+        {
+            Some(fixture) => {
+                fixture.forced_result = Some((home_goals, away_goals));
+                true
+            }
+            None => false,
+        }
+    }
 
-        league_table
+    /// Records the live score of the fixture between the given home and away
+    /// teams, so simulations complete it from that score
+    ///
+    /// Returns false if the list holds no such fixture
+    pub fn set_live_score(&mut self, home: &str, away: &str, live: LiveScore) -> bool {
+        match self
             .0
-            .entry("Arsenal".to_string())
-            .and_modify(|team| team.pts = 70);
-        league_table.print_table();
+            .iter_mut()
+            .find(|fixture| fixture.home == home && fixture.away == away)
+        {
+            Some(fixture) => {
+                fixture.live = Some(live);
+                true
+            }
+            None => false,
+        }
     }
 
-    #[test]
-    fn manually_update_team_data() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        assert_ne!(league_table.0.get("Liverpool"), None);
-        assert_eq!(67, league_table.0.get("Liverpool").unwrap().pts);
-        assert_eq!(40, league_table.0.get("Liverpool").unwrap().goal_diff);
+    /// Removes every forced result, returning the list to fully random simulation
+    pub fn clear_forced_results(&mut self) {
+        for fixture in &mut self.0 {
+            fixture.forced_result = None;
+        }
     }
 
-    #[test]
-    fn update_with_match_data() {
-        let new_match = Match {
-            home: "Liverpool".to_string(),
-            away: "Arsenal".to_string(),
-        };
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        league_table.add_team("Arsenal".to_string(), 27, 26);
-        league_table.update(&new_match, 2, 0);
+    /// Checks every fixture against the teams in a table, as in LeagueTable::validate_fixtures
+    pub fn validate(&self, table: &LeagueTable) -> std::result::Result<(), ValidationError> {
+        table.validate_fixtures(&self.0)
+    }
 
-        assert_eq!(70, league_table.0.get("Liverpool").unwrap().pts);
-        assert_eq!(42, league_table.0.get("Liverpool").unwrap().goal_diff);
+    /// Returns the fixtures the named team plays in, home or away
+    pub fn involving(&self, team: &str) -> FixtureList {
+        self.filtered(|fixture| fixture.home == team || fixture.away == team)
+    }
 
-        assert_eq!(27, league_table.0.get("Arsenal").unwrap().pts);
-        assert_eq!(24, league_table.0.get("Arsenal").unwrap().goal_diff);
+    /// Returns the fixtures scheduled for the given matchday
+    pub fn on_matchday(&self, matchday: u32) -> FixtureList {
+        self.filtered(|fixture| fixture.matchday == Some(matchday))
+    }
 
-        let second_match = Match {
-            home: "Liverpool".to_string(),
-            away: "Arsenal".to_string(),
+    /// Rates how hard each fixture is for both sides, and how hard each team's
+    /// remaining schedule is overall, from the current table
+    ///
+    /// A team's rating is the share of the available points it has taken from
+    /// the games it has played, or one half before it has played any, and the
+    /// difficulty of a fixture for one side is the rating of its opponent.
+    /// Fails if a fixture names a team that is not in the table
+    pub fn difficulty(
+        &self,
+        table: &LeagueTable,
+    ) -> std::result::Result<ScheduleDifficulty, TableError> {
+        let max_points = table.points_scheme.win.max(1);
+        let rating = |name: &str| {
+            let team = table
+                .get_team(name)
+                .ok_or_else(|| TableError::UnknownTeam(name.to_string()))?;
+            Ok(match team.games_played {
+                0 => 0.5,
+                played => f64::from(team.pts) / f64::from(played * max_points),
+            })
         };
-        league_table.update(&second_match, 2, 2);
 
-        assert_eq!(71, league_table.0.get("Liverpool").unwrap().pts);
-        assert_eq!(42, league_table.0.get("Liverpool").unwrap().goal_diff);
+        let mut fixtures = Vec::with_capacity(self.0.len());
+        let mut totals: HashMap<&str, (f64, u32)> = HashMap::new();
+        for fixture in &self.0 {
+            let home = rating(&fixture.away)?;
+            let away = rating(&fixture.home)?;
+            for (team, difficulty) in [(&fixture.home, home), (&fixture.away, away)] {
+                let total = totals.entry(team).or_default();
+                total.0 += difficulty;
+                total.1 += 1;
+            }
+            fixtures.push(FixtureDifficulty {
+                fixture: fixture.clone(),
+                home,
+                away,
+            });
+        }
 
-        assert_eq!(28, league_table.0.get("Arsenal").unwrap().pts);
-        assert_eq!(24, league_table.0.get("Arsenal").unwrap().goal_diff);
+        let mut teams: Vec<(String, f64)> = totals
+            .into_iter()
+            .map(|(team, (total, count))| (team.to_string(), total / f64::from(count)))
+            .collect();
+        teams.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(ScheduleDifficulty { fixtures, teams })
     }
 
-    #[test]
-    fn get_final_ranking() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        league_table.add_team("Arsenal".to_string(), 54, 28);
+    /// Returns the fixtures matching the predicate, in their original order
+    fn filtered(&self, predicate: impl Fn(&Match) -> bool) -> FixtureList {
+        Self(
+            self.0
+                .iter()
+                .filter(|fixture| predicate(fixture))
+                .cloned()
+                .collect(),
+        )
+    }
+}
 
-        let liverpool_rank = league_table.find_final_rank("Liverpool");
-        let arsenal_rank = league_table.find_final_rank("Arsenal");
+/// How hard a single fixture is for each side, from 0 (easiest) to 1 (hardest)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FixtureDifficulty {
+    fixture: Match,
+    home: f64,
+    away: f64,
+}
 
-        assert_eq!(1, liverpool_rank);
-        assert_eq!(2, arsenal_rank);
+impl FixtureDifficulty {
+    /// Returns the fixture being rated
+    pub fn fixture(&self) -> &Match {
+        &self.fixture
     }
 
-    #[test]
-    fn small_simulation() {
-        let mut league_table = LeagueTable::new();
-        league_table.add_team("Liverpool".to_string(), 67, 40);
-        league_table.add_team("Arsenal".to_string(), 54, 28);
-        league_table.add_team("Nottingham Forest".to_string(), 48, 18);
-        league_table.add_team("Manchester City".to_string(), 47, 16);
+    /// Returns how hard the fixture is for the home team
+    pub fn home(&self) -> f64 {
+        self.home
+    }
 
-        let mut matches = vec![
-            Match::from("Liverpool", "Arsenal"),
-            Match::from("Liverpool", "Nottingham Forest"),
-            Match::from("Liverpool", "Manchester City"),
-            Match::from("Arsenal", "Liverpool"),
-            Match::from("Arsenal", "Nottingham Forest"),
-            Match::from("Arsenal", "Manchester City"),
-            Match::from("Nottingham Forest", "Liverpool"),
-            Match::from("Nottingham Forest", "Arsenal"),
-            Match::from("Nottingham Forest", "Manchester City"),
-            Match::from("Manchester City", "Liverpool"),
-            Match::from("Manchester City", "Arsenal"),
-            Match::from("Manchester City", "Nottingham Forest"),
-        ];
+    /// Returns how hard the fixture is for the away team
+    pub fn away(&self) -> f64 {
+        self.away
+    }
+}
 
-        let target = "Arsenal".to_string();
-        let mut count = 0.0;
-        for _x in 1..50 {
-            if run_simulation(&target, &mut league_table, &mut matches) <= 1 {
-                count += 1.0;
-            }
-        }
+/// Difficulty ratings for a list of remaining fixtures, produced by FixtureList::difficulty
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScheduleDifficulty {
+    fixtures: Vec<FixtureDifficulty>,
+    teams: Vec<(String, f64)>,
+}
 
-        println!("{} {}%", target, count / 50.0 * 100.0);
+impl ScheduleDifficulty {
+    /// Returns the rating of every fixture, in the order of the fixture list
+    pub fn fixtures(&self) -> &[FixtureDifficulty] {
+        &self.fixtures
+    }
+
+    /// Returns the mean difficulty of the named team's remaining fixtures,
+    /// or None if the team has none left
+    pub fn strength_of_schedule(&self, team: &str) -> Option<f64> {
+        self.teams
+            .iter()
+            .find(|(name, _)| name == team)
+            .map(|&(_, difficulty)| difficulty)
+    }
+
+    /// Returns every team with fixtures remaining alongside the mean difficulty
+    /// of those fixtures, hardest schedule first
+    pub fn teams(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.teams
+            .iter()
+            .map(|(name, difficulty)| (name.as_str(), *difficulty))
+    }
+}
+
+impl Deref for FixtureList {
+    type Target = [Match];
+
+    fn deref(&self) -> &[Match] {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for FixtureList {
+    /// Reads a list of matches, keeping any repeated fixtures
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(Vec::<Match>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl FromIterator<Match> for FixtureList {
+    /// Collects matches into a list in the order given
+    fn from_iter<I: IntoIterator<Item = Match>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Match> for FixtureList {
+    /// Adds each match to the end of the list in turn
+    fn extend<I: IntoIterator<Item = Match>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for FixtureList {
+    type Item = Match;
+    type IntoIter = std::vec::IntoIter<Match>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FixtureList {
+    type Item = &'a Match;
+    type IntoIter = std::slice::Iter<'a, Match>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A single rule used to separate teams that are level on points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TiebreakRule {
+    /// Higher goal differential ranks first
+    GoalDiff,
+    /// More goals scored ranks first
+    GoalsScored,
+    /// Better record in a mini-table of the matches played between the tied
+    /// teams ranks first, judged on points, then goal differential, then goals scored
+    HeadToHead,
+    /// Team names in alphabetical order, as a last resort
+    Alphabetical,
+}
+
+/// Ordered chain of rules applied in turn to teams level on points
+///
+/// The first rule that separates two teams decides their order; teams still
+/// level after every rule are left in no particular order
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Tiebreakers(Vec<TiebreakRule>);
+
+impl Default for Tiebreakers {
+    /// Defaults to the Premier League rules
+    fn default() -> Self {
+        Self::premier_league()
+    }
+}
+
+impl Tiebreakers {
+    /// create a chain of tiebreakers applied in the order given
+    pub fn new(rules: Vec<TiebreakRule>) -> Self {
+        Self(rules)
+    }
+
+    /// Premier League rules: goal differential, then goals scored
+    pub fn premier_league() -> Self {
+        Self(vec![TiebreakRule::GoalDiff, TiebreakRule::GoalsScored])
+    }
+
+    /// La Liga rules: head-to-head record, then goal differential, then goals scored
+    pub fn la_liga() -> Self {
+        Self(vec![
+            TiebreakRule::HeadToHead,
+            TiebreakRule::GoalDiff,
+            TiebreakRule::GoalsScored,
+        ])
+    }
+
+    /// Returns the rules in the order they are applied
+    pub fn rules(&self) -> &[TiebreakRule] {
+        &self.0
+    }
+
+    /// Returns true if any rule needs results between teams to be recorded
+    fn uses_head_to_head(&self) -> bool {
+        self.0.contains(&TiebreakRule::HeadToHead)
+    }
+}
+
+/// A named band of finishing positions, such as a qualification or relegation spot
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Zone {
+    name: String,
+    first: u32,
+    last: u32,
+}
+
+impl Zone {
+    /// create a zone covering ranks first to last inclusive
+    pub fn new(name: &str, first: u32, last: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            first,
+            last,
+        }
+    }
+
+    /// Returns the name of the zone
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the highest rank in the zone
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    /// Returns the lowest rank in the zone
+    pub fn last(&self) -> u32 {
+        self.last
+    }
+
+    /// Returns true if a team finishing at rank lands in the zone
+    pub fn contains(&self, rank: u32) -> bool {
+        (self.first..=self.last).contains(&rank)
+    }
+}
+
+/// The zones a league divides its table into
+///
+/// Zones may overlap, so the champion can also sit in the
+/// Champions League places. A table has no zones by default
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Zones(Vec<Zone>);
+
+impl Zones {
+    /// create a set of zones, listed in the order given
+    pub fn new(zones: Vec<Zone>) -> Self {
+        Self(zones)
+    }
+
+    /// Premier League zones: champion, Champions League, Europa League and relegation
+    pub fn premier_league() -> Self {
+        Self(vec![
+            Zone::new("Champions", 1, 1),
+            Zone::new("Champions League", 1, 4),
+            Zone::new("Europa League", 5, 5),
+            Zone::new("Relegation", 18, 20),
+        ])
+    }
+
+    /// Zones that fit a league of any size: the champion and, in a league of
+    /// more than three teams, relegation for the bottom three
+    pub fn for_teams(teams: u32) -> Self {
+        let mut zones = vec![Zone::new("Champions", 1, 1)];
+        if teams > 3 {
+            zones.push(Zone::new("Relegation", teams - 2, teams));
+        }
+        Self(zones)
+    }
+
+    /// Returns every zone in the order they were listed
+    pub fn iter(&self) -> impl Iterator<Item = &Zone> {
+        self.0.iter()
+    }
+
+    /// Returns every zone a team finishing at rank lands in
+    pub fn zones_for(&self, rank: u32) -> impl Iterator<Item = &Zone> {
+        self.0.iter().filter(move |zone| zone.contains(rank))
+    }
+
+    /// Returns the zone with the given name
+    pub fn get(&self, name: &str) -> Option<&Zone> {
+        self.0.iter().find(|zone| zone.name == name)
+    }
+}
+
+/// Whether a team's finishing position relative to a target rank is already settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankStatus {
+    /// The team will finish at the target rank or better whatever happens
+    Clinched,
+    /// The team cannot finish at the target rank or better whatever happens
+    Eliminated,
+    /// The team's finish depends on the remaining results
+    Undecided,
+}
+
+/// What a LeagueTable does when asked to add a team it already holds
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Replace the existing team with the new one
+    #[default]
+    Overwrite,
+    /// Leave the existing team alone and return TableError::DuplicateTeam
+    Error,
+    /// Add the new team's points and record to the existing team's
+    Merge,
+}
+
+/// Error returned when a change to a LeagueTable cannot be made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// The team is already in the table
+    DuplicateTeam(String),
+    /// The team is not in the table
+    UnknownTeam(String),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateTeam(team) => write!(f, "{team:?} is already in the table"),
+            Self::UnknownTeam(team) => write!(f, "{team:?} is not in the table"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// Error returned when two SimulationReports cannot be merged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// The reports do not list the same teams in the same order
+    DifferentTeams,
+    /// The reports do not track the same fixtures for the same team and rank
+    DifferentKeyFixtures,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DifferentTeams => write!(f, "the reports are for different teams"),
+            Self::DifferentKeyFixtures => write!(f, "the reports track different key fixtures"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Compact handle for a team registered in a LeagueTable, used in place of
+/// the team's name inside simulation loops
+///
+/// Ids are handed out in the order teams are added and only have meaning for
+/// the table that assigned them and its clones. Removing a team renumbers
+/// every team added after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TeamId(u16);
+
+impl TeamId {
+    /// Returns the position of the team within its table
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A Match with both teams resolved to their ids in a LeagueTable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResolvedMatch {
+    home: TeamId,
+    away: TeamId,
+    site: Site,
+    home_advantage: Option<u32>,
+    forced_result: Option<(u32, u32)>,
+    live: Option<LiveScore>,
+}
+
+impl ResolvedMatch {
+    /// Returns the id of the home team
+    pub fn home(&self) -> TeamId {
+        self.home
+    }
+
+    /// Returns the id of the away team
+    pub fn away(&self) -> TeamId {
+        self.away
+    }
+
+    /// Returns whether the home team has home advantage
+    pub fn site(&self) -> Site {
+        self.site
+    }
+
+    /// Returns the share of the usual home advantage the home team has, as in Match
+    pub fn home_advantage_share(&self) -> f64 {
+        self.site.advantage_share(self.home_advantage)
+    }
+}
+
+/// Structure for storing current standings as well as 
+/// standings generated through a simulation
+///
+/// Teams are stored by TeamId, with names interned in a registry shared
+/// between clones, so a simulated copy of the table only duplicates team records
+#[derive(Debug, Default, Clone)]
+pub struct LeagueTable {
+    teams: Vec<Team>,
+    registry: Arc<HashMap<String, TeamId>>,
+    tiebreakers: Tiebreakers,
+    head_to_head: HashMap<(TeamId, TeamId), Vec<(u32, u32)>>,
+    duplicate_policy: DuplicatePolicy,
+    points_scheme: PointsScheme,
+    zones: Zones,
+}
+
+impl LeagueTable {
+    /// create an empty LeagueTable
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// create an empty LeagueTable which orders teams level on points
+    /// using the provided tiebreakers
+    pub fn with_tiebreakers(tiebreakers: Tiebreakers) -> Self {
+        Self {
+            tiebreakers,
+            ..Self::default()
+        }
+    }
+
+    /// create a LeagueTable from scratch given the teams in the league and
+    /// every match they have completed so far, computing points, goal
+    /// differential and each team's record from the results
+    ///
+    /// Fails if a result names a team that is not in the list of teams
+    pub fn from_results<I, S>(
+        teams: I,
+        results: &[MatchResult],
+    ) -> std::result::Result<Self, TableError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut league_table: Self = teams
+            .into_iter()
+            .map(|name| Team::new(name.into(), 0, 0))
+            .collect();
+        league_table.apply_results(results)?;
+        Ok(league_table)
+    }
+
+    /// Returns the points awarded for each match outcome
+    pub fn points_scheme(&self) -> PointsScheme {
+        self.points_scheme
+    }
+
+    /// Sets the points awarded for each match outcome in results applied from now on
+    pub fn set_points_scheme(&mut self, scheme: PointsScheme) {
+        self.points_scheme = scheme;
+    }
+
+    /// Returns the tiebreakers used to order teams level on points
+    pub fn tiebreakers(&self) -> &Tiebreakers {
+        &self.tiebreakers
+    }
+
+    /// Replaces the tiebreakers used to order teams level on points
+    ///
+    /// Results between teams are only recorded while the tiebreakers
+    /// include TiebreakRule::HeadToHead, so set these before applying results
+    pub fn set_tiebreakers(&mut self, tiebreakers: Tiebreakers) {
+        self.tiebreakers = tiebreakers;
+    }
+
+    /// Returns the zones the table is divided into
+    pub fn zones(&self) -> &Zones {
+        &self.zones
+    }
+
+    /// Replaces the zones the table is divided into
+    pub fn set_zones(&mut self, zones: Zones) {
+        self.zones = zones;
+    }
+
+    /// Returns every zone the team whose name matches the passed &str
+    /// currently sits in, or nothing if the team is not in the table
+    pub fn zones_of(&self, team: &str) -> Vec<&Zone> {
+        match self.team_id(team) {
+            Some(id) => self.zones.zones_for(self.rank_by_id(id)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Function to print an ordered league table to stdout
+    /// 
+    /// Used in unit testing
+    pub fn print_table(&self) {
+        print!("{self}");
+    }
+
+    /// Renders the table in rank order in the requested format
+    pub fn render(&self, format: TableFormat) -> String {
+        self.standings().render(format)
+    }
+
+    /// Returns a snapshot of the table with teams held in rank order
+    pub fn standings(&self) -> Standings {
+        Standings(self.ranked().into_iter().cloned().collect())
+    }
+
+    /// Returns references to all teams sorted by points, then the table's tiebreakers
+    fn ranked(&self) -> Vec<&Team> {
+        self.ranked_ids()
+            .into_iter()
+            .map(|id| &self.teams[id.index()])
+            .collect()
+    }
+
+    /// Returns the ids of all teams sorted by points, then the table's tiebreakers
+    fn ranked_ids(&self) -> Vec<TeamId> {
+        let states: Vec<TeamState> = self.teams.iter().map(Team::state).collect();
+        self.rank_states(&states, &[])
+    }
+
+    /// Orders teams as in ranked_ids, reading each team's record from states
+    /// rather than the table and counting the extra results between teams
+    /// alongside those the table has recorded
+    fn rank_states(&self, states: &[TeamState], extra_results: &[PlayedMatch]) -> Vec<TeamId> {
+        let mut ordered_vector: Vec<TeamId> = (0..states.len()).map(Self::id_at).collect();
+        ordered_vector.sort_by_key(|&id| Reverse(states[id.index()].pts));
+        ordered_vector
+            .chunk_by(|&x, &y| states[x.index()].pts == states[y.index()].pts)
+            .flat_map(|level| {
+                self.break_ties(
+                    states,
+                    extra_results,
+                    level.to_vec(),
+                    self.tiebreakers.rules(),
+                )
+            })
+            .collect()
+    }
+
+    /// Orders a group of teams level on points by applying each rule in turn
+    /// to whichever teams the previous rules left tied
+    fn break_ties(
+        &self,
+        states: &[TeamState],
+        extra_results: &[PlayedMatch],
+        mut group: Vec<TeamId>,
+        rules: &[TiebreakRule],
+    ) -> Vec<TeamId> {
+        let Some((rule, remaining_rules)) = rules.split_first() else {
+            return group;
+        };
+        if group.len() < 2 {
+            return group;
+        }
+
+        let mini_table = match rule {
+            TiebreakRule::HeadToHead => self.mini_table(extra_results, &group),
+            _ => HashMap::new(),
+        };
+        let compare = |x: &TeamId, y: &TeamId| {
+            let (i, j) = (x.index(), y.index());
+            match rule {
+                TiebreakRule::GoalDiff => states[j].goal_diff.cmp(&states[i].goal_diff),
+                TiebreakRule::GoalsScored => states[j].goals_for.cmp(&states[i].goals_for),
+                TiebreakRule::Alphabetical => self.teams[i].name.cmp(&self.teams[j].name),
+                TiebreakRule::HeadToHead => {
+                    let (x, y) = (&mini_table[x], &mini_table[y]);
+                    y.pts
+                        .cmp(&x.pts)
+                        .then_with(|| y.goal_diff.cmp(&x.goal_diff))
+                        .then_with(|| y.goals_for.cmp(&x.goals_for))
+                }
+            }
+        };
+
+        group.sort_by(compare);
+        group
+            .chunk_by(|x, y| compare(x, y).is_eq())
+            .flat_map(|tied| self.break_ties(states, extra_results, tied.to_vec(), remaining_rules))
+            .collect()
+    }
+
+    /// Builds a table from only the recorded and extra results between members of the group
+    fn mini_table(
+        &self,
+        extra_results: &[PlayedMatch],
+        group: &[TeamId],
+    ) -> HashMap<TeamId, TeamState> {
+        let mut mini_table: HashMap<TeamId, TeamState> =
+            group.iter().map(|&id| (id, TeamState::default())).collect();
+        let recorded = self
+            .head_to_head
+            .iter()
+            .flat_map(|(&(home, away), scores)| {
+                scores
+                    .iter()
+                    .map(move |&(home_goals, away_goals)| (home, away, home_goals, away_goals))
+            });
+        for (home, away, home_goals, away_goals) in recorded.chain(extra_results.iter().copied()) {
+            if !mini_table.contains_key(&home) || !mini_table.contains_key(&away) {
+                continue;
+            }
+            if let Some(team) = mini_table.get_mut(&home) {
+                team.update(home_goals, away_goals, &self.points_scheme);
+            }
+            if let Some(team) = mini_table.get_mut(&away) {
+                team.update(away_goals, home_goals, &self.points_scheme);
+            }
+        }
+        mini_table
+    }
+
+    /// Returns the recorded scores of matches in which home hosted away
+    pub fn head_to_head(&self, home: &str, away: &str) -> &[(u32, u32)] {
+        let (Some(home), Some(away)) = (self.team_id(home), self.team_id(away)) else {
+            return &[];
+        };
+        self.head_to_head
+            .get(&(home, away))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the id registered for the team whose name matches the passed &str
+    pub fn team_id(&self, name: &str) -> Option<TeamId> {
+        self.registry.get(name).copied()
+    }
+
+    /// Resolves both teams of a match to their ids, failing if either is not in the table
+    pub fn resolve(&self, fixture: &Match) -> std::result::Result<ResolvedMatch, TableError> {
+        let lookup = |name: &String| {
+            self.team_id(name)
+                .ok_or_else(|| TableError::UnknownTeam(name.clone()))
+        };
+        Ok(ResolvedMatch {
+            home: lookup(&fixture.home)?,
+            away: lookup(&fixture.away)?,
+            site: fixture.site,
+            home_advantage: fixture.home_advantage,
+            forced_result: fixture.forced_result,
+            live: fixture.live,
+        })
+    }
+
+    /// Resolves every match in a list, failing on the first team not in the table
+    pub fn resolve_all(
+        &self,
+        fixtures: &[Match],
+    ) -> std::result::Result<Vec<ResolvedMatch>, TableError> {
+        fixtures
+            .iter()
+            .map(|fixture| self.resolve(fixture))
+            .collect()
+    }
+
+    /// Returns the team whose name matches the passed &str, if present
+    pub fn get_team(&self, name: &str) -> Option<&Team> {
+        self.team_id(name).map(|id| &self.teams[id.index()])
+    }
+
+    /// Returns the team with the given id, if present
+    pub fn get_team_by_id(&self, id: TeamId) -> Option<&Team> {
+        self.teams.get(id.index())
+    }
+
+    /// Returns an iterator over all teams in the table, in the order they were added
+    pub fn teams(&self) -> impl Iterator<Item = &Team> {
+        self.teams.iter()
+    }
+
+    /// Returns the number of teams in the table
+    pub fn len(&self) -> usize {
+        self.teams.len()
+    }
+
+    /// Returns true if no teams have been added to the table
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty()
+    }
+
+    /// Returns the id of the team stored at the given position
+    fn id_at(index: usize) -> TeamId {
+        TeamId(u16::try_from(index).expect("a table should hold at most 65536 teams"))
+    }
+
+    /// Returns what add_team does when a team with the same name is already present
+    pub fn duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicate_policy
+    }
+
+    /// Sets what add_team does when a team with the same name is already present
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.duplicate_policy = policy;
+    }
+
+    /// Function to add to the table using raw data
+    ///
+    /// Fails only if the team is already present and the table's
+    /// duplicate policy is DuplicatePolicy::Error
+    pub fn add_team(
+        &mut self,
+        name: String,
+        pts: u32,
+        goals_diff: i32,
+    ) -> std::result::Result<(), TableError> {
+        let team = Team::new(name.clone(), pts, goals_diff);
+        self.add_team_struct(name, team)
+    }
+
+    /// Function to add to the table using an externally instantiated Team struct
+    ///
+    /// Fails only if the team is already present and the table's
+    /// duplicate policy is DuplicatePolicy::Error
+    pub fn add_team_struct(
+        &mut self,
+        name: String,
+        team: Team,
+    ) -> std::result::Result<(), TableError> {
+        match (self.team_id(&name), self.duplicate_policy) {
+            (None, _) => {
+                let id = Self::id_at(self.teams.len());
+                self.teams.push(team);
+                Arc::make_mut(&mut self.registry).insert(name, id);
+            }
+            (Some(id), DuplicatePolicy::Overwrite) => self.teams[id.index()] = team,
+            (Some(_), DuplicatePolicy::Error) => return Err(TableError::DuplicateTeam(name)),
+            (Some(id), DuplicatePolicy::Merge) => self.teams[id.index()].merge(&team),
+        }
+        Ok(())
+    }
+
+    /// Removes the team whose name matches the passed &str, along with any
+    /// recorded results involving it, returning the removed team
+    ///
+    /// Teams added after the removed team move down one TeamId
+    pub fn remove_team(&mut self, name: &str) -> Option<Team> {
+        let removed = self.team_id(name)?;
+        let shift = |id: TeamId| match id.cmp(&removed) {
+            Ordering::Greater => Some(TeamId(id.0 - 1)),
+            Ordering::Less => Some(id),
+            Ordering::Equal => None,
+        };
+
+        self.registry = Arc::new(
+            self.registry
+                .iter()
+                .filter_map(|(name, &id)| Some((name.clone(), shift(id)?)))
+                .collect(),
+        );
+        self.head_to_head = std::mem::take(&mut self.head_to_head)
+            .into_iter()
+            .filter_map(|((home, away), scores)| Some(((shift(home)?, shift(away)?), scores)))
+            .collect();
+        Some(self.teams.remove(removed.index()))
+    }
+
+    /// Renames a team in the table, in its recorded results, and in every
+    /// fixture of the provided list that refers to it
+    ///
+    /// Fails without changing anything if the old name is not in the table
+    /// or the new name already is
+    pub fn rename_team(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        fixtures: &mut [Match],
+    ) -> std::result::Result<(), TableError> {
+        let id = self
+            .team_id(old_name)
+            .ok_or_else(|| TableError::UnknownTeam(old_name.to_string()))?;
+        if self.team_id(new_name).is_some() {
+            return Err(TableError::DuplicateTeam(new_name.to_string()));
+        }
+        let registry = Arc::make_mut(&mut self.registry);
+        registry.remove(old_name);
+        registry.insert(new_name.to_string(), id);
+        self.teams[id.index()].name = new_name.to_string();
+
+        for fixture in fixtures {
+            for name in [&mut fixture.home, &mut fixture.away] {
+                if name == old_name {
+                    *name = new_name.to_string();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Function to update the data of the designated teams stored within the
+    /// LeagueTable based on simulated match data
+    /// 
+    /// The score is passed as is to the home team and reversed for the away team.
+    /// If the table breaks ties on head-to-head records the score is also
+    /// recorded against the pair of teams
+    pub fn update(&mut self, latest_match: &Match, home_goals: u32, away_goals: u32) {
+        self.apply_result(&MatchResult::new(
+            latest_match.clone(),
+            home_goals,
+            away_goals,
+        ))
+        .expect("both teams should be in the table");
+    }
+
+    /// Updates the table with a single completed match
+    ///
+    /// Fails, leaving the table unchanged, if the result names a team that is not in the table
+    pub fn apply_result(&mut self, result: &MatchResult) -> std::result::Result<(), TableError> {
+        let resolved = self.resolve(&result.fixture)?;
+        self.update_resolved(resolved, result.home_goals, result.away_goals);
+        Ok(())
+    }
+
+    /// Function to update the table based on match data already resolved
+    /// to team ids, avoiding any lookups by name
+    pub fn update_resolved(
+        &mut self,
+        latest_match: ResolvedMatch,
+        home_goals: u32,
+        away_goals: u32,
+    ) {
+        if self.tiebreakers.uses_head_to_head() {
+            self.head_to_head
+                .entry((latest_match.home, latest_match.away))
+                .or_default()
+                .push((home_goals, away_goals));
+        }
+        self.teams[latest_match.home.index()].update_with(
+            home_goals,
+            away_goals,
+            &self.points_scheme,
+        );
+        self.teams[latest_match.away.index()].update_with(
+            away_goals,
+            home_goals,
+            &self.points_scheme,
+        );
+    }
+
+    /// Updates the table with a list of completed matches
+    ///
+    /// Every result is checked before any is applied, so the table is left
+    /// unchanged if a result names a team that is not in the table
+    pub fn apply_results(
+        &mut self,
+        results: &[MatchResult],
+    ) -> std::result::Result<(), TableError> {
+        let resolved = results
+            .iter()
+            .map(|result| self.resolve(&result.fixture))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        for (result, fixture) in results.iter().zip(resolved) {
+            self.update_resolved(fixture, result.home_goals, result.away_goals);
+        }
+        Ok(())
+    }
+
+    /// Checks a list of remaining fixtures against the teams in the table
+    /// before any simulation is run
+    ///
+    /// Reports every fixture naming a team missing from the table, every fixture
+    /// listed more than once or pitting a team against itself, and every team
+    /// with more fixtures remaining than a double round robin season allows
+    pub fn validate_fixtures(
+        &self,
+        fixtures: &[Match],
+    ) -> std::result::Result<(), ValidationError> {
+        let mut issues = Vec::new();
+        let mut seen = HashSet::new();
+        let mut unknown = HashSet::new();
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+
+        for fixture in fixtures {
+            for team in [&fixture.home, &fixture.away] {
+                if self.team_id(team).is_none() && unknown.insert(team) {
+                    issues.push(FixtureIssue::UnknownTeam(team.clone()));
+                }
+                *remaining.entry(team).or_default() += 1;
+            }
+            if fixture.home == fixture.away {
+                issues.push(FixtureIssue::SameTeam(fixture.clone()));
+            } else if !seen.insert((&fixture.home, &fixture.away)) {
+                issues.push(FixtureIssue::DuplicateFixture(fixture.clone()));
+            }
+        }
+
+        let season_length = self.matches_per_season() as usize;
+        let mut teams: Vec<&Team> = self.teams.iter().collect();
+        teams.sort_by(|x, y| x.name.cmp(&y.name));
+        for team in teams {
+            let count = remaining.get(team.name()).copied().unwrap_or(0);
+            let max = season_length.saturating_sub(team.games_played as usize);
+            if count > max {
+                issues.push(FixtureIssue::TooManyFixtures {
+                    team: team.name.clone(),
+                    remaining: count,
+                    max,
+                });
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { issues })
+        }
+    }
+
+    /// Returns the number of matches each team plays in a double round robin
+    /// season between the teams in the table
+    pub fn matches_per_season(&self) -> u32 {
+        2 * (self.teams.len() as u32).saturating_sub(1)
+    }
+
+    /// Checks that every team's games played plus its remaining fixtures add
+    /// up to the length of the season, catching fixture lists with missing
+    /// or duplicated games
+    pub fn check_schedule(
+        &self,
+        fixtures: &[Match],
+        matches_per_season: u32,
+    ) -> std::result::Result<(), ValidationError> {
+        self.check_schedule_by(fixtures, |_| matches_per_season)
+    }
+
+    /// Checks the schedule as in check_schedule, against a season whose length
+    /// can differ from team to team
+    fn check_schedule_by(
+        &self,
+        fixtures: &[Match],
+        matches_per_season: impl Fn(&Team) -> u32,
+    ) -> std::result::Result<(), ValidationError> {
+        let mut remaining: HashMap<&str, u32> = HashMap::new();
+        for fixture in fixtures {
+            *remaining.entry(&fixture.home).or_default() += 1;
+            *remaining.entry(&fixture.away).or_default() += 1;
+        }
+
+        let mut teams: Vec<&Team> = self.teams.iter().collect();
+        teams.sort_by(|x, y| x.name.cmp(&y.name));
+        let issues: Vec<FixtureIssue> = teams
+            .into_iter()
+            .filter_map(|team| {
+                let count = remaining.get(team.name()).copied().unwrap_or(0);
+                let expected = matches_per_season(team);
+                (team.games_played + count != expected).then(|| FixtureIssue::ScheduleMismatch {
+                    team: team.name.clone(),
+                    played: team.games_played,
+                    remaining: count,
+                    expected,
+                })
+            })
+            .collect();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { issues })
+        }
+    }
+
+    /// Checks that across the matches already played and those remaining every
+    /// team meets every other team exactly once at home and once away
+    ///
+    /// Reports each home and away pairing that never appears or appears more
+    /// than once, as well as any team named that is not in the table
+    pub fn check_completeness(
+        &self,
+        played: &[MatchResult],
+        remaining: &[Match],
+    ) -> std::result::Result<(), ValidationError> {
+        let mut issues = Vec::new();
+        let mut unknown = HashSet::new();
+        let mut pairings: HashMap<(&str, &str), u32> = HashMap::new();
+        let fixtures = played.iter().map(|result| &result.fixture).chain(remaining);
+        for fixture in fixtures {
+            for team in [&fixture.home, &fixture.away] {
+                if self.team_id(team).is_none() && unknown.insert(team) {
+                    issues.push(FixtureIssue::UnknownTeam(team.clone()));
+                }
+            }
+            *pairings.entry((&fixture.home, &fixture.away)).or_default() += 1;
+        }
+
+        let mut names: Vec<&str> = self.teams.iter().map(|team| team.name()).collect();
+        names.sort();
+        for &home in &names {
+            for &away in names.iter().filter(|&&away| away != home) {
+                let pairing = || Match::from(home, away);
+                match pairings.get(&(home, away)).copied().unwrap_or(0) {
+                    0 => issues.push(FixtureIssue::MissingPairing(pairing())),
+                    1 => {}
+                    count => issues.push(FixtureIssue::RepeatedPairing {
+                        fixture: pairing(),
+                        count,
+                    }),
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { issues })
+        }
+    }
+
+    /// Returns the rank of every team in the table, computed with a single sort
+    pub fn ranks(&self) -> HashMap<String, u32> {
+        self.ranked()
+            .into_iter()
+            .zip(1..)
+            .map(|(team, rank)| (team.name.clone(), rank))
+            .collect()
+    }
+
+    /// Compares this table with another holding the same teams, typically the
+    /// current table against a simulated final table, reporting how each
+    /// team's points, goal differential and rank change from self to other
+    ///
+    /// Teams missing from either table are left out of the diff
+    pub fn diff(&self, other: &LeagueTable) -> TableDiff {
+        let before = self.ranks();
+        let changes = other
+            .ranked()
+            .into_iter()
+            .zip(1..)
+            .filter_map(|(after, to_rank)| {
+                let from = self.get_team(&after.name)?;
+                Some(TeamChange {
+                    name: after.name.clone(),
+                    points_delta: after.pts as i32 - from.pts as i32,
+                    goal_diff_delta: after.goal_diff - from.goal_diff,
+                    from_rank: before[&after.name],
+                    to_rank,
+                })
+            })
+            .collect();
+        TableDiff(changes)
+    }
+
+    /// Returns whether the named team is already certain to finish at the given
+    /// rank or better, already unable to, or neither, whatever happens in the
+    /// remaining fixtures
+    ///
+    /// Only points are considered: a team that could finish level on points
+    /// with a rival is not treated as certain to finish on either side of it.
+    /// Fixtures between rivals are treated independently, so a position that is
+    /// only settled by how such fixtures interact may be reported as Undecided,
+    /// but Clinched and Eliminated are never wrong
+    pub fn rank_status(
+        &self,
+        team: &str,
+        rank: u32,
+        remaining: &[Match],
+    ) -> std::result::Result<RankStatus, TableError> {
+        let target = self
+            .team_id(team)
+            .ok_or_else(|| TableError::UnknownTeam(team.to_string()))?;
+        let scheme = self.points_scheme;
+        let worst = scheme.win.min(scheme.draw).min(scheme.loss);
+        let best = scheme.win.max(scheme.draw).max(scheme.loss);
+
+        // the fewest and most points each team can finish on
+        let mut bounds: Vec<(u32, u32)> =
+            self.teams.iter().map(|team| (team.pts, team.pts)).collect();
+        for game in self.resolve_all(remaining)? {
+            let (home, away) = match game.forced_result {
+                Some((home_goals, away_goals)) => {
+                    let points = |scored: u32, conceded: u32| match scored.cmp(&conceded) {
+                        Ordering::Greater => scheme.win,
+                        Ordering::Equal => scheme.draw,
+                        Ordering::Less => scheme.loss,
+                    };
+                    let home = points(home_goals, away_goals);
+                    let away = points(away_goals, home_goals);
+                    ((home, home), (away, away))
+                }
+                None => ((worst, best), (worst, best)),
+            };
+            let bound = &mut bounds[game.home.index()];
+            *bound = (bound.0 + home.0, bound.1 + home.1);
+            let bound = &mut bounds[game.away.index()];
+            *bound = (bound.0 + away.0, bound.1 + away.1);
+        }
+
+        let (fewest, most) = bounds[target.index()];
+        let rivals = bounds
+            .iter()
+            .enumerate()
+            .filter(|&(id, _)| id != target.index());
+        let could_finish_above = rivals
+            .clone()
+            .filter(|(_, bound)| bound.1 >= fewest)
+            .count();
+        let certain_above = rivals.filter(|(_, bound)| bound.0 > most).count();
+        Ok(if (could_finish_above as u32) < rank {
+            RankStatus::Clinched
+        } else if certain_above as u32 >= rank {
+            RankStatus::Eliminated
+        } else {
+            RankStatus::Undecided
+        })
+    }
+
+    // could we do this more efficiently?
+    /// Returns the rank achieved in a single simulation by the team
+    /// whose name matches the passed &str
+    pub fn find_final_rank(&self, desired_team: &str) -> i32 {
+        match self.team_id(desired_team) {
+            Some(id) => self.rank_by_id(id) as i32,
+            None => self.teams.len() as i32 + 1,
+        }
+    }
+
+    /// Returns the rank of the team with the given id
+    pub fn rank_by_id(&self, team: TeamId) -> u32 {
+        let ordered_vector = self.ranked_ids();
+        let position = ordered_vector
+            .iter()
+            .position(|&id| id == team)
+            .unwrap_or(ordered_vector.len());
+
+        position as u32 + 1
+    }
+}
+
+impl fmt::Display for LeagueTable {
+    /// Writes the table in rank order as plain text
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.standings(), f)
+    }
+}
+
+impl Serialize for LeagueTable {
+    /// Serializes the table as an array of teams in rank order, matching
+    /// the layout of the standings json file
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.ranked())
+    }
+}
+
+impl<'de> Deserialize<'de> for LeagueTable {
+    /// Deserializes an array of teams into a table with default settings
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::<Team>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+impl FromIterator<Team> for LeagueTable {
+    /// Builds a table with default settings, keeping the last of any teams sharing a name
+    fn from_iter<I: IntoIterator<Item = Team>>(iter: I) -> Self {
+        let mut league_table = Self::new();
+        league_table.extend(iter);
+        league_table
+    }
+}
+
+impl Extend<Team> for LeagueTable {
+    /// Adds each team under its own name following the table's duplicate policy,
+    /// except that DuplicatePolicy::Error keeps the existing team rather than failing
+    fn extend<I: IntoIterator<Item = Team>>(&mut self, iter: I) {
+        for team in iter {
+            let _ = self.add_team_struct(team.name.clone(), team);
+        }
+    }
+}
+
+impl IntoIterator for LeagueTable {
+    type Item = Team;
+    type IntoIter = std::vec::IntoIter<Team>;
+
+    /// Consumes the table, yielding its teams in the order they were added
+    fn into_iter(self) -> Self::IntoIter {
+        self.teams.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LeagueTable {
+    type Item = &'a Team;
+    type IntoIter = std::slice::Iter<'a, Team>;
+
+    /// Yields the table's teams in the order they were added
+    fn into_iter(self) -> Self::IntoIter {
+        self.teams.iter()
+    }
+}
+
+/// Ordered view of a LeagueTable, with teams held in rank order
+///
+/// Indexing is by rank, so `standings[1]` is the team at the top of the table
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Standings(Vec<Team>);
+
+impl Standings {
+    /// Returns the team at the given rank (starting from 1), if there is one
+    pub fn get(&self, rank: usize) -> Option<&Team> {
+        rank.checked_sub(1).and_then(|i| self.0.get(i))
+    }
+
+    /// Returns the rank of the team whose name matches the passed &str
+    pub fn rank_of(&self, name: &str) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|team| team.name == name)
+            .map(|i| i + 1)
+    }
+
+    /// Returns the team whose name matches the passed &str, if present
+    pub fn find(&self, name: &str) -> Option<&Team> {
+        self.0.iter().find(|team| team.name == name)
+    }
+
+    /// Returns an iterator over the teams from first to last
+    pub fn iter(&self) -> impl Iterator<Item = &Team> {
+        self.0.iter()
+    }
+
+    /// Returns the number of teams in the standings
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the standings hold no teams
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Index<usize> for Standings {
+    type Output = Team;
+
+    /// Returns the team at the given rank, panicking if rank is 0 or past the last place
+    fn index(&self, rank: usize) -> &Team {
+        self.get(rank)
+            .expect("rank should be between 1 and the number of teams")
+    }
+}
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(TableFormat::Plain))
+    }
+}
+
+/// Output formats supported when rendering a table
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Space separated columns padded to line up, for terminals and logs
+    #[default]
+    Plain,
+    /// A GitHub flavored Markdown table
+    Markdown,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+const TABLE_COLUMNS: [&str; 10] = ["Rank", "Team", "P", "W", "D", "L", "GF", "GA", "GD", "Pts"];
+
+impl Standings {
+    /// Renders the standings as text in the requested format, one row per team
+    /// beneath a header row
+    ///
+    /// Plain and Markdown output pad every column to its widest entry so columns
+    /// stay aligned however long the team names are
+    pub fn render(&self, format: TableFormat) -> String {
+        let mut rows = vec![TABLE_COLUMNS.map(String::from)];
+        for (i, team) in self.0.iter().enumerate() {
+            rows.push([
+                (i + 1).to_string(),
+                team.name.clone(),
+                team.games_played.to_string(),
+                team.wins.to_string(),
+                team.draws.to_string(),
+                team.losses.to_string(),
+                team.goals_for.to_string(),
+                team.goals_against.to_string(),
+                team.goal_diff.to_string(),
+                team.pts.to_string(),
+            ]);
+        }
+
+        if format == TableFormat::Csv {
+            return rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| csv_field(cell))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                        + "\n"
+                })
+                .collect();
+        }
+
+        let mut widths = [0; TABLE_COLUMNS.len()];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        // team names read left to right, numbers line up on the right
+        let pad = |column: usize, cell: &str| match column {
+            1 => format!("{cell:<width$}", width = widths[column]),
+            _ => format!("{cell:>width$}", width = widths[column]),
+        };
+        let padded_rows = rows.iter().map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(column, cell)| pad(column, cell))
+                .collect::<Vec<_>>()
+        });
+
+        let mut rendered = String::new();
+        match format {
+            TableFormat::Plain => {
+                for row in padded_rows {
+                    rendered += row.join("  ").trim_end();
+                    rendered.push('\n');
+                }
+            }
+            TableFormat::Markdown => {
+                for (i, row) in padded_rows.enumerate() {
+                    rendered += &format!("| {} |\n", row.join(" | "));
+                    if i == 0 {
+                        let rule = widths
+                            .iter()
+                            .enumerate()
+                            .map(|(column, &width)| match column {
+                                1 => format!(":{}", "-".repeat(width.max(2) - 1)),
+                                _ => format!("{}:", "-".repeat(width.max(2) - 1)),
+                            })
+                            .collect::<Vec<_>>();
+                        rendered += &format!("| {} |\n", rule.join(" | "));
+                    }
+                }
+            }
+            TableFormat::Csv => unreachable!("csv output is returned before padding"),
+        }
+        rendered
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote, or line break
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Change in a single team's standing between two tables
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TeamChange {
+    name: String,
+    points_delta: i32,
+    goal_diff_delta: i32,
+    from_rank: u32,
+    to_rank: u32,
+}
+
+impl TeamChange {
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the points gained between the two tables
+    pub fn points_delta(&self) -> i32 {
+        self.points_delta
+    }
+
+    /// Returns the change in goal differential between the two tables
+    pub fn goal_diff_delta(&self) -> i32 {
+        self.goal_diff_delta
+    }
+
+    /// Returns the team's rank in the first table
+    pub fn from_rank(&self) -> u32 {
+        self.from_rank
+    }
+
+    /// Returns the team's rank in the second table
+    pub fn to_rank(&self) -> u32 {
+        self.to_rank
+    }
+
+    /// Returns the number of places climbed, negative if the team dropped
+    pub fn positions_gained(&self) -> i32 {
+        self.from_rank as i32 - self.to_rank as i32
+    }
+}
+
+impl fmt::Display for TeamChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let movement = match self.positions_gained().cmp(&0) {
+            Ordering::Greater => "climbs from",
+            Ordering::Less => "drops from",
+            Ordering::Equal => return write!(f, "{} stays {}", self.name, ordinal(self.to_rank)),
+        };
+        write!(
+            f,
+            "{} {} {} to {}",
+            self.name,
+            movement,
+            ordinal(self.from_rank),
+            ordinal(self.to_rank)
+        )
+    }
+}
+
+/// Report of how every team's standing changes between two tables,
+/// held in the rank order of the second table
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct TableDiff(Vec<TeamChange>);
+
+impl TableDiff {
+    /// Returns the change for the team whose name matches the passed &str
+    pub fn get(&self, name: &str) -> Option<&TeamChange> {
+        self.0.iter().find(|change| change.name == name)
+    }
+
+    /// Returns an iterator over every team's change in the second table's rank order
+    pub fn iter(&self) -> impl Iterator<Item = &TeamChange> {
+        self.0.iter()
+    }
+}
+
+/// Formats a rank as an English ordinal, e.g. 1st, 2nd, 11th, 23rd
+pub fn ordinal(rank: u32) -> String {
+    let suffix = match (rank % 10, rank % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{rank}{suffix}")
+}
+
+/// A single problem found when checking fixtures against a LeagueTable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureIssue {
+    /// A fixture names a team that is not in the table
+    UnknownTeam(String),
+    /// The same home and away pairing is listed more than once
+    DuplicateFixture(Match),
+    /// A fixture has the same team at home and away
+    SameTeam(Match),
+    /// A team has more fixtures remaining than it can still play
+    TooManyFixtures {
+        team: String,
+        remaining: usize,
+        max: usize,
+    },
+    /// A team's games played and remaining fixtures do not make up a full season
+    ScheduleMismatch {
+        team: String,
+        played: u32,
+        remaining: u32,
+        expected: u32,
+    },
+    /// A home and away pairing is neither played nor remaining
+    MissingPairing(Match),
+    /// A home and away pairing appears more than once across played and remaining matches
+    RepeatedPairing { fixture: Match, count: u32 },
+}
+
+impl fmt::Display for FixtureIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTeam(team) => write!(f, "unknown team {team:?}"),
+            Self::DuplicateFixture(fixture) => write!(
+                f,
+                "duplicate fixture {:?} v {:?}",
+                fixture.home, fixture.away
+            ),
+            Self::SameTeam(fixture) => write!(f, "{:?} cannot play itself", fixture.home),
+            Self::TooManyFixtures {
+                team,
+                remaining,
+                max,
+            } => write!(
+                f,
+                "{team:?} has {remaining} fixtures remaining but can play at most {max}"
+            ),
+            Self::ScheduleMismatch {
+                team,
+                played,
+                remaining,
+                expected,
+            } => write!(
+                f,
+                "{team:?} has played {played} with {remaining} remaining, expected {expected} in total"
+            ),
+            Self::MissingPairing(fixture) => write!(
+                f,
+                "{:?} v {:?} is neither played nor remaining",
+                fixture.home, fixture.away
+            ),
+            Self::RepeatedPairing { fixture, count } => write!(
+                f,
+                "{:?} v {:?} appears {count} times",
+                fixture.home, fixture.away
+            ),
+        }
+    }
+}
+
+/// Error returned when fixtures fail validation against a LeagueTable,
+/// holding every issue found rather than just the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    issues: Vec<FixtureIssue>,
+}
+
+impl ValidationError {
+    /// Returns every issue found, in the order they were detected
+    pub fn issues(&self) -> &[FixtureIssue] {
+        &self.issues
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid fixtures: ")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// Structures for simulation running and data tracking
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// How the target team finished a single simulated season
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimOutcome {
+    final_rank: u32,
+    points: u32,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    goal_diff: i32,
+}
+
+impl SimOutcome {
+    /// create the outcome for the named team from a finished season; a team not
+    /// in the table finishes below every team that is, with an empty record
+    fn new(season: &SeasonState, target_team: &str) -> Self {
+        let Some(id) = season.table.team_id(target_team) else {
+            return Self {
+                final_rank: season.teams.len() as u32 + 1,
+                ..Self::default()
+            };
+        };
+        let team = &season.teams[id.index()];
+        Self {
+            final_rank: season.rank_by_id(id),
+            points: team.pts,
+            wins: team.wins,
+            draws: team.draws,
+            losses: team.losses,
+            goal_diff: team.goal_diff,
+        }
+    }
+
+    /// Returns the team's finishing position, 1 being top of the table
+    pub fn final_rank(&self) -> u32 {
+        self.final_rank
+    }
+
+    /// Returns the team's final points total
+    pub fn points(&self) -> u32 {
+        self.points
+    }
+
+    /// Returns the number of matches the team won over the whole season
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    /// Returns the number of matches the team drew over the whole season
+    pub fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    /// Returns the number of matches the team lost over the whole season
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    /// Returns the team's final goal differential
+    pub fn goal_diff(&self) -> i32 {
+        self.goal_diff
+    }
+}
+
+/// Returns the shared HistoricalAverageModel used whenever no other model is given
+fn historical_model() -> &'static HistoricalAverageModel {
+    &HISTORICAL_MODEL
+}
+
+/// Simulates outcomes in all matches in the list of matches remaining in the season and 
+/// returns how the target team finished
+/// 
+/// Scores are sampled with the HistoricalAverageModel, which is based on overall
+/// historical data on the average number of goals scored by home or away teams in the
+/// top four tiers of English Football League play. It does not take into account
+/// recent form or historical results between specific teams; use
+/// run_simulation_with_model to simulate with another ScoreModel
+///
+/// Random numbers are drawn from the given generator, so a seeded generator
+/// reproduces the same simulation and a caller running many simulations can
+/// reuse one generator for all of them, e.g. `&mut rand::rng()`
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn run_simulation<R: Rng + ?Sized>(
+    target_team: &str,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    rng: &mut R,
+) -> SimOutcome {
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let model = historical_model();
+    let (season, _) = play_season(current_table, &fixtures, rng, |i, rng| {
+        model.sample_at(fixtures[i].home_advantage_share(), rng)
+    });
+    SimOutcome::new(&season, target_team)
+}
+
+/// Runs a simulation as in run_simulation, sampling every score from the given
+/// model and drawing random numbers from the given generator
+pub fn run_simulation_with_model<M, R>(
+    target_team: &str,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    model: &M,
+    rng: &mut R,
+) -> SimOutcome
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let season = simulate_season_with_model(current_table, &fixtures, match_list, model, rng);
+    SimOutcome::new(&season, target_team)
+}
+
+/// Runs a simulation as in run_simulation, with the target team and fixtures
+/// already resolved to ids so that repeated simulations can skip name lookups
+pub fn run_resolved_simulation(
+    target_team: TeamId,
+    current_table: &LeagueTable,
+    fixtures: &[ResolvedMatch],
+) -> u32 {
+    run_resolved_simulation_with_rng(target_team, current_table, fixtures, &mut rand::rng())
+}
+
+/// Runs a simulation as in run_resolved_simulation, drawing random numbers from the given generator
+pub fn run_resolved_simulation_with_rng<R: Rng + ?Sized>(
+    target_team: TeamId,
+    current_table: &LeagueTable,
+    fixtures: &[ResolvedMatch],
+    rng: &mut R,
+) -> u32 {
+    let model = historical_model();
+    play_season(current_table, fixtures, rng, |i, rng| {
+        model.sample_at(fixtures[i].home_advantage_share(), rng)
+    })
+    .0
+    .rank_by_id(target_team)
+}
+
+/// Simulates outcomes in all matches in the list of matches remaining in the season,
+/// as in run_simulation, and returns the final simulated table so that every
+/// team's finishing position, points and goal difference can be inspected
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn simulate_table(current_table: &LeagueTable, match_list: &[Match]) -> LeagueTable {
+    simulate_table_with_rng(current_table, match_list, &mut rand::rng())
+}
+
+/// Simulates the final table as in simulate_table, drawing random numbers from the given generator
+pub fn simulate_table_with_rng<R: Rng + ?Sized>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    rng: &mut R,
+) -> LeagueTable {
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let model = historical_model();
+    simulate_season(current_table, &fixtures, rng, |i, rng| {
+        model.sample_at(fixtures[i].home_advantage_share(), rng)
+    })
+}
+
+/// Simulates the final table as in simulate_table, sampling every score from
+/// the given model and drawing random numbers from the given generator
+pub fn simulate_table_with_model<M, R>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    model: &M,
+    rng: &mut R,
+) -> LeagueTable
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    simulate_season_with_model(current_table, &fixtures, match_list, model, rng).into_table()
+}
+
+/// Simulates a score for every match in the list without applying them to
+/// any table, returning one result per match in the order given
+pub fn simulate_results(match_list: &[Match]) -> Vec<MatchResult> {
+    simulate_results_with_model(match_list, historical_model(), &mut rand::rng())
+}
+
+/// Simulates scores as in simulate_results, drawing random numbers from the given generator
+pub fn simulate_results_with_rng<R: Rng>(match_list: &[Match], rng: &mut R) -> Vec<MatchResult> {
+    simulate_results_with_model(match_list, historical_model(), rng)
+}
+
+/// Simulates scores as in simulate_results, sampling every score from the given
+/// model and drawing random numbers from the given generator
+pub fn simulate_results_with_model<M, R>(
+    match_list: &[Match],
+    model: &M,
+    rng: &mut R,
+) -> Vec<MatchResult>
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    model.start_season();
+    match_list
+        .iter()
+        .map(|game| {
+            let (home_goals, away_goals) = play_match(game, model, rng);
+            model.record_result(game, home_goals, away_goals);
+            MatchResult::new(game.clone(), home_goals, away_goals)
+        })
+        .collect()
+}
+
+// a simulated result between two teams, as home, away, home goals and away goals
+type PlayedMatch = (TeamId, TeamId, u32, u32);
+
+/// A table part way through a simulated season
+///
+/// Names, rules and results recorded before the season began are read from
+/// the starting table, and only a flat list of every team's record is copied,
+/// so playing a season allocates one small list instead of cloning the table.
+/// A LeagueTable is only built if into_table asks for one
+#[derive(Debug, Clone)]
+struct SeasonState<'a> {
+    table: &'a LeagueTable,
+    teams: Vec<TeamState>,
+    // results played so far, kept only when ties are broken head to head
+    results: Vec<PlayedMatch>,
+}
+
+impl<'a> SeasonState<'a> {
+    /// create a season starting from the given table
+    fn new(table: &'a LeagueTable) -> Self {
+        Self {
+            table,
+            teams: table.teams.iter().map(Team::state).collect(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Applies a match result, as in LeagueTable::update_resolved
+    fn update(&mut self, game: ResolvedMatch, home_goals: u32, away_goals: u32) {
+        let scheme = &self.table.points_scheme;
+        if self.table.tiebreakers.uses_head_to_head() {
+            self.results
+                .push((game.home, game.away, home_goals, away_goals));
+        }
+        self.teams[game.home.index()].update(home_goals, away_goals, scheme);
+        self.teams[game.away.index()].update(away_goals, home_goals, scheme);
+    }
+
+    /// Returns the ids of all teams in finishing order, as in LeagueTable::ranked_ids
+    fn ranked_ids(&self) -> Vec<TeamId> {
+        self.table.rank_states(&self.teams, &self.results)
+    }
+
+    /// Returns the team's position, as in LeagueTable::rank_by_id
+    fn rank_by_id(&self, team: TeamId) -> u32 {
+        let ordered_vector = self.ranked_ids();
+        let position = ordered_vector
+            .iter()
+            .position(|&id| id == team)
+            .unwrap_or(ordered_vector.len());
+        position as u32 + 1
+    }
+
+    /// Returns the season so far as a full table
+    fn into_table(self) -> LeagueTable {
+        let mut table = self.table.clone();
+        for (team, state) in table.teams.iter_mut().zip(self.teams) {
+            team.set_state(state);
+        }
+        for (home, away, home_goals, away_goals) in self.results {
+            table
+                .head_to_head
+                .entry((home, away))
+                .or_default()
+                .push((home_goals, away_goals));
+        }
+        table
+    }
+}
+
+/// Plays out every remaining fixture once and returns the resulting final table,
+/// calling sample with each fixture's position in the list to sample a full match
+fn simulate_season<R: Rng + ?Sized>(
+    current_table: &LeagueTable,
+    fixtures: &[ResolvedMatch],
+    rng: &mut R,
+    sample: impl FnMut(usize, &mut R) -> (u32, u32),
+) -> LeagueTable {
+    play_season(current_table, fixtures, rng, sample)
+        .0
+        .into_table()
+}
+
+/// Plays out every remaining fixture once as in simulate_season, returning the
+/// final state of the season and the score of each fixture in the order given
+fn play_season<'a, R: Rng + ?Sized>(
+    current_table: &'a LeagueTable,
+    fixtures: &[ResolvedMatch],
+    rng: &mut R,
+    mut sample: impl FnMut(usize, &mut R) -> (u32, u32),
+) -> (SeasonState<'a>, Vec<(u32, u32)>) {
+    let mut season = SeasonState::new(current_table);
+    let mut scores = Vec::with_capacity(fixtures.len());
+
+    for (i, &game) in fixtures.iter().enumerate() {
+        let (home_goals, away_goals) =
+            play(game.forced_result, game.live, rng, |rng| sample(i, rng));
+        season.update(game, home_goals, away_goals);
+        scores.push((home_goals, away_goals));
+    }
+
+    (season, scores)
+}
+
+/// Plays out every remaining fixture once with the given model, as in play_season,
+/// letting the model see each result as it is decided
+///
+/// fixtures must be match_list resolved against the table, in the same order
+fn simulate_season_with_model<'a, M, R>(
+    current_table: &'a LeagueTable,
+    fixtures: &[ResolvedMatch],
+    match_list: &[Match],
+    model: &M,
+    rng: &mut R,
+) -> SeasonState<'a>
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    play_season_with_model(current_table, fixtures, match_list, model, rng).0
+}
+
+/// Plays out every remaining fixture once with the given model as in
+/// simulate_season_with_model, also returning every fixture's score in order
+fn play_season_with_model<'a, M, R>(
+    current_table: &'a LeagueTable,
+    fixtures: &[ResolvedMatch],
+    match_list: &[Match],
+    model: &M,
+    rng: &mut R,
+) -> (SeasonState<'a>, Vec<(u32, u32)>)
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    let mut season = SeasonState::new(current_table);
+    let mut scores = Vec::with_capacity(fixtures.len());
+    model.start_season();
+
+    for (&game, fixture) in fixtures.iter().zip(match_list) {
+        let (home_goals, away_goals) = play_match(fixture, model, rng);
+        model.record_result(fixture, home_goals, away_goals);
+        season.update(game, home_goals, away_goals);
+        scores.push((home_goals, away_goals));
+    }
+
+    (season, scores)
+}
+
+/// Decides the final score of a match with the given model, as in play
+fn play_match<M, R>(game: &Match, model: &M, rng: &mut R) -> (u32, u32)
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    play(game.forced_result, game.live, rng, |rng| {
+        model.sample_score(game, rng)
+    })
+}
+
+/// Decides the final score of a single match: a forced result is used as
+/// given, a match in progress is completed from its live score and any
+/// other match is sampled in full
+fn play<R: Rng + ?Sized>(
+    forced_result: Option<(u32, u32)>,
+    live: Option<LiveScore>,
+    rng: &mut R,
+    sample: impl FnOnce(&mut R) -> (u32, u32),
+) -> (u32, u32) {
+    match (forced_result, live) {
+        (Some(score), _) => score,
+        (None, Some(live)) => {
+            let full_match = sample(rng);
+            complete(live, full_match, rng)
+        }
+        (None, None) => sample(rng),
+    }
+}
+
+/// Adds the goals still to come in a match in progress to its live score
+///
+/// Goals are assumed to be spread evenly over the match, so each goal of a
+/// full match sample is kept with probability equal to the share of the
+/// match left to play
+fn complete<R: Rng + ?Sized>(live: LiveScore, full_match: (u32, u32), rng: &mut R) -> (u32, u32) {
+    let remaining = live.remaining_fraction();
+    let mut thin = |goals: u32| (0..goals).filter(|_| rng.random_bool(remaining)).count() as u32;
+    let home_goals = thin(full_match.0);
+    let away_goals = thin(full_match.1);
+    (live.home_goals + home_goals, live.away_goals + away_goals)
+}
+
+/// A shared flag that stops a running batch of simulations early
+///
+/// Clones share the same flag, so one clone can be handed to the batch through
+/// its SimulationConfig while another is kept to cancel it, e.g. when a web
+/// request is abandoned or the user presses Ctrl-C
+#[derive(Debug, Default, Clone)]
+pub struct CancellationHandle(Arc<AtomicBool>);
+
+impl PartialEq for CancellationHandle {
+    /// Handles are equal when they share the same flag
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationHandle {}
+
+/// A pool of threads that batches of simulations are run on
+///
+/// Clones share the same threads, so a pool created once, e.g. when a web
+/// server starts, can be handed to every batch through its SimulationConfig
+/// rather than each batch spawning threads of its own
+#[derive(Debug, Clone)]
+pub struct SimulationPool(Arc<rayon::ThreadPool>);
+
+impl PartialEq for SimulationPool {
+    /// Pools are equal when they share the same threads
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SimulationPool {}
+
+impl SimulationPool {
+    /// create a pool with the given number of threads, or a thread per core for zero
+    ///
+    /// Panics if the thread pool cannot be created
+    pub fn new(threads: u32) -> Self {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build()
+            .expect("should be able to create the simulation thread pool");
+        Self(Arc::new(pool))
+    }
+
+    /// Returns the number of threads in the pool
+    pub fn threads(&self) -> u32 {
+        self.0.current_num_threads() as u32
+    }
+}
+
+impl CancellationHandle {
+    /// create a handle that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops every batch holding a clone of this handle after the simulations
+    /// already underway
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+
+    /// Returns true if the handle has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// The estimate an adaptive batch of simulations watches, and how precise it
+/// must be before the batch stops
+#[derive(Debug, Clone, PartialEq)]
+struct Convergence {
+    target_team: String,
+    target: Target,
+    max_standard_error: f64,
+}
+
+/// What counts as success for a team in a simulated season
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Finishing at the given rank or better
+    Rank(u32),
+    /// Finishing above the named rival
+    Above(String),
+}
+
+/// How many seasons run_simulations plays out, the size of the thread pool
+/// that shares the work, how often progress is reported and how the batch can
+/// be cancelled or stop early
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationConfig {
+    simulations: u32,
+    threads: u32,
+    pool: Option<SimulationPool>,
+    progress_interval: u32,
+    cancellation: Option<CancellationHandle>,
+    time_budget: Option<Duration>,
+    convergence: Option<Convergence>,
+    variance_reduction: VarianceReduction,
+    rng_backend: RngBackend,
+    given: Vec<MatchResult>,
+    key_fixtures: Option<(String, u32)>,
+    play_offs: Option<PlayOffs>,
+}
+
+/// How the random draws of the simulations in a batch relate to one another
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VarianceReduction {
+    /// Every simulation makes its own independent draws
+    #[default]
+    Independent,
+    /// Simulations run in pairs, the second sampling every score from the
+    /// opposite end of the goal distributions to the first, so that a freak
+    /// season in one is balanced by the other and estimates settle sooner
+    Antithetic,
+    /// Scores are sampled from a randomly shifted Halton sequence, which covers
+    /// the possible seasons more evenly than independent draws and so
+    /// estimates rare outcomes, such as a mid-table title, with fewer simulations
+    Halton,
+}
+
+/// The random number generator each worker of a batch draws from
+///
+/// Each worker seeds its own generator from the thread's generator when it
+/// starts, so batches are never reproducible whichever backend is chosen
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RngBackend {
+    /// The standard generator, a cryptographically secure ChaCha12
+    #[default]
+    Standard,
+    /// A small, fast generator (Xoshiro256++ on 64-bit platforms), which is
+    /// plenty for Monte Carlo simulation and noticeably quicker
+    Fast,
+}
+
+/// A worker's generator, of whichever backend the batch was configured with
+enum WorkerRng {
+    Standard(Box<StdRng>),
+    Fast(SmallRng),
+}
+
+impl WorkerRng {
+    /// create a generator of the given backend seeded from the thread's generator
+    fn new(backend: RngBackend) -> Self {
+        let seed = &mut rand::rng();
+        match backend {
+            RngBackend::Standard => Self::Standard(Box::new(StdRng::from_rng(seed))),
+            RngBackend::Fast => Self::Fast(SmallRng::from_rng(seed)),
+        }
+    }
+}
+
+impl RngCore for WorkerRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Standard(rng) => rng.next_u32(),
+            Self::Fast(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Standard(rng) => rng.next_u64(),
+            Self::Fast(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Standard(rng) => rng.fill_bytes(dest),
+            Self::Fast(rng) => rng.fill_bytes(dest),
+        }
+    }
+}
+
+impl Default for SimulationConfig {
+    /// Defaults to 16000 simulations with a thread per core
+    fn default() -> Self {
+        Self::new(16000)
+    }
+}
+
+impl SimulationConfig {
+    /// create a config for the given number of simulations, with a thread per core
+    pub fn new(simulations: u32) -> Self {
+        Self {
+            simulations,
+            threads: 0,
+            pool: None,
+            progress_interval: 1000,
+            cancellation: None,
+            time_budget: None,
+            convergence: None,
+            variance_reduction: VarianceReduction::Independent,
+            rng_backend: RngBackend::Standard,
+            given: Vec::new(),
+            key_fixtures: None,
+            play_offs: None,
+        }
+    }
+
+    /// Plays the given play-offs after every simulated season, so the report
+    /// counts how often each team wins them, as in SimulationReport::play_off_probability
+    pub fn with_play_offs(mut self, play_offs: PlayOffs) -> Self {
+        self.play_offs = Some(play_offs);
+        self
+    }
+
+    /// Returns the play-offs played after every simulated season, if any
+    pub fn play_offs(&self) -> Option<&PlayOffs> {
+        self.play_offs.as_ref()
+    }
+
+    /// Makes the batch track how the chance of the target team finishing at
+    /// the target rank or better depends on the outcome of each remaining
+    /// fixture, for SimulationReport::key_fixtures
+    pub fn with_key_fixtures(mut self, target_team: &str, target_rank: u32) -> Self {
+        self.key_fixtures = Some((target_team.to_string(), target_rank));
+        self
+    }
+
+    /// Conditions every simulation on the given results, which are applied as
+    /// played in place of sampling the matching fixtures, as if each had been
+    /// pinned with FixtureList::force_result
+    ///
+    /// Results for fixtures that are not in the simulated list are ignored
+    pub fn given(mut self, results: &[MatchResult]) -> Self {
+        self.given = results.to_vec();
+        self
+    }
+
+    /// Sets the random number generator each worker draws from
+    pub fn with_rng_backend(mut self, rng_backend: RngBackend) -> Self {
+        self.rng_backend = rng_backend;
+        self
+    }
+
+    /// Sets how the random draws of the simulations relate to one another
+    pub fn with_variance_reduction(mut self, variance_reduction: VarianceReduction) -> Self {
+        self.variance_reduction = variance_reduction;
+        self
+    }
+
+    /// Sets the number of threads the simulations are shared between, where
+    /// zero means a thread per core
+    pub fn with_threads(mut self, threads: u32) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Runs the simulations on the given pool, whatever number of threads is
+    /// set, so that batches sharing it do not each spawn threads of their own
+    pub fn with_pool(mut self, pool: SimulationPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Sets how many simulations complete between calls to the progress
+    /// callback of run_simulations_with_progress
+    pub fn with_progress_interval(mut self, progress_interval: u32) -> Self {
+        self.progress_interval = progress_interval.max(1);
+        self
+    }
+
+    /// Sets the handle that cancels the batch, which is checked before each simulation
+    pub fn with_cancellation(mut self, cancellation: CancellationHandle) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Limits the batch to the given wall-clock time: simulations stop once it
+    /// has passed, and the configured number of simulations becomes the most
+    /// that will be run. The report's simulations() gives how many fitted
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// Returns the wall-clock time the batch may take, if it is limited
+    pub fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    /// Makes the batch adaptive: simulations run in rounds, and stop once the
+    /// standard error of the probability that the target team finishes at the
+    /// target rank or better falls below the given threshold, after at least
+    /// 2000 simulations. The configured number of simulations becomes the most
+    /// that will be run
+    pub fn with_convergence(
+        self,
+        target_team: &str,
+        target_rank: u32,
+        max_standard_error: f64,
+    ) -> Self {
+        self.with_convergence_on(target_team, Target::Rank(target_rank), max_standard_error)
+    }
+
+    /// Makes the batch adaptive as in with_convergence, watching the
+    /// probability that the target team meets the given target
+    pub fn with_convergence_on(
+        mut self,
+        target_team: &str,
+        target: Target,
+        max_standard_error: f64,
+    ) -> Self {
+        self.convergence = Some(Convergence {
+            target_team: target_team.to_string(),
+            target,
+            max_standard_error,
+        });
+        self
+    }
+
+    /// Returns true if the batch has a cancellation handle that has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationHandle::is_cancelled)
+    }
+
+    /// Returns the results every simulation is conditioned on
+    pub fn given_results(&self) -> &[MatchResult] {
+        &self.given
+    }
+
+    /// Returns the total number of simulations
+    pub fn simulations(&self) -> u32 {
+        self.simulations
+    }
+
+    /// Returns how the random draws of the simulations relate to one another
+    pub fn variance_reduction(&self) -> VarianceReduction {
+        self.variance_reduction
+    }
+
+    /// Returns the random number generator each worker draws from
+    pub fn rng_backend(&self) -> RngBackend {
+        self.rng_backend
+    }
+
+    /// Returns how many simulations complete between progress reports
+    pub fn progress_interval(&self) -> u32 {
+        self.progress_interval
+    }
+
+    /// Returns the number of threads the simulations are shared between, or
+    /// zero for a thread per core
+    pub fn threads(&self) -> u32 {
+        self.threads
+    }
+
+    /// Returns the pool the simulations are run on, if one was given
+    pub fn pool(&self) -> Option<&SimulationPool> {
+        self.pool.as_ref()
+    }
+}
+
+/// Returns the fixtures with any of the given results for them pinned in place
+fn conditioned(match_list: &[Match], given: &[MatchResult]) -> Vec<Match> {
+    match_list
+        .iter()
+        .map(|fixture| {
+            match given.iter().find(|result| {
+                result.fixture.home == fixture.home && result.fixture.away == fixture.away
+            }) {
+                Some(result) => fixture
+                    .clone()
+                    .with_forced_result(result.home_goals, result.away_goals),
+                None => fixture.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Simulates the rest of the season as many times as the config asks, in
+/// parallel on a thread pool of the configured size, and collects the final
+/// tables into a single SimulationReport
+///
+/// The batch runs on the config's pool if it has one, and otherwise on a pool
+/// of its own with the configured number of threads
+///
+/// Each thread records into its own report and the reports are merged at the
+/// end, so the threads never wait on one another. If the config's cancellation
+/// handle is cancelled the remaining simulations are skipped, and the report
+/// holds only those that completed, as it does when a time budget runs out.
+/// An adaptive config checks its estimate after every round of simulations
+/// and stops as soon as it has converged
+///
+/// Panics if a fixture names a team that is not in the table, or if the
+/// thread pool cannot be created
+pub fn run_simulations(
+    config: &SimulationConfig,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+) -> SimulationReport {
+    run_simulations_with_progress(config, current_table, match_list, |_, _| {})
+}
+
+/// Runs simulations as in run_simulations, sampling every score from the given model
+///
+/// Each thread plays its seasons with a copy of the model of its own, so a
+/// model that changes during a season never sees another thread's results.
+/// The config's variance reduction only applies to the HistoricalAverageModel
+/// of run_simulations, so with any other model every simulation makes its own
+/// independent draws
+///
+/// Panics if a fixture names a team that is not in the table, or if the
+/// thread pool cannot be created
+pub fn run_simulations_with_model<M>(
+    config: &SimulationConfig,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    model: &M,
+) -> SimulationReport
+where
+    M: ScoreModel + Clone + Send,
+{
+    run_observed_simulations(
+        config,
+        current_table,
+        match_list,
+        Some(model),
+        |_, _| {},
+        |_| {},
+    )
+}
+
+/// Runs simulations as in run_simulations, calling on_progress with the number
+/// of simulations completed so far and the total every time the configured
+/// interval of simulations completes, and once more when all are done or the
+/// batch stops early
+///
+/// on_progress is called from the simulation threads, so calls may arrive
+/// out of order and should return quickly
+pub fn run_simulations_with_progress<F>(
+    config: &SimulationConfig,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    on_progress: F,
+) -> SimulationReport
+where
+    F: Fn(u32, u32) + Sync,
+{
+    run_observed_simulations(
+        config,
+        current_table,
+        match_list,
+        None::<&HistoricalAverageModel>,
+        on_progress,
+        |_| {},
+    )
+}
+
+/// Runs simulations as in run_simulations, sending how the target team finished
+/// each simulated season down the channel as soon as that season is played, so
+/// a consumer can follow the batch live or keep the raw outcomes
+///
+/// Outcomes arrive in whatever order the threads finish them. The batch carries
+/// on if the receiver hangs up, and the returned report still covers every season
+pub fn stream_simulations(
+    config: &SimulationConfig,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    outcomes: Sender<SimOutcome>,
+) -> SimulationReport {
+    run_observed_simulations(
+        config,
+        current_table,
+        match_list,
+        None::<&HistoricalAverageModel>,
+        |_, _| {},
+        |season| {
+            let _ = outcomes.send(SimOutcome::new(season, target_team));
+        },
+    )
+}
+
+/// Runs simulations as in run_simulations_with_progress, also handing every
+/// finished season to on_season before it is recorded
+///
+/// Scores are sampled from the given model if there is one, and otherwise
+/// from the HistoricalAverageModel by way of its alias tables and quantiles
+fn run_observed_simulations<M, F, G>(
+    config: &SimulationConfig,
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    model: Option<&M>,
+    on_progress: F,
+    on_season: G,
+) -> SimulationReport
+where
+    M: ScoreModel + Clone + Send,
+    F: Fn(u32, u32) + Sync,
+    G: Fn(&SeasonState) + Sync,
+{
+    let match_list = conditioned(match_list, &config.given);
+    let fixtures = current_table
+        .resolve_all(&match_list)
+        .expect("fixtures should only name teams in the table");
+    let empty_report = || {
+        let report = SimulationReport::new(current_table);
+        match &config.key_fixtures {
+            Some((team, rank)) => report.with_key_fixtures(team, *rank, &match_list),
+            None => report,
+        }
+    };
+    // each worker clones its own copy of the given model from this one
+    let template = model.map(|model| Mutex::new(model.clone()));
+    let worker_model = || {
+        let template = template.as_ref()?;
+        let model = template
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Some(model.clone())
+    };
+    let model = historical_model();
+    let pool = match &config.pool {
+        Some(pool) => pool.clone(),
+        None => SimulationPool::new(config.threads),
+    };
+    let completed = AtomicU32::new(0);
+    let deadline = config.time_budget.map(|budget| Instant::now() + budget);
+    let stopped =
+        || config.is_cancelled() || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+    // running in rounds gives convergence and the time budget somewhere to be checked
+    let round = match (&config.convergence, config.time_budget) {
+        (None, None) => config.simulations,
+        _ => CONVERGENCE_ROUND,
+    };
+
+    // a random shift of every dimension keeps quasi-random estimates unbiased
+    let shifts: Vec<f64> = match config.variance_reduction {
+        VarianceReduction::Halton => {
+            let mut rng = rand::rng();
+            (0..2 * fixtures.len()).map(|_| rng.random()).collect()
+        }
+        _ => Vec::new(),
+    };
+    let bases = primes(shifts.len());
+    let shares: Vec<f64> = fixtures
+        .iter()
+        .map(|game| game.home_advantage_share())
+        .collect();
+
+    let mut report = empty_report();
+    while report.simulations() < config.simulations && !stopped() {
+        let batch = round.min(config.simulations - report.simulations());
+        let offset = report.simulations();
+        // only the historical model's antithetic runs play two seasons each
+        let runs = match (&template, config.variance_reduction) {
+            (None, VarianceReduction::Antithetic) => batch.div_ceil(2),
+            _ => batch,
+        };
+        report.combine(&pool.0.install(|| {
+            (0..runs)
+                .into_par_iter()
+                // each share of the work reuses one generator for all of its simulations
+                .fold(
+                    || {
+                        (
+                            empty_report(),
+                            WorkerRng::new(config.rng_backend),
+                            Vec::new(),
+                            worker_model(),
+                        )
+                    },
+                    |(mut report, mut rng, mut goals, given), run| {
+                        if stopped() {
+                            return (report, rng, goals, given);
+                        }
+                        let seasons = match (&given, config.variance_reduction) {
+                            (Some(given), _) => vec![play_season_with_model(
+                                current_table,
+                                &fixtures,
+                                &match_list,
+                                given,
+                                &mut rng,
+                            )],
+                            (None, VarianceReduction::Independent) => {
+                                // every score is drawn up front into the reused buffer
+                                model.sample_all(&shares, &mut rng, &mut goals);
+                                vec![play_season(current_table, &fixtures, &mut rng, |i, _| {
+                                    goals[i]
+                                })]
+                            }
+                            (None, VarianceReduction::Antithetic) => {
+                                let draws: Vec<(f64, f64)> = fixtures
+                                    .iter()
+                                    .map(|_| (rng.random(), rng.random()))
+                                    .collect();
+                                let mut seasons = vec![play_season(
+                                    current_table,
+                                    &fixtures,
+                                    &mut rng,
+                                    |i, _| model.quantile_at(shares[i], draws[i]),
+                                )];
+                                // the last run of an odd batch has no partner
+                                if 2 * run + 1 < batch {
+                                    seasons.push(play_season(
+                                        current_table,
+                                        &fixtures,
+                                        &mut rng,
+                                        |i, _| {
+                                            let (home, away) = draws[i];
+                                            model.quantile_at(shares[i], (1.0 - home, 1.0 - away))
+                                        },
+                                    ));
+                                }
+                                seasons
+                            }
+                            (None, VarianceReduction::Halton) => {
+                                // each fixture takes the next two dimensions of the point
+                                let point = offset + run + 1;
+                                let draw = |dimension: usize| {
+                                    (radical_inverse(point, bases[dimension]) + shifts[dimension])
+                                        .fract()
+                                };
+                                vec![play_season(current_table, &fixtures, &mut rng, |i, _| {
+                                    model.quantile_at(shares[i], (draw(2 * i), draw(2 * i + 1)))
+                                })]
+                            }
+                        };
+                        for (season, scores) in &seasons {
+                            on_season(season);
+                            report.record_season(season, scores);
+                            match (&config.play_offs, &given) {
+                                (Some(play_offs), Some(given)) => {
+                                    report.record_play_offs(season, play_offs, given, &mut rng)
+                                }
+                                (Some(play_offs), None) => {
+                                    report.record_play_offs(season, play_offs, model, &mut rng)
+                                }
+                                (None, _) => {}
+                            }
+                            let done = completed.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+                            if done.is_multiple_of(config.progress_interval)
+                                || done == config.simulations
+                            {
+                                on_progress(done, config.simulations);
+                            }
+                        }
+                        (report, rng, goals, given)
+                    },
+                )
+                .map(|(report, _, _, _)| report)
+                .reduce(empty_report, |mut report, other| {
+                    report.combine(&other);
+                    report
+                })
+        }));
+        if let Some(convergence) = &config.convergence {
+            let error = report.target_standard_error(&convergence.target_team, &convergence.target);
+            if error < convergence.max_standard_error
+                && report.simulations() >= MIN_CONVERGED_SIMULATIONS
+            {
+                break;
+            }
+        }
+    }
+    // a batch cut short still reports where it stopped, even before any simulation
+    let done = report.simulations();
+    if done != config.simulations && (done == 0 || !done.is_multiple_of(config.progress_interval)) {
+        on_progress(done, config.simulations);
+    }
+    report
+}
+
+/// Works out the exact probability of every team finishing in every position
+/// by enumerating every combination of win, draw and loss in the remaining
+/// fixtures, weighted by the HistoricalAverageModel's chance of each
+///
+/// Each outcome is played as its most likely scoreline, so goal difference
+/// ties are settled as if every win, draw and loss ended that way. Rows are
+/// ordered by expected finishing position, as in SimulationReport::rank_matrix.
+/// Returns None if more than 12 fixtures remain, where Monte Carlo simulation
+/// is the better tool. Use enumerate_target where goal difference must not be
+/// settled this way
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn enumerate_season(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+) -> Option<Vec<RankProbabilities>> {
+    let (fixtures, outcomes) = enumerable_outcomes(current_table, match_list)?;
+    let mut matrix = vec![vec![0.0; current_table.len()]; current_table.len()];
+    // every outcome continues, so the walk always visits them all
+    let _ = enumerate_outcomes(
+        &mut SeasonState::new(current_table),
+        &fixtures,
+        &outcomes,
+        1.0,
+        &mut |season, probability| {
+            for (id, rank) in season.ranked_ids().into_iter().zip(0..) {
+                matrix[id.index()][rank] += probability;
+            }
+            ControlFlow::Continue(())
+        },
+    );
+
+    let mut rows: Vec<(f64, RankProbabilities)> = current_table
+        .teams()
+        .zip(matrix)
+        .map(|(team, probabilities)| {
+            let position = probabilities
+                .iter()
+                .zip(1..)
+                .map(|(p, rank)| p * f64::from(rank))
+                .sum();
+            let row = RankProbabilities {
+                name: team.name.clone(),
+                probabilities,
+            };
+            (position, row)
+        })
+        .collect();
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Some(rows.into_iter().map(|(_, row)| row).collect())
+}
+
+/// Works out the exact probability of the named team meeting the target by
+/// enumerating every combination of win, draw and loss, as in enumerate_season
+///
+/// Where the team can finish level on points with a team that decides whether
+/// it meets the target, the tie would be settled by the goal differences of
+/// the most likely scorelines rather than by any real spread of results, so
+/// None is returned for Monte Carlo simulation to work it out instead, as it
+/// is when more than 12 fixtures remain. A team or rival not in the table
+/// never meets its target
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn enumerate_target(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    target: &Target,
+) -> Option<f64> {
+    let (fixtures, outcomes) = enumerable_outcomes(current_table, match_list)?;
+    let team = current_table.team_id(target_team);
+    let rival = match target {
+        Target::Above(rival) => current_table.team_id(rival),
+        Target::Rank(_) => None,
+    };
+    let (Some(team), Some(rival)) = (team, rival.or(team)) else {
+        return Some(0.0);
+    };
+    let mut success = 0.0;
+    let walk = enumerate_outcomes(
+        &mut SeasonState::new(current_table),
+        &fixtures,
+        &outcomes,
+        1.0,
+        &mut |season, probability| {
+            let ranked = season.ranked_ids();
+            let points = |id: &TeamId| season.teams[id.index()].pts;
+            let position = |id| ranked.iter().position(|&other| other == id).unwrap_or(0);
+            let (met, tied) = match target {
+                Target::Rank(rank) => {
+                    // the teams on the same points fill a run of positions, and
+                    // only a run spanning the rank leaves the tiebreakers to decide
+                    let on_points: Vec<usize> = (0..ranked.len())
+                        .filter(|&i| points(&ranked[i]) == points(&team))
+                        .collect();
+                    let (first, last) = (on_points[0], on_points[on_points.len() - 1]);
+                    let rank = *rank as usize;
+                    (position(team) < rank, first < rank && last >= rank)
+                }
+                Target::Above(_) => (
+                    position(team) < position(rival),
+                    team != rival && points(&team) == points(&rival),
+                ),
+            };
+            // no exact answer can be given, so there is no point going on
+            if tied {
+                return ControlFlow::Break(());
+            }
+            if met {
+                success += probability;
+            }
+            ControlFlow::Continue(())
+        },
+    );
+    walk.is_continue().then_some(success)
+}
+
+/// The scores a fixture is enumerated over, each with its chance
+type Outcomes = Vec<(u32, u32, f64)>;
+
+/// Returns the fixtures resolved against the table along with the outcomes
+/// each is enumerated over, or None if there are too many to enumerate
+///
+/// Panics if a fixture names a team that is not in the table
+fn enumerable_outcomes(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+) -> Option<(Vec<ResolvedMatch>, Vec<Outcomes>)> {
+    if match_list.len() > MAX_ENUMERATED_FIXTURES {
+        return None;
+    }
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let model = historical_model();
+    let outcomes = fixtures
+        .iter()
+        .map(|&game| match game.forced_result {
+            Some((home_goals, away_goals)) => vec![(home_goals, away_goals, 1.0)],
+            None => outcome_scores(model, game),
+        })
+        .collect();
+    Some((fixtures, outcomes))
+}
+
+/// Plays every combination of the given outcomes of the remaining fixtures,
+/// handing on_outcome the season each combination leaves and its probability,
+/// and stops as soon as on_outcome breaks
+///
+/// Each outcome is played into the season in place and taken back out once
+/// every combination after it has been visited, so nothing is cloned
+fn enumerate_outcomes(
+    season: &mut SeasonState,
+    fixtures: &[ResolvedMatch],
+    outcomes: &[Outcomes],
+    probability: f64,
+    on_outcome: &mut dyn FnMut(&SeasonState, f64) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    let Some((&game, rest)) = fixtures.split_first() else {
+        return on_outcome(season, probability);
+    };
+    let (home, away) = (game.home.index(), game.away.index());
+    let before = (season.teams[home], season.teams[away], season.results.len());
+    for &(home_goals, away_goals, chance) in &outcomes[0] {
+        season.update(game, home_goals, away_goals);
+        let walk = enumerate_outcomes(
+            season,
+            rest,
+            &outcomes[1..],
+            probability * chance,
+            on_outcome,
+        );
+        season.teams[home] = before.0;
+        season.teams[away] = before.1;
+        season.results.truncate(before.2);
+        walk?;
+    }
+    ControlFlow::Continue(())
+}
+
+/// Returns the most likely final score of a home win, a draw and an away win
+/// in the fixture, each with the chance of that outcome, skipping impossible outcomes
+///
+/// A match in progress keeps its live score and adds goals from a full match,
+/// each kept with the chance that it falls in the time left, as in complete
+fn outcome_scores(model: &HistoricalAverageModel, game: ResolvedMatch) -> Vec<(u32, u32, f64)> {
+    let (home, away) = model.goal_probabilities(game.home_advantage_share());
+    let (home, away, live) = match game.live {
+        Some(live) => {
+            let remaining = live.remaining_fraction();
+            (
+                thinned(&home, remaining),
+                thinned(&away, remaining),
+                (live.home_goals, live.away_goals),
+            )
+        }
+        None => (home, away, (0, 0)),
+    };
+
+    // (chance of the outcome, most likely score, that score's chance) for each outcome
+    let mut best = [(0.0, (0, 0), 0.0); 3];
+    for (home_goals, home_chance) in (live.0..).zip(&home) {
+        for (away_goals, away_chance) in (live.1..).zip(&away) {
+            let chance = home_chance * away_chance;
+            let outcome = &mut best[match home_goals.cmp(&away_goals) {
+                Ordering::Greater => 0,
+                Ordering::Equal => 1,
+                Ordering::Less => 2,
+            }];
+            outcome.0 += chance;
+            if chance > outcome.2 {
+                outcome.1 = (home_goals, away_goals);
+                outcome.2 = chance;
+            }
+        }
+    }
+    best.into_iter()
+        .filter(|&(chance, _, _)| chance > 0.0)
+        .map(|(chance, (home_goals, away_goals), _)| (home_goals, away_goals, chance))
+        .collect()
+}
+
+/// Returns the distribution of goals still to come when each goal of a full
+/// match is kept with the chance that it falls in the remaining fraction of the match
+fn thinned(full_match: &[f64], remaining: f64) -> Vec<f64> {
+    let mut thinned = vec![0.0; full_match.len()];
+    for (goals, &chance) in full_match.iter().enumerate() {
+        // binomial probabilities of keeping each number of the goals
+        let mut ways = 1.0;
+        for (kept, slot) in thinned.iter_mut().enumerate().take(goals + 1) {
+            let dropped = goals - kept;
+            *slot += chance
+                * ways
+                * remaining.powi(kept as i32)
+                * (1.0 - remaining).powi(dropped as i32);
+            ways = ways * dropped as f64 / (kept + 1) as f64;
+        }
+    }
+    thinned
+}
+
+/// Returns the first count prime numbers, the bases of a Halton sequence
+fn primes(count: usize) -> Vec<u32> {
+    let mut primes: Vec<u32> = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if primes.iter().all(|prime| candidate % prime != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/// Returns the index'th element of the van der Corput sequence in the given
+/// base, found by mirroring the index's digits about the decimal point
+fn radical_inverse(mut index: u32, base: u32) -> f64 {
+    let mut inverse = 0.0;
+    let mut scale = 1.0 / f64::from(base);
+    while index > 0 {
+        inverse += f64::from(index % base) * scale;
+        index /= base;
+        scale /= f64::from(base);
+    }
+    inverse
+}
+
+/// Simulates the rest of the season the given number of times and collects
+/// the final tables into a SimulationReport
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn simulate_batch(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    simulations: u32,
+) -> SimulationReport {
+    simulate_batch_with_rng(current_table, match_list, simulations, &mut rand::rng())
+}
+
+/// Runs a batch of simulations as in simulate_batch, drawing random numbers
+/// from the given generator so that a seeded generator reproduces the whole batch
+pub fn simulate_batch_with_rng<R: Rng + ?Sized>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    simulations: u32,
+    rng: &mut R,
+) -> SimulationReport {
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let model = historical_model();
+    let mut report = SimulationReport::new(current_table);
+    for _ in 0..simulations {
+        let (season, _) = play_season(current_table, &fixtures, rng, |i, rng| {
+            model.sample_at(fixtures[i].home_advantage_share(), rng)
+        });
+        report.record_season(&season, &[]);
+    }
+    report
+}
+
+/// Runs a batch of simulations as in simulate_batch, sampling every score from
+/// the given model and drawing random numbers from the given generator
+pub fn simulate_batch_with_model<M, R>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    simulations: u32,
+    model: &M,
+    rng: &mut R,
+) -> SimulationReport
+where
+    M: ScoreModel + ?Sized,
+    R: Rng,
+{
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let mut report = SimulationReport::new(current_table);
+    for _ in 0..simulations {
+        let season = simulate_season_with_model(current_table, &fixtures, match_list, model, rng);
+        report.record_season(&season, &[]);
+    }
+    report
+}
+
+/// Simulates the rest of the season matchday by matchday the given number of
+/// times, returning the probability that the named team meets the target in
+/// the table as it stands after each future matchday, earliest first
+///
+/// Fixtures without a matchday are played after the last scheduled matchday,
+/// and give a final point with no matchday. A team not in the table never
+/// meets its target, nor does a team aiming to finish above a rival not in
+/// the table, as with SimulationReport::successes_above
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn simulate_trajectory(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    target: &Target,
+    simulations: u32,
+) -> Vec<TrajectoryPoint> {
+    simulate_trajectory_with_rng(
+        current_table,
+        match_list,
+        target_team,
+        target,
+        simulations,
+        &mut rand::rng(),
+    )
+}
+
+/// Simulates the trajectory as in simulate_trajectory, drawing random numbers
+/// from the given generator so that a seeded generator reproduces the series
+pub fn simulate_trajectory_with_rng<R: Rng + ?Sized>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    target: &Target,
+    simulations: u32,
+    rng: &mut R,
+) -> Vec<TrajectoryPoint> {
+    let fixtures = current_table
+        .resolve_all(match_list)
+        .expect("fixtures should only name teams in the table");
+    let model = historical_model();
+
+    // play fixtures in matchday order, unscheduled ones last
+    let mut order: Vec<usize> = (0..fixtures.len()).collect();
+    order.sort_by_key(|&i| (match_list[i].matchday.is_none(), match_list[i].matchday));
+    let mut matchdays: Vec<Option<u32>> = order.iter().map(|&i| match_list[i].matchday).collect();
+    matchdays.dedup();
+
+    let team = current_table.team_id(target_team);
+    let rival = match target {
+        Target::Above(rival) => current_table.team_id(rival),
+        Target::Rank(_) => None,
+    };
+    let meets = |season: &SeasonState| match (team, target) {
+        (Some(team), Target::Rank(rank)) => season.rank_by_id(team) <= *rank,
+        (Some(team), Target::Above(_)) => {
+            rival.is_some_and(|rival| season.rank_by_id(team) < season.rank_by_id(rival))
+        }
+        (None, _) => false,
+    };
+
+    let mut successes = vec![0u32; matchdays.len()];
+    for _ in 0..simulations {
+        let mut season = SeasonState::new(current_table);
+        let mut played = order.iter().peekable();
+        for (matchday, count) in matchdays.iter().zip(&mut successes) {
+            while let Some(&&i) = played.peek() {
+                if match_list[i].matchday != *matchday {
+                    break;
+                }
+                let game = fixtures[i];
+                let (home_goals, away_goals) = play(game.forced_result, game.live, rng, |rng| {
+                    model.sample_at(game.home_advantage_share(), rng)
+                });
+                season.update(game, home_goals, away_goals);
+                played.next();
+            }
+            *count += u32::from(meets(&season));
+        }
+    }
+
+    matchdays
+        .into_iter()
+        .zip(successes)
+        .map(|(matchday, count)| TrajectoryPoint {
+            matchday,
+            probability: if simulations == 0 {
+                0.0
+            } else {
+                f64::from(count) / f64::from(simulations)
+            },
+        })
+        .collect()
+}
+
+/// The chance of a team meeting its target in the table after one matchday
+///
+/// Serializes to json as {"matchday": 30, "probability": 0.25}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrajectoryPoint {
+    matchday: Option<u32>,
+    probability: f64,
+}
+
+impl TrajectoryPoint {
+    /// Returns the matchday, or None for fixtures played without one
+    pub fn matchday(&self) -> Option<u32> {
+        self.matchday
+    }
+
+    /// Returns the fraction of simulations in which the team met its target
+    /// once the matchday had been played
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+}
+
+/// Plays out the rest of the season once, keeping every result, so it can be
+/// browsed match by match
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn replay_season(current_table: &LeagueTable, match_list: &[Match]) -> SeasonReplay {
+    replay_season_with_rng(current_table, match_list, &mut rand::rng())
+}
+
+/// Plays out the season as in replay_season, drawing random numbers from the
+/// given generator so that a seeded generator reproduces the replay
+pub fn replay_season_with_rng<R: Rng>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    rng: &mut R,
+) -> SeasonReplay {
+    let results = simulate_results_with_rng(match_list, rng);
+    let mut final_table = current_table.clone();
+    final_table
+        .apply_results(&results)
+        .expect("fixtures should only name teams in the table");
+    SeasonReplay {
+        results,
+        final_table,
+    }
+}
+
+/// Plays out the rest of the season the given number of times and returns the
+/// replay of a typical one: the season in which the named team's points came
+/// closest to its median across all of them
+///
+/// Panics if a fixture names a team that is not in the table
+pub fn median_replay(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    simulations: u32,
+) -> SeasonReplay {
+    median_replay_with_rng(
+        current_table,
+        match_list,
+        target_team,
+        simulations,
+        &mut rand::rng(),
+    )
+}
+
+/// Finds a typical season as in median_replay, drawing random numbers from the
+/// given generator so that a seeded generator picks the same season
+///
+/// Each season is played from its own seed, so only the points of each need
+/// to be kept while searching and the chosen one is simply played again
+pub fn median_replay_with_rng<R: Rng>(
+    current_table: &LeagueTable,
+    match_list: &[Match],
+    target_team: &str,
+    simulations: u32,
+    rng: &mut R,
+) -> SeasonReplay {
+    let seeds: Vec<u64> = (0..simulations.max(1)).map(|_| rng.random()).collect();
+    let points_of = |replay: &SeasonReplay| {
+        replay
+            .final_table
+            .get_team(target_team)
+            .map_or(0, Team::points)
+    };
+    let points: Vec<u32> = seeds
+        .iter()
+        .map(|&seed| {
+            points_of(&replay_season_with_rng(
+                current_table,
+                match_list,
+                &mut StdRng::seed_from_u64(seed),
+            ))
+        })
+        .collect();
+    let mut sorted = points.clone();
+    sorted.sort_unstable();
+    let median = sorted[sorted.len() / 2];
+    let closest = points
+        .iter()
+        .position(|&total| total == median)
+        .expect("the median should be one of the totals");
+    replay_season_with_rng(
+        current_table,
+        match_list,
+        &mut StdRng::seed_from_u64(seeds[closest]),
+    )
+}
+
+/// A single simulated remainder of the season: every match result in the
+/// order the fixtures were given and the final table they lead to
+///
+/// Serializes to json as {"results": [...], "final_table": [...]}, with
+/// results in the layout of the fixtures file plus their scores and the
+/// table in the layout of the standings file
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonReplay {
+    results: Vec<MatchResult>,
+    final_table: LeagueTable,
+}
+
+impl SeasonReplay {
+    /// Returns every simulated result in fixture order
+    pub fn results(&self) -> &[MatchResult] {
+        &self.results
+    }
+
+    /// Returns the table as it stands once every result has been applied
+    pub fn final_table(&self) -> &LeagueTable {
+        &self.final_table
+    }
+
+    /// Returns the replay as pretty-printed json, for saving or sharing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a replay should always serialize")
+    }
+}
+
+/// A named what-if: results to assume and the model that samples the rest
+struct Scenario {
+    name: String,
+    given: Vec<MatchResult>,
+    model: Box<dyn ScoreModel>,
+}
+
+/// Several what-if scenarios to be simulated side by side against a baseline
+///
+/// Every scenario, and the baseline, plays the same number of seasons, and
+/// each fixture of season n draws from its own generator, seeded from the
+/// season and the fixture's place in the list the same way in every scenario.
+/// A fixture whose result is assumed, or a model that draws more or fewer
+/// numbers, therefore leaves the draws of every other fixture alone, and the
+/// scenarios differ only where their assumptions do and not by the luck of the
+/// draw. The baseline samples every remaining fixture from a HistoricalAverageModel
+pub struct ScenarioSet {
+    simulations: u32,
+    seed: u64,
+    scenarios: Vec<Scenario>,
+}
+
+impl ScenarioSet {
+    /// create an empty set of scenarios, each to be simulated the given number
+    /// of times from seeds derived from the given seed
+    pub fn new(simulations: u32, seed: u64) -> Self {
+        Self {
+            simulations,
+            seed,
+            scenarios: Vec::new(),
+        }
+    }
+
+    /// Adds a scenario that assumes the given results, as in
+    /// SimulationConfig::given, and samples the rest as the baseline does
+    pub fn with_scenario(self, name: &str, given: &[MatchResult]) -> Self {
+        self.with_model_scenario(name, given, HistoricalAverageModel::new())
+    }
+
+    /// Adds a scenario that assumes the given results and samples the rest
+    /// from the given model
+    pub fn with_model_scenario<M: ScoreModel + 'static>(
+        mut self,
+        name: &str,
+        given: &[MatchResult],
+        model: M,
+    ) -> Self {
+        self.scenarios.push(Scenario {
+            name: name.to_string(),
+            given: given.to_vec(),
+            model: Box::new(model),
+        });
+        self
+    }
+
+    /// Returns the names of the scenarios in the order they were added
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.scenarios.iter().map(|scenario| scenario.name.as_str())
+    }
+
+    /// Simulates the baseline and every scenario from the given table and fixtures
+    ///
+    /// Panics if a fixture names a team that is not in the table
+    pub fn run(&self, current_table: &LeagueTable, match_list: &[Match]) -> ScenarioComparison {
+        let baseline = historical_model();
+        ScenarioComparison {
+            baseline: self.simulate(current_table, match_list, baseline),
+            scenarios: self
+                .scenarios
+                .iter()
+                .map(|scenario| {
+                    let fixtures = conditioned(match_list, &scenario.given);
+                    (
+                        scenario.name.clone(),
+                        self.simulate(current_table, &fixtures, scenario.model.as_ref()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Plays every season of one scenario, each fixture of season n seeded as in every other
+    fn simulate(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        model: &dyn ScoreModel,
+    ) -> SimulationReport {
+        let fixtures = current_table
+            .resolve_all(match_list)
+            .expect("fixtures should only name teams in the table");
+        let mut report = SimulationReport::new(current_table);
+        for season in 0..self.simulations {
+            let mut state = SeasonState::new(current_table);
+            model.start_season();
+            for (index, (&game, fixture)) in fixtures.iter().zip(match_list).enumerate() {
+                let mut rng = StdRng::seed_from_u64(self.fixture_seed(season, index));
+                let (home_goals, away_goals) = play_match(fixture, model, &mut rng);
+                model.record_result(fixture, home_goals, away_goals);
+                state.update(game, home_goals, away_goals);
+            }
+            report.record_season(&state, &[]);
+        }
+        report
+    }
+
+    /// Returns the seed of the given fixture's generator in the given season
+    fn fixture_seed(&self, season: u32, index: usize) -> u64 {
+        self.seed
+            .wrapping_add(u64::from(season) << 32)
+            .wrapping_add(index as u64)
+    }
+}
+
+/// The reports from simulating a ScenarioSet, for comparing each scenario with the baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioComparison {
+    baseline: SimulationReport,
+    scenarios: Vec<(String, SimulationReport)>,
+}
+
+impl ScenarioComparison {
+    /// Returns the report of the baseline, in which no results were assumed
+    pub fn baseline(&self) -> &SimulationReport {
+        &self.baseline
+    }
+
+    /// Returns the report of the named scenario, if there is one
+    pub fn scenario(&self, name: &str) -> Option<&SimulationReport> {
+        self.scenarios
+            .iter()
+            .find(|(scenario, _)| scenario == name)
+            .map(|(_, report)| report)
+    }
+
+    /// Returns how much more likely the team is to finish at the given rank or
+    /// better under the named scenario than under the baseline, negative if
+    /// less likely, or None if there is no such scenario
+    pub fn delta(&self, name: &str, team: &str, rank: u32) -> Option<f64> {
+        self.scenario(name)
+            .map(|report| report.probability(team, rank) - self.baseline.probability(team, rank))
+    }
+
+    /// Returns a row per team, in the order of the original table, setting its
+    /// baseline chance of finishing at the given rank or better beside the
+    /// change under each scenario
+    pub fn deltas(&self, rank: u32) -> Vec<ScenarioDeltas> {
+        self.baseline
+            .totals
+            .iter()
+            .map(|totals| {
+                let baseline = self.baseline.probability(&totals.name, rank);
+                ScenarioDeltas {
+                    name: totals.name.clone(),
+                    baseline,
+                    deltas: self
+                        .scenarios
+                        .iter()
+                        .map(|(scenario, report)| {
+                            (
+                                scenario.clone(),
+                                report.probability(&totals.name, rank) - baseline,
+                            )
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// One team's row of a ScenarioComparison
+///
+/// Serializes to json as {"name": "Arsenal", "baseline": 0.4, "deltas": [["Win the derby", 0.1]]}
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScenarioDeltas {
+    name: String,
+    baseline: f64,
+    deltas: Vec<(String, f64)>,
+}
+
+impl ScenarioDeltas {
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the team's chance of reaching the rank under the baseline
+    pub fn baseline(&self) -> f64 {
+        self.baseline
+    }
+
+    /// Returns the change in the team's chance under the named scenario, or
+    /// zero for a scenario not in the comparison
+    pub fn delta(&self, scenario: &str) -> f64 {
+        self.deltas
+            .iter()
+            .find(|(name, _)| name == scenario)
+            .map_or(0.0, |&(_, delta)| delta)
+    }
+
+    /// Returns each scenario's name alongside the change in the team's chance under it
+    pub fn deltas(&self) -> &[(String, f64)] {
+        &self.deltas
+    }
+}
+
+/// Two divisions linked by promotion and relegation, for projecting which
+/// division each team plays in several seasons from now
+///
+/// The current season of each division is played out from its table and its
+/// remaining fixtures, both divisions together in every simulation, so the
+/// teams that go up are always the ones the lower table sends up and the
+/// teams that come down always make way for them
+///
+/// At the end of every season the bottom teams of the upper division swap
+/// places with the top teams of the lower one, outright unless the lower
+/// division holds play-offs for its last promotion place. A relegation
+/// play-off can then give the next team down in the upper division the chance
+/// to stay up against the next team in the lower. Both divisions then
+/// start again from nothing on a double round robin, keeping their
+/// tiebreakers, points scheme and zones
+#[derive(Debug, Clone)]
+pub struct LeagueSystem {
+    upper: LeagueTable,
+    upper_fixtures: Vec<Match>,
+    lower: LeagueTable,
+    lower_fixtures: Vec<Match>,
+    exchanged: u32,
+    playoffs: Option<PlayOffs>,
+    relegation_playoff: bool,
+}
+
+impl LeagueSystem {
+    /// create a league system from each division's current table and remaining
+    /// fixtures, exchanging three teams between them at the end of every season
+    pub fn new(
+        upper: LeagueTable,
+        upper_fixtures: &[Match],
+        lower: LeagueTable,
+        lower_fixtures: &[Match],
+    ) -> Self {
+        Self {
+            upper,
+            upper_fixtures: upper_fixtures.to_vec(),
+            lower,
+            lower_fixtures: lower_fixtures.to_vec(),
+            exchanged: 3,
+            playoffs: None,
+            relegation_playoff: false,
+        }
+    }
+
+    /// Sets the play-offs the lower division holds for the last promotion place,
+    /// the rest going to the teams at the top of its table
+    pub fn with_playoffs(mut self, playoffs: PlayOffs) -> Self {
+        self.playoffs = Some(playoffs);
+        self
+    }
+
+    /// Returns the play-offs the lower division holds for the last promotion place, if any
+    pub fn playoffs(&self) -> Option<&PlayOffs> {
+        self.playoffs.as_ref()
+    }
+
+    /// Sets whether the highest upper division team outside the exchanged places
+    /// meets the highest lower division team outside them in a two-legged tie,
+    /// the lower division team at home first, with the winner playing in the
+    /// upper division next season, as in the Bundesliga's play-off between
+    /// 16th and the second division's third
+    pub fn with_relegation_playoff(mut self, relegation_playoff: bool) -> Self {
+        self.relegation_playoff = relegation_playoff;
+        self
+    }
+
+    /// Returns true if a relegation play-off is held after the exchanged places
+    pub fn relegation_playoff(&self) -> bool {
+        self.relegation_playoff
+    }
+
+    /// Sets the number of teams promoted and relegated at the end of every season
+    pub fn with_exchanged(mut self, exchanged: u32) -> Self {
+        self.exchanged = exchanged;
+        self
+    }
+
+    /// Returns the number of teams promoted and relegated at the end of every season
+    pub fn exchanged(&self) -> u32 {
+        self.exchanged
+    }
+
+    /// Plays the given number of seasons, starting with the current one, the
+    /// given number of times and counts where every team ends up after each
+    ///
+    /// Every match is sampled with the HistoricalAverageModel; use
+    /// project_with_model to play them with another ScoreModel.
+    /// Panics if a fixture names a team that is not in its division's table
+    pub fn project(&self, seasons: u32, simulations: u32) -> DivisionProjection {
+        self.project_with_rng(seasons, simulations, &mut rand::rng())
+    }
+
+    /// Projects the divisions as in project, drawing random numbers from the given generator
+    pub fn project_with_rng<R: Rng>(
+        &self,
+        seasons: u32,
+        simulations: u32,
+        rng: &mut R,
+    ) -> DivisionProjection {
+        self.project_with_model(seasons, simulations, historical_model(), rng)
+    }
+
+    /// Projects the divisions as in project, sampling every match of every
+    /// season from the given model and drawing random numbers from the given generator
+    pub fn project_with_model<M, R>(
+        &self,
+        seasons: u32,
+        simulations: u32,
+        model: &M,
+        rng: &mut R,
+    ) -> DivisionProjection
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let mut upper_counts: HashMap<String, Vec<u64>> = self
+            .upper
+            .teams()
+            .chain(self.lower.teams())
+            .map(|team| (team.name().to_string(), vec![0; seasons as usize]))
+            .collect();
+        let mut exchanges: HashMap<(Vec<String>, Vec<String>), u64> = HashMap::new();
+        for _ in 0..simulations {
+            let mut upper =
+                simulate_table_with_model(&self.upper, &self.upper_fixtures, model, rng);
+            let mut lower =
+                simulate_table_with_model(&self.lower, &self.lower_fixtures, model, rng);
+            for season in 0..seasons as usize {
+                let (upper_names, lower_names) = self.exchange(&upper, &lower, model, rng);
+                if season == 0 {
+                    let moved = |names: &[String], from: &LeagueTable| -> Vec<String> {
+                        let moved = names.iter().filter(|name| from.team_id(name).is_some());
+                        moved.cloned().collect()
+                    };
+                    let mut promoted = moved(&upper_names, &self.lower);
+                    let mut relegated = moved(&lower_names, &self.upper);
+                    promoted.sort();
+                    relegated.sort();
+                    *exchanges.entry((promoted, relegated)).or_default() += 1;
+                }
+                for name in &upper_names {
+                    if let Some(counts) = upper_counts.get_mut(name) {
+                        counts[season] += 1;
+                    }
+                }
+                if season + 1 < seasons as usize {
+                    upper = Self::play_fresh_season(&self.upper, &upper_names, model, rng);
+                    lower = Self::play_fresh_season(&self.lower, &lower_names, model, rng);
+                }
+            }
+        }
+        let mut exchanges: Vec<Exchange> = exchanges
+            .into_iter()
+            .map(|((promoted, relegated), count)| Exchange {
+                promoted,
+                relegated,
+                probability: count as f64 / f64::from(simulations),
+            })
+            .collect();
+        exchanges.sort_by(|a, b| {
+            b.probability
+                .total_cmp(&a.probability)
+                .then_with(|| (&a.promoted, &a.relegated).cmp(&(&b.promoted, &b.relegated)))
+        });
+        DivisionProjection {
+            simulations,
+            seasons,
+            upper_counts,
+            exchanges,
+        }
+    }
+
+    /// Returns the number of teams that really change division each season,
+    /// which is never more than either division holds
+    fn exchanged_teams(&self) -> usize {
+        (self.exchanged as usize)
+            .min(self.upper.len())
+            .min(self.lower.len())
+    }
+
+    /// Returns the names of the teams in each division next season, once the
+    /// bottom of the finished upper table has swapped with the top of the lower,
+    /// sampling any play-off matches from the given model
+    fn exchange<M: ScoreModel + ?Sized, R: Rng>(
+        &self,
+        upper: &LeagueTable,
+        lower: &LeagueTable,
+        model: &M,
+        rng: &mut R,
+    ) -> (Vec<String>, Vec<String>) {
+        let exchanged = self.exchanged_teams();
+        let names = |table: &LeagueTable| -> Vec<String> {
+            table
+                .standings()
+                .iter()
+                .map(|team| team.name().to_string())
+                .collect()
+        };
+        let (upper, mut lower) = (names(upper), names(lower));
+        let (staying_up, relegated) = upper.split_at(upper.len() - exchanged);
+        // the play-off winner is moved up to take the last promotion place
+        let playoff_winner = match (&self.playoffs, exchanged) {
+            (Some(playoffs), 1..) => playoffs.winner_from_standings(&lower, model, rng),
+            _ => None,
+        };
+        if let Some(winner) = playoff_winner {
+            let position = lower.iter().position(|name| *name == winner);
+            if let Some(position) = position.filter(|&position| position >= exchanged - 1) {
+                let winner = lower.remove(position);
+                lower.insert(exchanged - 1, winner);
+            }
+        }
+        let (promoted, staying_down) = lower.split_at(exchanged);
+        let mut upper = [staying_up, promoted].concat();
+        let mut lower = [relegated, staying_down].concat();
+        // the upper side defends its place against the lower side
+        if self.relegation_playoff && !staying_up.is_empty() && exchanged < lower.len() {
+            let (defending, challenger) = (staying_up.len() - 1, exchanged);
+            let tie = TwoLeggedTie::new(&lower[challenger], &upper[defending]);
+            let result = tie.simulate_with_model(model, rng);
+            if result.winner() == lower[challenger] {
+                std::mem::swap(&mut upper[defending], &mut lower[challenger]);
+            }
+        }
+        (upper, lower)
+    }
+
+    /// Plays a whole double round robin season between the named teams, starting
+    /// from nothing under the rules of the given division, with the given model
+    fn play_fresh_season<M: ScoreModel + ?Sized, R: Rng>(
+        division: &LeagueTable,
+        names: &[String],
+        model: &M,
+        rng: &mut R,
+    ) -> LeagueTable {
+        let mut table = LeagueTable::with_tiebreakers(division.tiebreakers().clone());
+        table.set_points_scheme(division.points_scheme());
+        table.set_zones(division.zones().clone());
+        for name in names {
+            table
+                .add_team(name.clone(), 0, 0)
+                .expect("a division should not name a team twice");
+        }
+        let fixtures = FixtureList::round_robin(names.iter().cloned(), true);
+        simulate_table_with_model(&table, &fixtures, model, rng)
+    }
+}
+
+/// How often each team of a LeagueSystem played in the upper division, season by season
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivisionProjection {
+    simulations: u32,
+    seasons: u32,
+    // the number of simulations in which each team was in the upper division after each season
+    upper_counts: HashMap<String, Vec<u64>>,
+    exchanges: Vec<Exchange>,
+}
+
+impl DivisionProjection {
+    /// Returns the number of times the seasons were played
+    pub fn simulations(&self) -> u32 {
+        self.simulations
+    }
+
+    /// Returns the number of seasons played each time, including the current one
+    pub fn seasons(&self) -> u32 {
+        self.seasons
+    }
+
+    /// Returns the chance that the named team is in the upper division once
+    /// the given number of seasons have been played, 1 being the end of the
+    /// current season, or zero for a team in neither division or a season
+    /// beyond the projection
+    pub fn upper_division_probability(&self, team: &str, seasons: u32) -> f64 {
+        let count = seasons.checked_sub(1).and_then(|season| {
+            self.upper_counts
+                .get(team)
+                .and_then(|counts| counts.get(season as usize))
+        });
+        match (count, self.simulations) {
+            (Some(&count), 1..) => count as f64 / f64::from(self.simulations),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns every combination of teams promoted and relegated at the end of
+    /// the current season, most likely first, with the chance of each. Empty
+    /// if no seasons were played
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+}
+
+/// The teams that swapped divisions at the end of a season in a DivisionProjection
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    promoted: Vec<String>,
+    relegated: Vec<String>,
+    probability: f64,
+}
+
+impl Exchange {
+    /// Returns the names of the teams promoted to the upper division, in alphabetical order
+    pub fn promoted(&self) -> &[String] {
+        &self.promoted
+    }
+
+    /// Returns the names of the teams relegated to the lower division, in alphabetical order
+    pub fn relegated(&self) -> &[String] {
+        &self.relegated
+    }
+
+    /// Returns the chance of exactly these teams swapping divisions
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+}
+
+/// Running totals for one team across a batch of simulations
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct TeamTotals {
+    name: String,
+    points: u64,
+    goal_diff: i64,
+    rank: u64,
+    wins: u64,
+    draws: u64,
+    losses: u64,
+    // the number of simulations in which the team finished in each position, top first
+    rank_counts: Vec<u64>,
+    // the number of simulations in which the team finished on each points total
+    points_counts: BTreeMap<u32, u64>,
+    // the number of simulations in which the team finished above each team in the table
+    above_counts: Vec<u64>,
+    // the number of simulations in which the team won the play-offs
+    #[serde(default)]
+    play_off_wins: u64,
+}
+
+impl TeamTotals {
+    /// Returns the lowest points total that at least the given fraction of
+    /// simulations finished on or below, or zero if there are no simulations
+    fn points_percentile(&self, fraction: f64) -> u32 {
+        points_percentile(&self.points_counts, fraction)
+    }
+}
+
+/// Returns the lowest points total that at least the given fraction of the
+/// counted simulations finished on or below, or zero if none were counted
+fn points_percentile(points_counts: &BTreeMap<u32, u64>, fraction: f64) -> u32 {
+    let simulations: u64 = points_counts.values().sum();
+    let needed = ((fraction * simulations as f64).ceil() as u64).max(1);
+    let mut seen = 0;
+    for (&points, &count) in points_counts {
+        seen += count;
+        if seen >= needed {
+            return points;
+        }
+    }
+    0
+}
+
+/// Aggregated final tables from a batch of simulations of the same season
+///
+/// Reports from batches run separately, e.g. on different threads,
+/// can be combined with merge, and saved with write_json to be read back,
+/// merged or compared later
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "ReportData")]
+pub struct SimulationReport {
+    simulations: u32,
+    totals: Vec<TeamTotals>,
+    zones: Zones,
+    // the number of simulations in which the team finishing in each position
+    // did so on each points total, top of the table first
+    position_points: Vec<BTreeMap<u32, u64>>,
+    key_fixtures: Option<KeyFixtures>,
+}
+
+/// A report as written by write_json, before it is checked to hold a count
+/// for every team and position
+#[derive(Deserialize)]
+struct ReportData {
+    simulations: u32,
+    totals: Vec<TeamTotals>,
+    zones: Zones,
+    position_points: Vec<BTreeMap<u32, u64>>,
+    key_fixtures: Option<KeyFixtures>,
+}
+
+impl TryFrom<ReportData> for SimulationReport {
+    type Error = String;
+
+    fn try_from(data: ReportData) -> std::result::Result<Self, Self::Error> {
+        let teams = data.totals.len();
+        if data.position_points.len() != teams {
+            return Err(format!(
+                "position_points has {} positions for {teams} teams",
+                data.position_points.len()
+            ));
+        }
+        for totals in &data.totals {
+            if totals.rank_counts.len() != teams || totals.above_counts.len() != teams {
+                return Err(format!(
+                    "{:?} needs rank_counts and above_counts for all {teams} teams",
+                    totals.name
+                ));
+            }
+            if totals.rank_counts.iter().sum::<u64>() != u64::from(data.simulations) {
+                return Err(format!(
+                    "{:?} has rank_counts that do not add up to the {} simulations",
+                    totals.name, data.simulations
+                ));
+            }
+        }
+        if let Some(key_fixtures) = &data.key_fixtures {
+            if !data
+                .totals
+                .iter()
+                .any(|totals| totals.name == key_fixtures.team)
+            {
+                return Err(format!(
+                    "key_fixtures team {:?} is not in the report",
+                    key_fixtures.team
+                ));
+            }
+        }
+        Ok(Self {
+            simulations: data.simulations,
+            totals: data.totals,
+            zones: data.zones,
+            position_points: data.position_points,
+            key_fixtures: data.key_fixtures,
+        })
+    }
+}
+
+/// How often each outcome of each remaining fixture came up, and how often the
+/// target team went on to finish at the target rank or better when it did
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct KeyFixtures {
+    team: String,
+    rank: u32,
+    fixtures: Vec<FixtureTally>,
+}
+
+impl KeyFixtures {
+    /// Returns true if both track the same fixtures, in the same order, for the same team and rank
+    fn tracks_same(&self, other: &KeyFixtures) -> bool {
+        self.team == other.team
+            && self.rank == other.rank
+            && self.fixtures.len() == other.fixtures.len()
+            && self
+                .fixtures
+                .iter()
+                .zip(&other.fixtures)
+                .all(|(x, y)| x.home == y.home && x.away == y.away)
+    }
+}
+
+/// Counts for one fixture, indexed by home win, draw and away win
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct FixtureTally {
+    home: String,
+    away: String,
+    outcomes: [u64; 3],
+    successes: [u64; 3],
+}
+
+impl FixtureTally {
+    /// Returns the target team's chance of success after each outcome of the fixture
+    fn influence(&self) -> FixtureInfluence {
+        let [home_win, draw, away_win] = std::array::from_fn(|outcome| {
+            (self.outcomes[outcome] > 0)
+                .then(|| self.successes[outcome] as f64 / self.outcomes[outcome] as f64)
+        });
+        FixtureInfluence {
+            home: self.home.clone(),
+            away: self.away.clone(),
+            home_win,
+            draw,
+            away_win,
+        }
+    }
+}
+
+impl SimulationReport {
+    /// create an empty report for simulations starting from the given table,
+    /// summarised by the table's zones or, if it has none, by those of
+    /// Zones::for_teams for a league of its size
+    pub fn new(current_table: &LeagueTable) -> Self {
+        let zones = match current_table.zones().iter().next() {
+            Some(_) => current_table.zones().clone(),
+            None => Zones::for_teams(current_table.len() as u32),
+        };
+        Self {
+            simulations: 0,
+            zones,
+            position_points: vec![BTreeMap::new(); current_table.len()],
+            key_fixtures: None,
+            totals: current_table
+                .teams()
+                .map(|team| TeamTotals {
+                    name: team.name.clone(),
+                    points: 0,
+                    goal_diff: 0,
+                    rank: 0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    rank_counts: vec![0; current_table.len()],
+                    points_counts: BTreeMap::new(),
+                    above_counts: vec![0; current_table.len()],
+                    play_off_wins: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Makes the report track the target team's chance of finishing at the
+    /// target rank or better after each outcome of each of the given fixtures,
+    /// which must be the fixtures the simulations play, in the same order.
+    /// Seasons only count towards this when recorded with record_scores
+    ///
+    /// Nothing is tracked for a team not in the table
+    pub fn with_key_fixtures(
+        mut self,
+        target_team: &str,
+        target_rank: u32,
+        match_list: &[Match],
+    ) -> Self {
+        self.key_fixtures = self
+            .totals
+            .iter()
+            .any(|totals| totals.name == target_team)
+            .then(|| KeyFixtures {
+                team: target_team.to_string(),
+                rank: target_rank,
+                fixtures: match_list
+                    .iter()
+                    .map(|fixture| FixtureTally {
+                        home: fixture.home.clone(),
+                        away: fixture.away.clone(),
+                        outcomes: [0; 3],
+                        successes: [0; 3],
+                    })
+                    .collect(),
+            });
+        self
+    }
+
+    /// Adds one simulated final table to the report as in record, along with
+    /// the score of each fixture played to reach it for tracking key fixtures
+    pub fn record_scores(&mut self, simulated_table: &LeagueTable, scores: &[(u32, u32)]) {
+        self.record_season(&SeasonState::new(simulated_table), scores);
+    }
+
+    /// Returns the remaining fixtures ordered by how far the target team's
+    /// chance of finishing at the target rank or better swings between the
+    /// outcomes of each, the most influential first, or nothing if the
+    /// report was not set up to track key fixtures
+    pub fn key_fixtures(&self) -> Vec<FixtureInfluence> {
+        let Some(key_fixtures) = &self.key_fixtures else {
+            return Vec::new();
+        };
+        let mut influences: Vec<FixtureInfluence> = key_fixtures
+            .fixtures
+            .iter()
+            .map(FixtureTally::influence)
+            .collect();
+        influences.sort_by(|a, b| b.influence().total_cmp(&a.influence()));
+        influences
+    }
+
+    /// Returns the target team's must-win fixtures: those in which its chance
+    /// of finishing at the target rank or better falls below the threshold
+    /// whenever it fails to win, ordered as in key_fixtures
+    ///
+    /// Fixtures the target team never failed to win in the batch, such as a
+    /// forced win, are not must-win. Returns nothing if the report was not set
+    /// up to track key fixtures
+    pub fn must_win_fixtures(&self, threshold: f64) -> Vec<FixtureInfluence> {
+        let Some(key_fixtures) = &self.key_fixtures else {
+            return Vec::new();
+        };
+        let target = &key_fixtures.team;
+        let mut must_win: Vec<FixtureInfluence> = key_fixtures
+            .fixtures
+            .iter()
+            .filter(|tally| {
+                // the outcomes in which the target team drew or lost
+                let not_won = if &tally.home == target {
+                    1..3
+                } else if &tally.away == target {
+                    0..2
+                } else {
+                    return false;
+                };
+                let outcomes: u64 = tally.outcomes[not_won.clone()].iter().sum();
+                let successes: u64 = tally.successes[not_won].iter().sum();
+                outcomes > 0 && (successes as f64 / outcomes as f64) < threshold
+            })
+            .map(FixtureTally::influence)
+            .collect();
+        must_win.sort_by(|a, b| b.influence().total_cmp(&a.influence()));
+        must_win
+    }
+
+    /// Adds one simulated final table to the report
+    ///
+    /// The table must hold the same teams as the table the report was created from
+    pub fn record(&mut self, simulated_table: &LeagueTable) {
+        self.record_season(&SeasonState::new(simulated_table), &[]);
+    }
+
+    /// Adds one simulated season to the report, along with the score of each
+    /// fixture played in it if key fixtures are being tracked
+    fn record_season(&mut self, season: &SeasonState, scores: &[(u32, u32)]) {
+        let ranked = season.ranked_ids();
+        if let Some(key_fixtures) = &mut self.key_fixtures {
+            let rank = ranked
+                .iter()
+                .position(|id| self.totals[id.index()].name == key_fixtures.team)
+                .map_or(u32::MAX, |position| position as u32 + 1);
+            let success = u64::from(rank <= key_fixtures.rank);
+            for (tally, &(home_goals, away_goals)) in key_fixtures.fixtures.iter_mut().zip(scores) {
+                let outcome = match home_goals.cmp(&away_goals) {
+                    Ordering::Greater => 0,
+                    Ordering::Equal => 1,
+                    Ordering::Less => 2,
+                };
+                tally.outcomes[outcome] += 1;
+                tally.successes[outcome] += success;
+            }
+        }
+        for (position, id) in ranked.iter().enumerate() {
+            let above_counts = &mut self.totals[id.index()].above_counts;
+            for below in &ranked[position + 1..] {
+                above_counts[below.index()] += 1;
+            }
+        }
+        for (id, rank) in ranked.into_iter().zip(1..) {
+            let team = &season.teams[id.index()];
+            let totals = &mut self.totals[id.index()];
+            totals.points += u64::from(team.pts);
+            totals.goal_diff += i64::from(team.goal_diff);
+            totals.rank += rank;
+            totals.wins += u64::from(team.wins);
+            totals.draws += u64::from(team.draws);
+            totals.losses += u64::from(team.losses);
+            totals.rank_counts[rank as usize - 1] += 1;
+            *totals.points_counts.entry(team.pts).or_default() += 1;
+            *self.position_points[rank as usize - 1]
+                .entry(team.pts)
+                .or_default() += 1;
+        }
+        self.simulations += 1;
+    }
+
+    /// Plays the play-offs from one simulated season's final table, sampling
+    /// every match from the given model, and counts the winner
+    fn record_play_offs<M: ScoreModel + ?Sized, R: Rng>(
+        &mut self,
+        season: &SeasonState,
+        play_offs: &PlayOffs,
+        model: &M,
+        rng: &mut R,
+    ) {
+        let standings: Vec<String> = season
+            .ranked_ids()
+            .into_iter()
+            .map(|id| self.totals[id.index()].name.clone())
+            .collect();
+        if let Some(winner) = play_offs.winner_from_standings(&standings, model, rng) {
+            if let Some(totals) = self.totals.iter_mut().find(|totals| totals.name == winner) {
+                totals.play_off_wins += 1;
+            }
+        }
+    }
+
+    /// Writes the report as pretty-printed json to the file at the given path,
+    /// replacing any already there, so it can be read back with read_json
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), LeagueError> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self).map_err(std::io::Error::from)?;
+        Ok(())
+    }
+
+    /// Reads a report written by write_json from the file at the given path
+    ///
+    /// Fails with a schema error if the counts it holds do not cover every
+    /// team and position, or its key fixtures name a team not in it
+    pub fn read_json<P: AsRef<Path>>(path: P) -> std::result::Result<Self, LeagueError> {
+        read_json(BufReader::new(File::open(path)?))
+    }
+
+    /// Combines the simulations recorded in another report for the same table into this one
+    ///
+    /// Fails, leaving this report as it was, unless both reports list the same
+    /// teams in the same order and track the same key fixtures, if any
+    pub fn merge(&mut self, other: &SimulationReport) -> std::result::Result<(), MergeError> {
+        let same_teams = self.totals.len() == other.totals.len()
+            && self
+                .totals
+                .iter()
+                .zip(&other.totals)
+                .all(|(x, y)| x.name == y.name);
+        if !same_teams {
+            return Err(MergeError::DifferentTeams);
+        }
+        let same_key_fixtures = match (&self.key_fixtures, &other.key_fixtures) {
+            (Some(x), Some(y)) => x.tracks_same(y),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_key_fixtures {
+            return Err(MergeError::DifferentKeyFixtures);
+        }
+        self.combine(other);
+        Ok(())
+    }
+
+    /// Combines another report into this one as in merge, without checking
+    /// that it is for the same table
+    fn combine(&mut self, other: &SimulationReport) {
+        for (totals, other) in self.totals.iter_mut().zip(&other.totals) {
+            totals.points += other.points;
+            totals.goal_diff += other.goal_diff;
+            totals.rank += other.rank;
+            totals.wins += other.wins;
+            totals.draws += other.draws;
+            totals.losses += other.losses;
+            totals.play_off_wins += other.play_off_wins;
+            for (count, other) in totals.above_counts.iter_mut().zip(&other.above_counts) {
+                *count += other;
+            }
+            for (count, other) in totals.rank_counts.iter_mut().zip(&other.rank_counts) {
+                *count += other;
+            }
+            for (&points, &count) in &other.points_counts {
+                *totals.points_counts.entry(points).or_default() += count;
+            }
+        }
+        if let (Some(key_fixtures), Some(other)) = (&mut self.key_fixtures, &other.key_fixtures) {
+            for (tally, other) in key_fixtures.fixtures.iter_mut().zip(&other.fixtures) {
+                for outcome in 0..3 {
+                    tally.outcomes[outcome] += other.outcomes[outcome];
+                    tally.successes[outcome] += other.successes[outcome];
+                }
+            }
+        }
+        for (position, other) in self.position_points.iter_mut().zip(&other.position_points) {
+            for (&points, &count) in other {
+                *position.entry(points).or_default() += count;
+            }
+        }
+        self.simulations += other.simulations;
+    }
+
+    /// Returns the number of simulations recorded
+    pub fn simulations(&self) -> u32 {
+        self.simulations
+    }
+
+    /// Returns the number of simulations in which the named team finished at
+    /// the given rank or better, or zero for a team not in the table
+    pub fn successes(&self, team: &str, rank: u32) -> u64 {
+        self.team_totals(team)
+            .map(|totals| totals.rank_counts.iter().take(rank as usize).sum())
+            .unwrap_or(0)
+    }
+
+    /// Returns the fraction of simulations, from 0.0 to 1.0, in which the named
+    /// team finished at the given rank or better
+    pub fn probability(&self, team: &str, rank: u32) -> f64 {
+        if self.simulations == 0 {
+            return 0.0;
+        }
+        self.successes(team, rank) as f64 / f64::from(self.simulations)
+    }
+
+    /// Returns the number of simulations in which the named team finished above
+    /// the named rival, zero if either is not in the table
+    pub fn successes_above(&self, team: &str, rival: &str) -> u64 {
+        let Some(rival) = self.totals.iter().position(|totals| totals.name == rival) else {
+            return 0;
+        };
+        self.team_totals(team)
+            .map_or(0, |totals| totals.above_counts[rival])
+    }
+
+    /// Returns the fraction of simulations in which the named team finished
+    /// above the named rival
+    pub fn probability_above(&self, team: &str, rival: &str) -> f64 {
+        if self.simulations == 0 {
+            return 0.0;
+        }
+        self.successes_above(team, rival) as f64 / f64::from(self.simulations)
+    }
+
+    /// Returns the fraction of simulations in which the named team won the
+    /// play-offs, zero if the batch played none or the team is not in the table
+    pub fn play_off_probability(&self, team: &str) -> f64 {
+        match (self.team_totals(team), self.simulations) {
+            (Some(totals), 1..) => totals.play_off_wins as f64 / f64::from(self.simulations),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the fraction of simulations in which the named team was
+    /// promoted, either by finishing in the given number of automatic places
+    /// or by winning the play-offs held below them
+    pub fn promotion_probability(&self, team: &str, automatic_places: u32) -> f64 {
+        self.probability(team, automatic_places) + self.play_off_probability(team)
+    }
+
+    /// Returns the fraction of simulations in which the named team met the target
+    pub fn target_probability(&self, team: &str, target: &Target) -> f64 {
+        match target {
+            Target::Rank(rank) => self.probability(team, *rank),
+            Target::Above(rival) => self.probability_above(team, rival),
+        }
+    }
+
+    /// Returns the standard error of target_probability, as in standard_error
+    pub fn target_standard_error(&self, team: &str, target: &Target) -> f64 {
+        if self.simulations == 0 {
+            return f64::INFINITY;
+        }
+        let successes = match target {
+            Target::Rank(rank) => self.successes(team, *rank),
+            Target::Above(rival) => self.successes_above(team, rival),
+        };
+        // one success and one failure are added, so an outcome that always or
+        // never came up is still given some uncertainty
+        let trials = f64::from(self.simulations) + 2.0;
+        let probability = (successes as f64 + 1.0) / trials;
+        (probability * (1.0 - probability) / trials).sqrt()
+    }
+
+    /// Returns the points total that would have been enough to finish at the
+    /// given rank or better in the given fraction of simulations, e.g. the
+    /// points needed for a top four finish nine seasons in ten with a
+    /// confidence of 0.9
+    ///
+    /// Ties on points are counted as enough. None if the rank is outside the
+    /// table or no simulations have been recorded
+    pub fn points_needed(&self, rank: u32, confidence: f64) -> Option<u32> {
+        let position = self.position_points.get((rank as usize).checked_sub(1)?)?;
+        if self.simulations == 0 {
+            return None;
+        }
+        Some(points_percentile(position, confidence))
+    }
+
+    /// Returns the standard error of probability as an estimate of the true
+    /// probability that the named team finishes at the given rank or better
+    ///
+    /// The estimate is taken from (successes + 1) / (simulations + 2) rather
+    /// than the raw fraction, so it is never zero when the team always or never
+    /// reached the rank. Infinite if no simulations have been recorded
+    pub fn standard_error(&self, team: &str, rank: u32) -> f64 {
+        self.target_standard_error(team, &Target::Rank(rank))
+    }
+
+    /// Returns the fraction of simulations in which the named team finished in
+    /// each position, top of the table first, or None for a team not in the table
+    pub fn rank_distribution(&self, team: &str) -> Option<Vec<f64>> {
+        self.team_totals(team)
+            .map(|totals| self.distribution(totals))
+    }
+
+    /// Returns the probability of every team finishing in every position: one
+    /// row per team, ordered by mean finishing position, each holding the
+    /// fraction of simulations in which the team finished in each position
+    ///
+    /// Serializes to json as an array of {"name": "Arsenal", "probabilities": [0.6, 0.3, 0.1]},
+    /// ready to be rendered as a heatmap
+    pub fn rank_matrix(&self) -> Vec<RankProbabilities> {
+        let mut rows: Vec<(u64, RankProbabilities)> = self
+            .totals
+            .iter()
+            .map(|totals| {
+                let row = RankProbabilities {
+                    name: totals.name.clone(),
+                    probabilities: self.distribution(totals),
+                };
+                (totals.rank, row)
+            })
+            .collect();
+        rows.sort_by_key(|(rank, _)| *rank);
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+
+    /// Returns the fraction of simulations in which a team finished in each position
+    fn distribution(&self, totals: &TeamTotals) -> Vec<f64> {
+        let count = f64::from(self.simulations.max(1));
+        totals
+            .rank_counts
+            .iter()
+            .map(|&finishes| finishes as f64 / count)
+            .collect()
+    }
+
+    /// Returns every team's probability of finishing in each of the report's
+    /// zones, such as winning the title or being relegated, ordered by mean
+    /// finishing position
+    pub fn summary(&self) -> Vec<ZoneProbabilities> {
+        self.zone_probabilities(&self.zones)
+    }
+
+    /// Returns every team's probability of finishing in each of the given
+    /// zones, ordered by mean finishing position
+    pub fn zone_probabilities(&self, zones: &Zones) -> Vec<ZoneProbabilities> {
+        let mut totals: Vec<&TeamTotals> = self.totals.iter().collect();
+        totals.sort_by_key(|totals| totals.rank);
+        totals
+            .into_iter()
+            .map(|totals| ZoneProbabilities {
+                name: totals.name.clone(),
+                zones: zones
+                    .iter()
+                    .map(|zone| {
+                        let finishes: u64 = totals
+                            .rank_counts
+                            .iter()
+                            .zip(1..)
+                            .filter(|&(_, rank)| zone.contains(rank))
+                            .map(|(finishes, _)| finishes)
+                            .sum();
+                        let probability = finishes as f64 / f64::from(self.simulations.max(1));
+                        (zone.name().to_string(), probability)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Returns the running totals of the named team
+    fn team_totals(&self, team: &str) -> Option<&TeamTotals> {
+        self.totals.iter().find(|totals| totals.name == team)
+    }
+
+    /// Returns the projected final table: each team's mean points, goal
+    /// differential and finishing position across every simulation, sorted by
+    /// mean points and then by mean finishing position
+    ///
+    /// Empty if no simulations have been recorded
+    pub fn expected_table(&self) -> Vec<ExpectedStanding> {
+        if self.simulations == 0 {
+            return Vec::new();
+        }
+        let count = f64::from(self.simulations);
+        let mut table: Vec<ExpectedStanding> = self
+            .totals
+            .iter()
+            .map(|totals| ExpectedStanding {
+                name: totals.name.clone(),
+                points: totals.points as f64 / count,
+                goal_diff: totals.goal_diff as f64 / count,
+                position: totals.rank as f64 / count,
+                wins: totals.wins as f64 / count,
+                draws: totals.draws as f64 / count,
+                losses: totals.losses as f64 / count,
+                median_points: totals.points_percentile(0.5),
+                points_band: (
+                    totals.points_percentile(0.05),
+                    totals.points_percentile(0.95),
+                ),
+            })
+            .collect();
+        table.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(a.position.total_cmp(&b.position))
+        });
+        table
+    }
+}
+
+/// One team's chances of finishing in each zone of the table
+///
+/// Serializes to json as {"name": "Arsenal", "zones": [["Champions", 0.2], ["Relegation", 0.0]]}
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZoneProbabilities {
+    name: String,
+    zones: Vec<(String, f64)>,
+}
+
+impl ZoneProbabilities {
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the fraction of simulations in which the team finished in the
+    /// named zone, or zero for a zone not in the summary
+    pub fn probability(&self, zone: &str) -> f64 {
+        self.zones
+            .iter()
+            .find(|(name, _)| name == zone)
+            .map_or(0.0, |&(_, probability)| probability)
+    }
+
+    /// Returns each zone's name alongside the team's chance of finishing in it
+    pub fn zones(&self) -> &[(String, f64)] {
+        &self.zones
+    }
+}
+
+/// How the target team's chances in a batch of simulations depended on one fixture
+///
+/// Each chance is None if the outcome never came up in the batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FixtureInfluence {
+    home: String,
+    away: String,
+    home_win: Option<f64>,
+    draw: Option<f64>,
+    away_win: Option<f64>,
+}
+
+impl FixtureInfluence {
+    /// Returns the name of the home team
+    pub fn home(&self) -> &str {
+        &self.home
+    }
+
+    /// Returns the name of the away team
+    pub fn away(&self) -> &str {
+        &self.away
+    }
+
+    /// Returns the target team's chance of success in the simulations the home team won
+    pub fn home_win(&self) -> Option<f64> {
+        self.home_win
+    }
+
+    /// Returns the target team's chance of success in the simulations the match was drawn
+    pub fn draw(&self) -> Option<f64> {
+        self.draw
+    }
+
+    /// Returns the target team's chance of success in the simulations the away team won
+    pub fn away_win(&self) -> Option<f64> {
+        self.away_win
+    }
+
+    /// Returns the difference between the target team's chances after the
+    /// best and worst outcomes of the fixture that came up
+    pub fn influence(&self) -> f64 {
+        let chances = [self.home_win, self.draw, self.away_win];
+        let seen = chances.iter().flatten();
+        let best = seen.clone().copied().fold(f64::NEG_INFINITY, f64::max);
+        let worst = seen.copied().fold(f64::INFINITY, f64::min);
+        (best - worst).max(0.0)
+    }
+}
+
+/// One team's row of a SimulationReport's rank matrix
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankProbabilities {
+    name: String,
+    probabilities: Vec<f64>,
+}
+
+impl RankProbabilities {
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the probability that the team finishes at the given rank or better
+    pub fn at_or_above(&self, rank: u32) -> f64 {
+        self.probabilities.iter().take(rank as usize).sum()
+    }
+
+    /// Returns the fraction of simulations in which the team finished in each
+    /// position, top of the table first
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+}
+
+/// A team's averages across a batch of simulated seasons
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpectedStanding {
+    name: String,
+    points: f64,
+    goal_diff: f64,
+    position: f64,
+    wins: f64,
+    draws: f64,
+    losses: f64,
+    median_points: u32,
+    points_band: (u32, u32),
+}
+
+impl ExpectedStanding {
+    /// Returns the team's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the team's mean final points total
+    pub fn points(&self) -> f64 {
+        self.points
+    }
+
+    /// Returns the team's mean final goal differential
+    pub fn goal_diff(&self) -> f64 {
+        self.goal_diff
+    }
+
+    /// Returns the team's mean finishing position
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Returns the team's mean number of wins over the whole season
+    pub fn wins(&self) -> f64 {
+        self.wins
+    }
+
+    /// Returns the team's mean number of draws over the whole season
+    pub fn draws(&self) -> f64 {
+        self.draws
+    }
+
+    /// Returns the team's mean number of losses over the whole season
+    pub fn losses(&self) -> f64 {
+        self.losses
+    }
+
+    /// Returns the team's median final points total
+    pub fn median_points(&self) -> u32 {
+        self.median_points
+    }
+
+    /// Returns the 5th and 95th percentiles of the team's final points total,
+    /// between which nine simulated seasons in ten finish
+    pub fn points_band(&self) -> (u32, u32) {
+        self.points_band
+    }
+}
+
+/// The extra time a competition plays when a knockout tie is level, before penalties
+///
+/// Goals in extra time are sampled from a full match of the score model, each
+/// kept with probability equal to the share of 90 minutes played, scaled by
+/// the goal rate: 1.0 scores as freely as normal time, while a lower rate
+/// allows for tired legs and cautious play. No minutes at all sends a level tie
+/// straight to penalties
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ExtraTime {
+    minutes: u32,
+    goal_rate: f64,
+}
+
+impl Default for ExtraTime {
+    /// Defaults to 30 minutes scoring at the normal rate
+    fn default() -> Self {
+        Self::new(30, 1.0)
+    }
+}
+
+impl ExtraTime {
+    /// create extra time lasting the given number of minutes, scoring at the
+    /// given rate relative to normal time
+    pub fn new(minutes: u32, goal_rate: f64) -> Self {
+        Self { minutes, goal_rate }
+    }
+
+    /// create the rules of a competition with no extra time, whose level ties go to penalties
+    pub fn none() -> Self {
+        Self::new(0, 1.0)
+    }
+
+    /// Returns the number of minutes of extra time played
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    /// Returns how freely goals come in extra time, relative to normal time
+    pub fn goal_rate(&self) -> f64 {
+        self.goal_rate
+    }
+
+    /// Samples the goals each side scores in extra time at the fixture's
+    /// ground, as (home goals, away goals), from the given model
+    pub fn sample<M, R>(&self, fixture: &Match, model: &M, rng: &mut R) -> (u32, u32)
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        if self.minutes == 0 {
+            return (0, 0);
+        }
+        let keep =
+            (f64::from(self.minutes) / f64::from(MATCH_MINUTES) * self.goal_rate).clamp(0.0, 1.0);
+        let full_match = model.sample_score(fixture, rng);
+        let mut thin = |goals: u32| (0..goals).filter(|_| rng.random_bool(keep)).count() as u32;
+        (thin(full_match.0), thin(full_match.1))
+    }
+
+    /// Plays a single knockout match, followed by this extra time and then
+    /// penalties if level, returning true if the home side goes through
+    fn knockout_match<M, R>(&self, game: &Match, model: &M, rng: &mut R) -> bool
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let mut score = play_match(game, model, rng);
+        if score.0 == score.1 {
+            let extra_time = self.sample(game, model, rng);
+            score = (score.0 + extra_time.0, score.1 + extra_time.1);
+        }
+        match score.0.cmp(&score.1) {
+            Ordering::Equal => rng.random_bool(0.5),
+            outcome => outcome == Ordering::Greater,
+        }
+    }
+}
+
+/// A knockout tie decided over two matches, with each team at home once
+///
+/// The aggregate score across both legs decides the tie. When it is level the
+/// away goals rule, if used, is applied to the 180 minutes of the two legs,
+/// then the competition's extra time is played at the end of the second leg,
+/// 30 minutes unless set otherwise, and if still level the tie goes to penalties
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TwoLeggedTie {
+    first_leg: Match,
+    second_leg: Match,
+    away_goals_rule: bool,
+    #[serde(default)]
+    extra_time: ExtraTime,
+}
+
+impl TwoLeggedTie {
+    /// create a tie with the first leg at home_first's ground and the second
+    /// at away_first's, without the away goals rule
+    pub fn new(home_first: &str, away_first: &str) -> Self {
+        Self {
+            first_leg: Match::from(home_first, away_first),
+            second_leg: Match::from(away_first, home_first),
+            away_goals_rule: false,
+            extra_time: ExtraTime::default(),
+        }
+    }
+
+    /// Sets the extra time played when the tie is still level after both legs
+    pub fn with_extra_time(mut self, extra_time: ExtraTime) -> Self {
+        self.extra_time = extra_time;
+        self
+    }
+
+    /// Returns the extra time played when the tie is still level after both legs
+    pub fn extra_time(&self) -> ExtraTime {
+        self.extra_time
+    }
+
+    /// Sets whether away goals break a level aggregate before extra time
+    pub fn with_away_goals_rule(mut self, away_goals_rule: bool) -> Self {
+        self.away_goals_rule = away_goals_rule;
+        self
+    }
+
+    /// Returns the first leg
+    pub fn first_leg(&self) -> &Match {
+        &self.first_leg
+    }
+
+    /// Returns the second leg
+    pub fn second_leg(&self) -> &Match {
+        &self.second_leg
+    }
+
+    /// Returns true if away goals break a level aggregate
+    pub fn away_goals_rule(&self) -> bool {
+        self.away_goals_rule
+    }
+
+    /// Decides the tie from the scores of both legs, each given as (home goals, away goals)
+    ///
+    /// Returns None if the tie is still level after the two legs and needs
+    /// extra time
+    pub fn decide(&self, first_leg: (u32, u32), second_leg: (u32, u32)) -> Option<TieResult> {
+        let aggregate = (first_leg.0 + second_leg.1, first_leg.1 + second_leg.0);
+        if aggregate.0 != aggregate.1 {
+            return Some(self.result(aggregate.0 > aggregate.1, aggregate, TieDecider::Aggregate));
+        }
+        // each team's away goals come from the leg the other team hosted
+        let away_goals = (second_leg.1, first_leg.1);
+        if self.away_goals_rule && away_goals.0 != away_goals.1 {
+            return Some(self.result(
+                away_goals.0 > away_goals.1,
+                aggregate,
+                TieDecider::AwayGoals,
+            ));
+        }
+        None
+    }
+
+    /// Simulates both legs, and extra time and penalties if needed
+    ///
+    /// Either leg may carry a forced result or a live score, as with any other match
+    pub fn simulate(&self) -> TieResult {
+        self.simulate_with_rng(&mut rand::rng())
+    }
+
+    /// Simulates the tie as in simulate, drawing random numbers from the given generator
+    pub fn simulate_with_rng<R: Rng>(&self, rng: &mut R) -> TieResult {
+        self.simulate_with_model(historical_model(), rng)
+    }
+
+    /// Simulates the tie as in simulate, sampling scores from the given model
+    /// and drawing random numbers from the given generator
+    pub fn simulate_with_model<M, R>(&self, model: &M, rng: &mut R) -> TieResult
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let first_leg = play_match(&self.first_leg, model, rng);
+        let second_leg = play_match(&self.second_leg, model, rng);
+        if let Some(result) = self.decide(first_leg, second_leg) {
+            return result;
+        }
+
+        // extra time is played at the second leg's ground
+        let extra_time = self.extra_time.sample(&self.second_leg, model, rng);
+        let aggregate = (
+            first_leg.0 + second_leg.1 + extra_time.1,
+            first_leg.1 + second_leg.0 + extra_time.0,
+        );
+        if aggregate.0 != aggregate.1 {
+            self.result(aggregate.0 > aggregate.1, aggregate, TieDecider::ExtraTime)
+        } else {
+            self.result(rng.random_bool(0.5), aggregate, TieDecider::Penalties)
+        }
+    }
+
+    /// Builds the result of the tie, won by the first leg's home team if first_team_wins
+    fn result(
+        &self,
+        first_team_wins: bool,
+        aggregate: (u32, u32),
+        decided_by: TieDecider,
+    ) -> TieResult {
+        let winner = if first_team_wins {
+            &self.first_leg.home
+        } else {
+            &self.first_leg.away
+        };
+        TieResult {
+            winner: winner.clone(),
+            aggregate,
+            decided_by,
+        }
+    }
+}
+
+/// How a two-legged tie was decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum TieDecider {
+    /// More goals across the two legs
+    Aggregate,
+    /// Level on aggregate, but more goals scored away from home
+    AwayGoals,
+    /// More goals once extra time was added
+    ExtraTime,
+    /// Still level after extra time
+    Penalties,
+}
+
+/// The outcome of a two-legged tie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TieResult {
+    winner: String,
+    aggregate: (u32, u32),
+    decided_by: TieDecider,
+}
+
+impl TieResult {
+    /// Returns the name of the team that goes through
+    pub fn winner(&self) -> &str {
+        &self.winner
+    }
+
+    /// Returns the aggregate score, including any extra time, with the first
+    /// leg's home team first
+    pub fn aggregate(&self) -> (u32, u32) {
+        self.aggregate
+    }
+
+    /// Returns how the tie was decided
+    pub fn decided_by(&self) -> TieDecider {
+        self.decided_by
+    }
+}
+
+/// A post-season knockout between the teams finishing in a band of positions
+/// for a single prize, such as the Championship's play-offs for third to sixth
+///
+/// The highest finisher meets the lowest, the second highest the second
+/// lowest and so on, in two-legged ties with the higher finisher at home in
+/// the second leg. With an odd number of teams left the highest finisher sits
+/// the round out. The last two meet once at a neutral ground, with the
+/// competition's extra time and then penalties if level
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayOffs {
+    first: u32,
+    last: u32,
+    extra_time: ExtraTime,
+}
+
+impl PlayOffs {
+    /// create play-offs between the teams finishing from first to last inclusive
+    pub fn new(first: u32, last: u32) -> Self {
+        Self {
+            first,
+            last,
+            extra_time: ExtraTime::default(),
+        }
+    }
+
+    /// create the Championship's play-offs, between third and sixth, for the last promotion place
+    pub fn championship() -> Self {
+        Self::new(3, 6)
+    }
+
+    /// Sets the extra time played in level ties and in a level final
+    pub fn with_extra_time(mut self, extra_time: ExtraTime) -> Self {
+        self.extra_time = extra_time;
+        self
+    }
+
+    /// Returns the highest finishing position that reaches the play-offs
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    /// Returns the lowest finishing position that reaches the play-offs
+    pub fn last(&self) -> u32 {
+        self.last
+    }
+
+    /// Returns the extra time played in level ties and in a level final
+    pub fn extra_time(&self) -> ExtraTime {
+        self.extra_time
+    }
+
+    /// Plays the play-offs between the teams in their positions in the final
+    /// table and returns the winner's name, or None if no team finished in them
+    ///
+    /// Every match is sampled with the HistoricalAverageModel; use
+    /// winner_with_model to play them with another ScoreModel
+    pub fn winner(&self, final_table: &LeagueTable) -> Option<String> {
+        self.winner_with_rng(final_table, &mut rand::rng())
+    }
+
+    /// Plays the play-offs as in winner, drawing random numbers from the given generator
+    pub fn winner_with_rng<R: Rng>(
+        &self,
+        final_table: &LeagueTable,
+        rng: &mut R,
+    ) -> Option<String> {
+        self.winner_with_model(final_table, historical_model(), rng)
+    }
+
+    /// Plays the play-offs as in winner, sampling every match from the given
+    /// model and drawing random numbers from the given generator
+    pub fn winner_with_model<M, R>(
+        &self,
+        final_table: &LeagueTable,
+        model: &M,
+        rng: &mut R,
+    ) -> Option<String>
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let names: Vec<String> = final_table
+            .standings()
+            .iter()
+            .map(|team| team.name().to_string())
+            .collect();
+        self.winner_from_standings(&names, model, rng)
+    }
+
+    /// Plays the play-offs between the named teams, listed in finishing order
+    fn winner_from_standings<M: ScoreModel + ?Sized, R: Rng>(
+        &self,
+        standings: &[String],
+        model: &M,
+        rng: &mut R,
+    ) -> Option<String> {
+        let first = (self.first.max(1) - 1) as usize;
+        let last = (self.last as usize).min(standings.len());
+        let mut remaining = standings.get(first..last)?.to_vec();
+        while remaining.len() > 2 {
+            let bye = (remaining.len() % 2 == 1).then(|| remaining.remove(0));
+            let ties = remaining.len() / 2;
+            let winners = (0..ties).map(|i| {
+                let (higher, lower) = (&remaining[i], &remaining[remaining.len() - 1 - i]);
+                TwoLeggedTie::new(lower, higher)
+                    .with_extra_time(self.extra_time)
+                    .simulate_with_model(model, rng)
+                    .winner
+            });
+            remaining = bye.into_iter().chain(winners).collect();
+        }
+        let second_team = remaining.pop()?;
+        let Some(first_team) = remaining.pop() else {
+            return Some(second_team);
+        };
+        let final_match = Match::from(&first_team, &second_team).with_site(Site::Neutral);
+        let first_team_wins = self.extra_time.knockout_match(&final_match, model, rng);
+        Some(if first_team_wins {
+            first_team
+        } else {
+            second_team
+        })
+    }
+}
+
+/// How a knockout pairing in a conference bracket is played
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SeriesFormat {
+    /// One match at the higher seed's ground, with extra time and then
+    /// penalties if level
+    #[default]
+    SingleMatch,
+    /// Matches until one side has won a majority of the given number, the
+    /// higher seed at home first and the grounds alternating, with a level
+    /// match going straight to penalties
+    BestOf(u32),
+}
+
+/// A league whose teams are split into conferences for a knockout bracket
+/// after the regular season, as in Major League Soccer
+///
+/// The regular season is a single table of every team. The top finishers of
+/// each conference are seeded in order of their finishing positions and play
+/// down to a conference champion, the highest seed left meeting the lowest in
+/// every round. When the number left is not a power of two the top seeds sit
+/// out a play-in round for the rest. The conference champions then meet, seeded
+/// by their regular season finishing positions, for the championship
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConferenceLeague {
+    conferences: Vec<(String, Vec<String>)>,
+    playoff_places: u32,
+    rounds: Vec<SeriesFormat>,
+    extra_time: ExtraTime,
+}
+
+impl ConferenceLeague {
+    /// create a league with no conferences, in which the top four of each
+    /// conference reach the playoffs and every pairing is a single match
+    pub fn new() -> Self {
+        Self {
+            conferences: Vec::new(),
+            playoff_places: 4,
+            rounds: Vec::new(),
+            extra_time: ExtraTime::default(),
+        }
+    }
+
+    /// Adds a conference holding the named teams
+    pub fn with_conference<I, S>(mut self, name: &str, teams: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let teams = teams.into_iter().map(Into::into).collect();
+        self.conferences.push((name.to_string(), teams));
+        self
+    }
+
+    /// Sets the number of teams from each conference that reach the playoffs
+    pub fn with_playoff_places(mut self, playoff_places: u32) -> Self {
+        self.playoff_places = playoff_places;
+        self
+    }
+
+    /// Sets how each round of a conference bracket is played, the first
+    /// format for the first round played, a play-in round included, and so
+    /// on. Rounds beyond those given, and the championship, are single matches
+    pub fn with_rounds(mut self, rounds: &[SeriesFormat]) -> Self {
+        self.rounds = rounds.to_vec();
+        self
+    }
+
+    /// Sets the extra time played in a level single match
+    pub fn with_extra_time(mut self, extra_time: ExtraTime) -> Self {
+        self.extra_time = extra_time;
+        self
+    }
+
+    /// Returns the number of teams from each conference that reach the playoffs
+    pub fn playoff_places(&self) -> u32 {
+        self.playoff_places
+    }
+
+    /// Returns the names of the conferences in the order they were added
+    pub fn conferences(&self) -> impl Iterator<Item = &str> {
+        self.conferences.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the name of the conference the named team plays in, if any
+    pub fn conference_of(&self, team: &str) -> Option<&str> {
+        self.conferences
+            .iter()
+            .find(|(_, teams)| teams.iter().any(|name| name == team))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the standings of the named conference's teams within the
+    /// table, in rank order, or None if there is no such conference
+    pub fn conference_standings(&self, table: &LeagueTable, conference: &str) -> Option<Standings> {
+        let (_, teams) = self
+            .conferences
+            .iter()
+            .find(|(name, _)| name == conference)?;
+        let standings = table.standings();
+        Some(Standings(
+            standings
+                .iter()
+                .filter(|team| teams.contains(&team.name))
+                .cloned()
+                .collect(),
+        ))
+    }
+
+    /// Returns the names of the teams the named conference sends to the
+    /// playoffs from the final table, top seed first
+    pub fn seeds(&self, final_table: &LeagueTable, conference: &str) -> Vec<String> {
+        self.conference_standings(final_table, conference)
+            .map(|standings| {
+                standings
+                    .iter()
+                    .take(self.playoff_places as usize)
+                    .map(|team| team.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Plays the playoffs from the final table and returns the champion's
+    /// name, or None if no team reached them
+    ///
+    /// Every match is sampled with the HistoricalAverageModel; use
+    /// champion_with_model to play them with another ScoreModel
+    pub fn champion(&self, final_table: &LeagueTable) -> Option<String> {
+        self.champion_with_rng(final_table, &mut rand::rng())
+    }
+
+    /// Plays the playoffs as in champion, drawing random numbers from the given generator
+    pub fn champion_with_rng<R: Rng>(
+        &self,
+        final_table: &LeagueTable,
+        rng: &mut R,
+    ) -> Option<String> {
+        self.champion_with_model(final_table, historical_model(), rng)
+    }
+
+    /// Plays the playoffs as in champion, sampling every match from the given
+    /// model and drawing random numbers from the given generator
+    pub fn champion_with_model<M, R>(
+        &self,
+        final_table: &LeagueTable,
+        model: &M,
+        rng: &mut R,
+    ) -> Option<String>
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let mut conference_champions: Vec<String> = self
+            .conferences()
+            .filter_map(|conference| {
+                let seeds = self.seeds(final_table, conference);
+                self.play_bracket(seeds, &self.rounds, model, rng)
+            })
+            .collect();
+        let standings = final_table.standings();
+        conference_champions.sort_by_key(|team| standings.rank_of(team));
+        self.play_bracket(conference_champions, &[], model, rng)
+    }
+
+    /// Plays out the regular season from the table and fixtures the given
+    /// number of times, followed each time by the playoffs, and counts how
+    /// often every team reached them and won them
+    ///
+    /// Every match is sampled with the HistoricalAverageModel; use
+    /// project_with_model to play them with another ScoreModel.
+    /// Panics if a fixture names a team that is not in the table
+    pub fn project(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+    ) -> PlayoffProjection {
+        self.project_with_rng(current_table, match_list, simulations, &mut rand::rng())
+    }
+
+    /// Projects the playoffs as in project, drawing random numbers from the given generator
+    pub fn project_with_rng<R: Rng>(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+        rng: &mut R,
+    ) -> PlayoffProjection {
+        self.project_with_model(
+            current_table,
+            match_list,
+            simulations,
+            historical_model(),
+            rng,
+        )
+    }
+
+    /// Projects the playoffs as in project, sampling the regular season and
+    /// every playoff match from the given model and drawing random numbers
+    /// from the given generator
+    pub fn project_with_model<M, R>(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+        model: &M,
+        rng: &mut R,
+    ) -> PlayoffProjection
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let mut projection = PlayoffProjection {
+            simulations,
+            qualified: HashMap::new(),
+            champions: HashMap::new(),
+        };
+        for _ in 0..simulations {
+            let final_table = simulate_table_with_model(current_table, match_list, model, rng);
+            for conference in self.conferences() {
+                for team in self.seeds(&final_table, conference) {
+                    *projection.qualified.entry(team).or_default() += 1;
+                }
+            }
+            if let Some(champion) = self.champion_with_model(&final_table, model, rng) {
+                *projection.champions.entry(champion).or_default() += 1;
+            }
+        }
+        projection
+    }
+
+    /// Plays a bracket between the named teams, top seed first, using the
+    /// given format for each round in turn, and returns the winner's name
+    fn play_bracket<M: ScoreModel + ?Sized, R: Rng>(
+        &self,
+        seeds: Vec<String>,
+        rounds: &[SeriesFormat],
+        model: &M,
+        rng: &mut R,
+    ) -> Option<String> {
+        let mut remaining: Vec<(usize, String)> = seeds.into_iter().enumerate().collect();
+        let mut round = 0;
+        while remaining.len() > 1 {
+            let format = rounds.get(round).copied().unwrap_or_default();
+            // the top seeds sit out until the rest are down to a power of two
+            let byes = remaining.len().next_power_of_two() - remaining.len();
+            let playing = remaining.split_off(byes);
+            for i in 0..playing.len() / 2 {
+                let (higher, lower) = (&playing[i], &playing[playing.len() - 1 - i]);
+                let higher_wins = self.play_series(&higher.1, &lower.1, format, model, rng);
+                remaining.push(if higher_wins { higher } else { lower }.clone());
+            }
+            remaining.sort();
+            round += 1;
+        }
+        remaining.pop().map(|(_, team)| team)
+    }
+
+    /// Plays one pairing in the given format with the given model, returning
+    /// true if the higher seed goes through
+    fn play_series<M: ScoreModel + ?Sized, R: Rng>(
+        &self,
+        higher: &str,
+        lower: &str,
+        format: SeriesFormat,
+        model: &M,
+        rng: &mut R,
+    ) -> bool {
+        match format {
+            SeriesFormat::SingleMatch => {
+                self.extra_time
+                    .knockout_match(&Match::from(higher, lower), model, rng)
+            }
+            SeriesFormat::BestOf(games) => {
+                let needed = games / 2 + 1;
+                let (mut higher_wins, mut lower_wins) = (0, 0);
+                let mut higher_at_home = true;
+                while higher_wins < needed && lower_wins < needed {
+                    let (home, away) = if higher_at_home {
+                        (higher, lower)
+                    } else {
+                        (lower, higher)
+                    };
+                    let score = play_match(&Match::from(home, away), model, rng);
+                    let home_wins = match score.0.cmp(&score.1) {
+                        Ordering::Equal => rng.random_bool(0.5),
+                        outcome => outcome == Ordering::Greater,
+                    };
+                    if home_wins == higher_at_home {
+                        higher_wins += 1;
+                    } else {
+                        lower_wins += 1;
+                    }
+                    higher_at_home = !higher_at_home;
+                }
+                higher_wins == needed
+            }
+        }
+    }
+}
+
+impl Default for ConferenceLeague {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How often each team of a ConferenceLeague reached the playoffs and won the championship
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayoffProjection {
+    simulations: u32,
+    // the number of simulations in which each team reached the playoffs and won them
+    qualified: HashMap<String, u64>,
+    champions: HashMap<String, u64>,
+}
+
+impl PlayoffProjection {
+    /// Returns the number of seasons played
+    pub fn simulations(&self) -> u32 {
+        self.simulations
+    }
+
+    /// Returns the chance that the named team reaches the playoffs
+    pub fn playoff_probability(&self, team: &str) -> f64 {
+        self.share(self.qualified.get(team))
+    }
+
+    /// Returns the chance that the named team wins the championship
+    pub fn champion_probability(&self, team: &str) -> f64 {
+        self.share(self.champions.get(team))
+    }
+
+    /// Returns the count as a share of the simulations, or zero if there is none
+    fn share(&self, count: Option<&u64>) -> f64 {
+        match (count, self.simulations) {
+            (Some(&count), 1..) => count as f64 / f64::from(self.simulations),
+            _ => 0.0,
+        }
+    }
+}
+
+/// The stages of the Champions League knockouts, in the order they are reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum KnockoutStage {
+    /// The two-legged play-off between the teams ranked 9th to 24th in the league phase
+    KnockoutPlayOffs,
+    /// The last 16, where the top eight of the league phase join the play-off winners
+    RoundOf16,
+    /// The last eight
+    QuarterFinals,
+    /// The last four
+    SemiFinals,
+    /// The single match final at a neutral ground
+    Final,
+    /// Winning the final
+    Winner,
+}
+
+impl KnockoutStage {
+    /// every stage, in the order they are reached
+    const ALL: [Self; 6] = [
+        Self::KnockoutPlayOffs,
+        Self::RoundOf16,
+        Self::QuarterFinals,
+        Self::SemiFinals,
+        Self::Final,
+        Self::Winner,
+    ];
+}
+
+/// The Champions League from 2024-25: a single 36 team league phase followed
+/// by a seeded knockout bracket
+///
+/// The league phase is played as any other table, each team playing eight of
+/// the others. The top eight go straight to the round of 16 and the teams
+/// ranked 9th to 24th play off for the other eight places, 9th meeting 24th,
+/// 10th meeting 23rd and so on. The team ranked k of the top eight meets the
+/// winner of the play-off led by the team ranked 17 - k, and the winners of
+/// the round of 16 follow a fixed bracket in which the top two seeds can only
+/// meet in the final. Every tie is two-legged, with the better ranked team at
+/// home in the second leg, except the final, a single match at a neutral ground
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChampionsLeague {
+    extra_time: ExtraTime,
+}
+
+impl ChampionsLeague {
+    // teams going straight to the round of 16, and the lowest rank reaching the play-offs
+    const DIRECT_PLACES: usize = 8;
+    const PLAYOFF_PLACES: usize = 24;
+    // the league phase seeds of the round of 16 ties, in bracket order
+    const BRACKET: [usize; 8] = [1, 8, 4, 5, 2, 7, 3, 6];
+
+    /// create the competition with 30 minutes of extra time in level ties
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the extra time played in level ties
+    pub fn with_extra_time(mut self, extra_time: ExtraTime) -> Self {
+        self.extra_time = extra_time;
+        self
+    }
+
+    /// Returns the extra time played in level ties
+    pub fn extra_time(&self) -> ExtraTime {
+        self.extra_time
+    }
+
+    /// Plays the knockouts from the final league phase table and returns the
+    /// winner's name, or None if fewer than 24 teams took part
+    ///
+    /// Every tie is sampled with the HistoricalAverageModel; use
+    /// champion_with_model to play them with another ScoreModel
+    pub fn champion(&self, league_phase: &LeagueTable) -> Option<String> {
+        self.champion_with_rng(league_phase, &mut rand::rng())
+    }
+
+    /// Plays the knockouts as in champion, drawing random numbers from the given generator
+    pub fn champion_with_rng<R: Rng>(
+        &self,
+        league_phase: &LeagueTable,
+        rng: &mut R,
+    ) -> Option<String> {
+        self.champion_with_model(league_phase, historical_model(), rng)
+    }
+
+    /// Plays the knockouts as in champion, sampling every tie from the given
+    /// model and drawing random numbers from the given generator
+    pub fn champion_with_model<M, R>(
+        &self,
+        league_phase: &LeagueTable,
+        model: &M,
+        rng: &mut R,
+    ) -> Option<String>
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let standings: Vec<String> = league_phase
+            .standings()
+            .iter()
+            .map(|team| team.name().to_string())
+            .collect();
+        self.play_knockouts(&standings, model, rng, |_, _| {})
+    }
+
+    /// Plays out the rest of the league phase from the table and fixtures the
+    /// given number of times, followed each time by the knockouts, and counts
+    /// how far every team got
+    ///
+    /// Every match is sampled with the HistoricalAverageModel; use
+    /// project_with_model to play them with another ScoreModel.
+    /// Panics if a fixture names a team that is not in the table
+    pub fn project(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+    ) -> KnockoutProjection {
+        self.project_with_rng(current_table, match_list, simulations, &mut rand::rng())
+    }
+
+    /// Projects the competition as in project, drawing random numbers from the given generator
+    pub fn project_with_rng<R: Rng>(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+        rng: &mut R,
+    ) -> KnockoutProjection {
+        self.project_with_model(
+            current_table,
+            match_list,
+            simulations,
+            historical_model(),
+            rng,
+        )
+    }
+
+    /// Projects the competition as in project, sampling the league phase and
+    /// every tie from the given model and drawing random numbers from the
+    /// given generator
+    pub fn project_with_model<M, R>(
+        &self,
+        current_table: &LeagueTable,
+        match_list: &[Match],
+        simulations: u32,
+        model: &M,
+        rng: &mut R,
+    ) -> KnockoutProjection
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let fixtures = current_table
+            .resolve_all(match_list)
+            .expect("fixtures should only name teams in the table");
+        let mut furthest: HashMap<String, KnockoutStage> = HashMap::new();
+        let mut projection = KnockoutProjection {
+            simulations,
+            counts: HashMap::new(),
+        };
+        for _ in 0..simulations {
+            let league_phase =
+                simulate_season_with_model(current_table, &fixtures, match_list, model, rng)
+                    .into_table();
+            let standings: Vec<String> = league_phase
+                .standings()
+                .iter()
+                .map(|team| team.name().to_string())
+                .collect();
+            furthest.clear();
+            self.play_knockouts(&standings, model, rng, |team, stage| {
+                furthest.insert(team.to_string(), stage);
+            });
+            for (team, stage) in furthest.drain() {
+                let counts = projection.counts.entry(team).or_insert([0; 6]);
+                counts[stage as usize] += 1;
+            }
+        }
+        projection
+    }
+
+    /// Plays the knockouts between the named teams, listed in league phase
+    /// order, calling reached with every team and stage as each stage is
+    /// reached, and returns the winner's name
+    fn play_knockouts<M, R, F>(
+        &self,
+        standings: &[String],
+        model: &M,
+        rng: &mut R,
+        mut reached: F,
+    ) -> Option<String>
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+        F: FnMut(&str, KnockoutStage),
+    {
+        if standings.len() < Self::PLAYOFF_PLACES {
+            return None;
+        }
+        // every tie is hosted second by the better ranked team, the first named
+        let mut tie = |higher: &String, lower: &String, stage: KnockoutStage| {
+            reached(higher, stage);
+            reached(lower, stage);
+            let result = TwoLeggedTie::new(lower, higher)
+                .with_extra_time(self.extra_time)
+                .simulate_with_model(model, rng);
+            if result.winner == *higher {
+                higher.clone()
+            } else {
+                lower.clone()
+            }
+        };
+
+        let playoff_winners: Vec<String> = (0..Self::DIRECT_PLACES)
+            .map(|i| {
+                let higher = &standings[Self::DIRECT_PLACES + i];
+                let lower = &standings[Self::PLAYOFF_PLACES - 1 - i];
+                tie(higher, lower, KnockoutStage::KnockoutPlayOffs)
+            })
+            .collect();
+        let mut remaining: Vec<String> = Self::BRACKET
+            .iter()
+            .map(|&seed| {
+                let opponent = &playoff_winners[Self::DIRECT_PLACES - seed];
+                tie(&standings[seed - 1], opponent, KnockoutStage::RoundOf16)
+            })
+            .collect();
+        let rank = |team: &String| standings.iter().position(|name| name == team);
+        for stage in [KnockoutStage::QuarterFinals, KnockoutStage::SemiFinals] {
+            remaining = remaining
+                .chunks(2)
+                .map(|pair| {
+                    let (mut higher, mut lower) = (&pair[0], &pair[1]);
+                    if rank(lower) < rank(higher) {
+                        (higher, lower) = (lower, higher);
+                    }
+                    tie(higher, lower, stage)
+                })
+                .collect();
+        }
+
+        let (first, second) = (&remaining[0], &remaining[1]);
+        reached(first, KnockoutStage::Final);
+        reached(second, KnockoutStage::Final);
+        let final_match = Match::from(first, second).with_site(Site::Neutral);
+        let winner = if self.extra_time.knockout_match(&final_match, model, rng) {
+            first
+        } else {
+            second
+        };
+        reached(winner, KnockoutStage::Winner);
+        Some(winner.clone())
+    }
+}
+
+/// How far each team of a ChampionsLeague projection got in the knockouts
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnockoutProjection {
+    simulations: u32,
+    // the number of simulations in which each team went out at, or won, each stage
+    counts: HashMap<String, [u64; 6]>,
+}
+
+impl KnockoutProjection {
+    /// Returns the number of times the competition was played
+    pub fn simulations(&self) -> u32 {
+        self.simulations
+    }
+
+    /// Returns the chance that the named team reaches at least the given
+    /// stage, so the top eight of the league phase count as reaching the
+    /// knockout play-offs they skip
+    pub fn stage_probability(&self, team: &str, stage: KnockoutStage) -> f64 {
+        let Some(counts) = self.counts.get(team).filter(|_| self.simulations > 0) else {
+            return 0.0;
+        };
+        let reached: u64 = counts[stage as usize..].iter().sum();
+        reached as f64 / f64::from(self.simulations)
+    }
+
+    /// Returns the chance of the named team reaching every stage, in the order they are reached
+    pub fn stage_probabilities(&self, team: &str) -> Vec<(KnockoutStage, f64)> {
+        KnockoutStage::ALL
+            .iter()
+            .map(|&stage| (stage, self.stage_probability(team, stage)))
+            .collect()
+    }
+}
+
+/// A league that splits in two late in the season, as the Scottish Premiership
+/// does once every team has played 33 matches
+///
+/// At the split the top teams form one group and the rest another, and each
+/// team meets every other team in its group once more. Teams then finish
+/// within their group whatever their points, so the whole top group finishes
+/// above the whole bottom group
+///
+/// Until the split the groups and their fixtures are worked out in each
+/// simulated season. Once it has happened, the real groups are set with
+/// with_top_teams and the real post-split fixtures are played instead
+///
+/// Serializes to json as {"top_group": 6, "rounds_before_split": 3}, along
+/// with "top_teams" once the split has happened
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SplitLeague {
+    top_group: u32,
+    #[serde(default = "SplitLeague::default_rounds")]
+    rounds_before_split: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    top_teams: Option<Vec<String>>,
+}
+
+impl SplitLeague {
+    /// create a split with the given number of teams in the top group, made
+    /// once every team has met every other team three times
+    pub fn new(top_group: u32) -> Self {
+        Self {
+            top_group,
+            rounds_before_split: Self::default_rounds(),
+            top_teams: None,
+        }
+    }
+
+    /// Returns the number of times each team meets every other team before
+    /// the split when none is given
+    fn default_rounds() -> u32 {
+        3
+    }
+
+    /// create the Scottish Premiership's split into a top six and bottom six
+    pub fn scottish_premiership() -> Self {
+        Self::new(6)
+    }
+
+    /// Sets the teams in the top group, for a season already past the split,
+    /// so that the fixtures simulated are taken to be those after the split
+    pub fn with_top_teams<I, S>(mut self, teams: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let teams: Vec<String> = teams.into_iter().map(Into::into).collect();
+        self.top_group = teams.len() as u32;
+        self.top_teams = Some(teams);
+        self
+    }
+
+    /// Sets the number of times each team meets every other team before the split
+    pub fn with_rounds_before_split(mut self, rounds: u32) -> Self {
+        self.rounds_before_split = rounds;
+        self
+    }
+
+    /// Returns the number of teams in the top group
+    pub fn top_group(&self) -> u32 {
+        self.top_group
+    }
+
+    /// Returns the number of times each team meets every other team before the split
+    pub fn rounds_before_split(&self) -> u32 {
+        self.rounds_before_split
+    }
+
+    /// Returns the number of matches each team in the table plays before the split
+    pub fn matches_before_split(&self, table: &LeagueTable) -> u32 {
+        self.rounds_before_split * (table.len() as u32).saturating_sub(1)
+    }
+
+    /// Checks the table and remaining fixtures against the length of the
+    /// season as check_schedule does, where the fixtures run up to the split
+    /// until the top teams are set and to the end of the season after
+    pub fn check_schedule(
+        &self,
+        table: &LeagueTable,
+        fixtures: &[Match],
+    ) -> std::result::Result<(), ValidationError> {
+        let before_split = self.matches_before_split(table);
+        let Some(top) = &self.top_teams else {
+            return table.check_schedule(fixtures, before_split);
+        };
+        let bottom = (table.len() as u32).saturating_sub(top.len() as u32);
+        table.check_schedule_by(fixtures, |team| {
+            let group = if top.contains(&team.name) {
+                top.len() as u32
+            } else {
+                bottom
+            };
+            before_split + group.saturating_sub(1)
+        })
+    }
+
+    /// Returns the teams in the top group if the split has already happened
+    pub fn top_teams(&self) -> Option<&[String]> {
+        self.top_teams.as_deref()
+    }
+
+    /// Returns the names of the teams in the top and bottom groups, each in
+    /// rank order, of the table as it stands at the split
+    pub fn groups(&self, table_at_split: &LeagueTable) -> (Vec<String>, Vec<String>) {
+        let mut top: Vec<String> = table_at_split
+            .standings()
+            .iter()
+            .map(|team| team.name().to_string())
+            .collect();
+        let bottom = top.split_off((self.top_group as usize).min(top.len()));
+        (top, bottom)
+    }
+
+    /// Returns the fixtures played after the split, in which every team meets
+    /// every other team in its group once
+    pub fn split_fixtures(&self, top: &[String], bottom: &[String]) -> FixtureList {
+        let mut fixtures = FixtureList::round_robin(top.iter().cloned(), false);
+        fixtures.extend(FixtureList::round_robin(bottom.iter().cloned(), false));
+        fixtures
+    }
+
+    /// Returns the standings of the final table with the named top group
+    /// ahead of every other team, each group in rank order
+    pub fn final_standings(&self, final_table: &LeagueTable, top: &[String]) -> Standings {
+        let (mut above, below): (Vec<Team>, Vec<Team>) = final_table
+            .standings()
+            .iter()
+            .cloned()
+            .partition(|team| top.contains(&team.name));
+        above.extend(below);
+        Standings(above)
+    }
+
+    /// Plays out the rest of the season, returning the final standings
+    ///
+    /// Before the split the fixtures are the regular ones still to come, and
+    /// the split fixtures are generated once they are played. After the split,
+    /// with the top teams set, they are the real post-split fixtures left
+    ///
+    /// Panics if a fixture names a team that is not in the table
+    pub fn simulate(&self, current_table: &LeagueTable, fixtures: &[Match]) -> Standings {
+        self.simulate_with_rng(current_table, fixtures, &mut rand::rng())
+    }
+
+    /// Plays out the season as in simulate, drawing random numbers from the given generator
+    pub fn simulate_with_rng<R: Rng + ?Sized>(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        rng: &mut R,
+    ) -> Standings {
+        if let Some(top) = &self.top_teams {
+            let final_table = simulate_table_with_rng(current_table, fixtures, rng);
+            return self.final_standings(&final_table, top);
+        }
+        let at_split = simulate_table_with_rng(current_table, fixtures, rng);
+        let (top, bottom) = self.groups(&at_split);
+        let split_fixtures = self.split_fixtures(&top, &bottom);
+        let final_table = simulate_table_with_rng(&at_split, &split_fixtures, rng);
+        self.final_standings(&final_table, &top)
+    }
+
+    /// Plays out the season as in simulate, sampling every match from the
+    /// given model and drawing random numbers from the given generator
+    pub fn simulate_with_model<M, R>(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        model: &M,
+        rng: &mut R,
+    ) -> Standings
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        if let Some(top) = &self.top_teams {
+            let final_table = simulate_table_with_model(current_table, fixtures, model, rng);
+            return self.final_standings(&final_table, top);
+        }
+        let at_split = simulate_table_with_model(current_table, fixtures, model, rng);
+        let (top, bottom) = self.groups(&at_split);
+        let split_fixtures = self.split_fixtures(&top, &bottom);
+        let final_table = simulate_table_with_model(&at_split, &split_fixtures, model, rng);
+        self.final_standings(&final_table, &top)
+    }
+
+    /// Plays out the season as in simulate the given number of times and
+    /// returns the fraction of seasons in which the named team met the target
+    pub fn target_probability(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        target_team: &str,
+        target: &Target,
+        simulations: u32,
+    ) -> f64 {
+        self.target_probability_with_rng(
+            current_table,
+            fixtures,
+            target_team,
+            target,
+            simulations,
+            &mut rand::rng(),
+        )
+    }
+
+    /// Works out the chance of meeting the target as in target_probability,
+    /// drawing random numbers from the given generator
+    pub fn target_probability_with_rng<R: Rng + ?Sized>(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        target_team: &str,
+        target: &Target,
+        simulations: u32,
+        rng: &mut R,
+    ) -> f64 {
+        let met = (0..simulations)
+            .filter(|_| {
+                let standings = self.simulate_with_rng(current_table, fixtures, rng);
+                let Some(rank) = standings.rank_of(target_team) else {
+                    return false;
+                };
+                match target {
+                    Target::Rank(target_rank) => rank <= *target_rank as usize,
+                    Target::Above(rival) => standings.rank_of(rival).is_some_and(|r| rank < r),
+                }
+            })
+            .count();
+        met as f64 / f64::from(simulations.max(1))
+    }
+
+    /// Plays out the season as in simulate the given number of times and
+    /// returns the fraction of seasons in which every team finished in each
+    /// position, ordered by mean finishing position as in SimulationReport::rank_matrix
+    pub fn rank_matrix(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        simulations: u32,
+    ) -> Vec<RankProbabilities> {
+        self.rank_matrix_with_rng(current_table, fixtures, simulations, &mut rand::rng())
+    }
+
+    /// Builds the rank matrix as in rank_matrix, drawing random numbers from the given generator
+    pub fn rank_matrix_with_rng<R: Rng + ?Sized>(
+        &self,
+        current_table: &LeagueTable,
+        fixtures: &[Match],
+        simulations: u32,
+        rng: &mut R,
+    ) -> Vec<RankProbabilities> {
+        let teams = current_table.len();
+        let mut counts = vec![vec![0u64; teams]; teams];
+        for _ in 0..simulations {
+            let standings = self.simulate_with_rng(current_table, fixtures, rng);
+            for (rank, team) in standings.iter().enumerate() {
+                if let Some(id) = current_table.team_id(team.name()) {
+                    counts[id.index()][rank] += 1;
+                }
+            }
+        }
+        let count = f64::from(simulations.max(1));
+        let mut rows: Vec<(u64, RankProbabilities)> = current_table
+            .teams()
+            .zip(counts)
+            .map(|(team, counts)| {
+                let rank_total = counts.iter().zip(1..).map(|(n, rank)| n * rank).sum();
+                let row = RankProbabilities {
+                    name: team.name().to_string(),
+                    probabilities: counts.iter().map(|&n| n as f64 / count).collect(),
+                };
+                (rank_total, row)
+            })
+            .collect();
+        rows.sort_by_key(|(rank_total, _)| *rank_total);
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+}
+
+/// How a league's season is played out
+///
+/// Serializes to json as "round_robin", or as {"split": {...}} holding a
+/// SplitLeague. The default is a double round robin
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeagueFormat {
+    /// Every team meets every other team once at home and once away
+    #[default]
+    RoundRobin,
+    /// The league splits in two late in the season
+    Split(SplitLeague),
+}
+
+impl LeagueFormat {
+    /// Checks that every team's games played plus its remaining fixtures add
+    /// up to the length of the season in this format, as check_schedule does
+    pub fn check_schedule(
+        &self,
+        table: &LeagueTable,
+        fixtures: &[Match],
+    ) -> std::result::Result<(), ValidationError> {
+        match self {
+            Self::RoundRobin => table.check_schedule(fixtures, table.matches_per_season()),
+            Self::Split(split) => split.check_schedule(table, fixtures),
+        }
+    }
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Reading in data from files
+
+/// Names a league whose data files sit in a directory of their own, as in
+/// data/championship/standings.json and data/championship/fixtures.json
+///
+/// Ids are made of lowercase letters, digits and hyphens, so one taken from a
+/// request can never reach outside the data directory. The default is the
+/// Premier League, "premier-league"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String")]
+pub struct LeagueId(String);
+
+impl LeagueId {
+    /// create an id for the named league, or None if the name is empty or
+    /// holds anything but lowercase letters, digits and hyphens
+    pub fn new(id: &str) -> Option<Self> {
+        let valid = id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        (valid && !id.is_empty()).then(|| Self(id.to_string()))
+    }
+
+    /// Returns the id as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the path of one of the league's data files under the working directory
+    pub fn path(&self, file: &str) -> std::result::Result<PathBuf, LeagueError> {
+        default_path(&format!("{DATA_DIRECTORY}/{}/{file}", self.0))
+    }
+
+    /// Returns every league with a standings file in the data directory under
+    /// the working directory, in alphabetical order
+    pub fn available() -> std::result::Result<Vec<Self>, LeagueError> {
+        let mut leagues: Vec<Self> = std::fs::read_dir(default_path(DATA_DIRECTORY)?)?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let league = Self::new(entry.file_name().to_str()?)?;
+                entry
+                    .path()
+                    .join(STANDINGS_FILE)
+                    .is_file()
+                    .then_some(league)
+            })
+            .collect();
+        leagues.sort();
+        Ok(leagues)
+    }
+}
+
+impl Default for LeagueId {
+    fn default() -> Self {
+        Self(DEFAULT_LEAGUE.to_string())
+    }
+}
+
+impl TryFrom<String> for LeagueId {
+    type Error = String;
+
+    fn try_from(id: String) -> std::result::Result<Self, Self::Error> {
+        Self::new(&id).ok_or_else(|| format!("{id:?} is not a valid league id"))
+    }
+}
+
+impl fmt::Display for LeagueId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where a set of standings was read from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DataSource {
+    /// A json file on disk
+    File(PathBuf),
+    /// A web API, identified by the url fetched
+    Api(String),
+}
+
+impl fmt::Display for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Api(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+/// Records where a set of standings came from and when it was last updated,
+/// the file's modification time or the time of the API fetch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandingsSnapshot {
+    source: DataSource,
+    updated_at: SystemTime,
+}
+
+impl StandingsSnapshot {
+    /// create a snapshot of standings read from source as they stood at updated_at
+    pub fn new(source: DataSource, updated_at: SystemTime) -> Self {
+        Self { source, updated_at }
+    }
+
+    /// Returns where the standings were read from
+    pub fn source(&self) -> &DataSource {
+        &self.source
+    }
+
+    /// Returns when the standings were last updated
+    pub fn updated_at(&self) -> SystemTime {
+        self.updated_at
+    }
+
+    /// Returns how long ago the standings were last updated, or zero if
+    /// the update time is in the future
+    pub fn age(&self) -> Duration {
+        self.updated_at.elapsed().unwrap_or_default()
+    }
+
+    /// Returns true if the standings were last updated more than max_age ago
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age() > max_age
+    }
+}
+
+/// Function to read in a list of the remaining fixtures in the season
+/// from the default league's json file, data/premier-league/fixtures.json
+/// under the working directory, and return them in a FixtureList, as in
+/// read_fixtures_from
+pub fn read_fixtures() -> std::result::Result<FixtureList, LeagueError> {
+    read_league_fixtures(&LeagueId::default())
+}
+
+/// Reads the remaining fixtures of the given league from its fixtures.json,
+/// as in read_fixtures_from
+pub fn read_league_fixtures(league: &LeagueId) -> std::result::Result<FixtureList, LeagueError> {
+    read_fixtures_from(league.path(FIXTURES_FILE)?)
+}
+
+/// Reads the given league's format from the format.json in its directory, as
+/// in read_format_from, or the default format if it has no such file
+pub fn read_league_format(league: &LeagueId) -> std::result::Result<LeagueFormat, LeagueError> {
+    let path = league.path(FORMAT_FILE)?;
+    if path.is_file() {
+        read_format_from(path)
+    } else {
+        Ok(LeagueFormat::default())
+    }
+}
+
+/// Reads a league's format from the json file at the given path, in the form
+/// described on LeagueFormat
+///
+/// Fails with a LeagueError if the file cannot be read or does not hold a format
+pub fn read_format_from<P: AsRef<Path>>(path: P) -> std::result::Result<LeagueFormat, LeagueError> {
+    read_json(BufReader::new(File::open(path)?))
+}
+
+/// Reads the remaining fixtures in the season from the json file at the given
+/// path and returns them in a FixtureList
+/// 
+/// Json should take form of an array of objects, each containing two string literals
+/// labeled "home" and "away" as appropriate, and optionally a "kickoff" time in
+/// RFC 3339 format, a "matchday" number, a "venue" naming the ground and a
+/// "site" of "Home" or "Neutral"
+///
+/// Repeated fixtures are kept, for validate_fixtures to report. Fails with a
+/// LeagueError if the file cannot be read or does not hold fixtures in this form
+pub fn read_fixtures_from<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<FixtureList, LeagueError> {
+    read_json(BufReader::new(File::open(path)?))
+}
+
+/// Deserializes a json array of fixtures, in the format read by read_fixtures,
+/// from any reader
+///
+/// On failure the error names the position in the json of the offending value,
+/// such as the field of a fixture at a given array index
+pub fn parse_fixtures<R: Read>(reader: R) -> std::result::Result<FixtureList, FormatError> {
+    parse_json(reader)
+}
+
+/// Deserializes any json data, reporting the position of the offending value on failure
+fn parse_json<T: DeserializeOwned, R: Read>(reader: R) -> std::result::Result<T, FormatError> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|error| FormatError {
+        path: error.path().to_string(),
+        message: error.into_inner().to_string(),
+    })
+}
+
+/// Error returned when a data file does not match the expected json format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    path: String,
+    message: String,
+}
+
+impl FormatError {
+    /// Returns the position in the json of the offending value, such as "[3].home"
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value at {}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Error returned when a data file cannot be read into the crate's types
+#[derive(Debug)]
+pub enum LeagueError {
+    /// The file could not be found, opened or read
+    Io(std::io::Error),
+    /// The file is not well-formed json
+    Json(FormatError),
+    /// The file is json but does not take the expected form, such as a team
+    /// missing its "name" or a fixture with a misspelled field
+    Schema(FormatError),
+}
+
+impl fmt::Display for LeagueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read data file: {error}"),
+            Self::Json(error) => write!(f, "data file is not valid json: {error}"),
+            Self::Schema(error) => write!(f, "data file has the wrong form: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LeagueError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Json(error) | Self::Schema(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for LeagueError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Returns the full path of a default data file, given relative to the working directory
+fn default_path(relative: &str) -> std::result::Result<PathBuf, LeagueError> {
+    Ok(RelativePath::new(relative).to_path(current_dir()?))
+}
+
+/// Deserializes a data file, telling json that is malformed apart from json
+/// that does not match the expected types
+fn read_json<T: DeserializeOwned, R: Read>(reader: R) -> std::result::Result<T, LeagueError> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+        let path = error.path().to_string();
+        let error = error.into_inner();
+        if error.is_io() {
+            return LeagueError::Io(error.into());
+        }
+        let schema = error.is_data();
+        let error = FormatError {
+            path,
+            message: error.to_string(),
+        };
+        if schema {
+            LeagueError::Schema(error)
+        } else {
+            LeagueError::Json(error)
+        }
+    })
+}
+
+/// Function to read in the current standings of a league of any size from
+/// the default league's json file, data/premier-league/standings.json under
+/// the working directory, as in read_standings_from
+pub fn read_standings() -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    read_league_standings(&LeagueId::default())
+}
+
+/// Reads the current standings of the given league from its standings.json,
+/// as in read_standings_from
+pub fn read_league_standings(
+    league: &LeagueId,
+) -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    read_standings_from(league.path(STANDINGS_FILE)?)
+}
+
+/// Reads the current standings of a league of any size from the json file at
+/// the given path and stores them in a LeagueTable struct
+/// 
+/// Json file should take the form of an array of objects, each of which
+/// must take the form of a Team struct in order to be read: "name", "pts" and
+/// "goal_diff" are required, while "games_played", "wins", "draws", "losses",
+/// "goals_for" and "goals_against" may be omitted. A team with "goals_for" and
+/// "goals_against" may leave out "goal_diff", and one with "wins", "draws" and
+/// "losses" may leave out "games_played". Giving goals scored lets the goals
+/// scored tiebreak separate teams level on points and goal difference
+///
+/// Returns the table alongside a snapshot recording the file read and its
+/// modification time, or a LeagueError if the file cannot be read or parsed
+pub fn read_standings_from<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    let standings_full_path = path.as_ref().to_path_buf();
+    let file = File::open(&standings_full_path)?;
+    let updated_at = file
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or_else(|_| SystemTime::now());
+    let standings_data: Vec<Team> = read_json(BufReader::new(file))?;
+    let mut current_table = LeagueTable::new();
+    current_table.extend(standings_data);
+    let snapshot = StandingsSnapshot::new(DataSource::File(standings_full_path), updated_at);
+    Ok((current_table, snapshot))
+}
+
+/// Reads the standings from the json file at the given path as in
+/// read_standings_from, without blocking the calling thread
+///
+/// Must be awaited within a tokio runtime, such as the web server's
+pub async fn read_standings_from_async<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    let standings_full_path = path.as_ref().to_path_buf();
+    let contents = tokio::fs::read(&standings_full_path).await?;
+    let updated_at = tokio::fs::metadata(&standings_full_path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or_else(|_| SystemTime::now());
+    let standings_data: Vec<Team> = read_json(contents.as_slice())?;
+    let mut current_table = LeagueTable::new();
+    current_table.extend(standings_data);
+    let snapshot = StandingsSnapshot::new(DataSource::File(standings_full_path), updated_at);
+    Ok((current_table, snapshot))
+}
+
+/// Reads the fixtures from the json file at the given path as in
+/// read_fixtures_from, without blocking the calling thread
+///
+/// Must be awaited within a tokio runtime, such as the web server's
+pub async fn read_fixtures_from_async<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<FixtureList, LeagueError> {
+    read_json(tokio::fs::read(path).await?.as_slice())
+}
+
+/// Reads the current standings of the given league as in
+/// read_league_standings, without blocking the calling thread
+pub async fn read_league_standings_async(
+    league: &LeagueId,
+) -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    read_standings_from_async(league.path(STANDINGS_FILE)?).await
+}
+
+/// Reads the remaining fixtures of the given league as in
+/// read_league_fixtures, without blocking the calling thread
+pub async fn read_league_fixtures_async(
+    league: &LeagueId,
+) -> std::result::Result<FixtureList, LeagueError> {
+    read_fixtures_from_async(league.path(FIXTURES_FILE)?).await
+}
+
+/// Function to read in a list of matches already played this season from
+/// the default league's json file, data/premier-league/results.json under the
+/// working directory, as in read_results_from
+pub fn read_results() -> std::result::Result<Vec<MatchResult>, LeagueError> {
+    read_league_results(&LeagueId::default())
+}
+
+/// Reads the matches the given league has played so far from its
+/// results.json, as in read_results_from
+pub fn read_league_results(
+    league: &LeagueId,
+) -> std::result::Result<Vec<MatchResult>, LeagueError> {
+    read_results_from(league.path(RESULTS_FILE)?)
+}
+
+/// Reads a list of matches already played this season from the json file at
+/// the given path and returns the results in a vector
+///
+/// Json should take the form of an array of objects, each taking the form of a
+/// MatchResult: "home" and "away" names alongside "home_goals" and "away_goals"
+///
+/// A file with a .csv extension is read as csv instead, in the format
+/// described on parse_results_csv
+pub fn read_results_from<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<Vec<MatchResult>, LeagueError> {
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+    if path.extension().is_some_and(|extension| extension == "csv") {
+        parse_results_csv(reader).map_err(LeagueError::Schema)
+    } else {
+        read_json(reader)
+    }
+}
+
+/// Deserializes results from csv with a header row naming the columns "home",
+/// "away", "home_goals" and "away_goals", and optionally "matchday"
+///
+/// The path of any error is the line of the offending record, such as "line 3"
+pub fn parse_results_csv<R: Read>(reader: R) -> std::result::Result<Vec<MatchResult>, FormatError> {
+    csv::Reader::from_reader(reader)
+        .deserialize()
+        .map(|record| {
+            record.map(|record: ResultRecord| {
+                let fixture = Match::from(&record.home, &record.away);
+                let fixture = match record.matchday {
+                    Some(matchday) => fixture.with_matchday(matchday),
+                    None => fixture,
+                };
+                MatchResult::new(fixture, record.home_goals, record.away_goals)
+            })
+        })
+        .collect::<std::result::Result<_, csv::Error>>()
+        .map_err(|error| FormatError {
+            path: error
+                .position()
+                .map(|position| format!("line {}", position.line()))
+                .unwrap_or_default(),
+            message: error.to_string(),
+        })
+}
+
+/// One row of a results csv file
+#[derive(Deserialize)]
+struct ResultRecord {
+    home: String,
+    away: String,
+    home_goals: u32,
+    away_goals: u32,
+    #[serde(default)]
+    matchday: Option<u32>,
+}
+
+/// Works out the current standings from a file of every result so far, json
+/// or csv as read by read_results_from, so that no separate standings file
+/// has to be kept in step with the results
+///
+/// The table holds every team named in a result, in the order each first
+/// appears, so a team yet to play is left out. Returns the table alongside a
+/// snapshot recording the file read and its modification time
+pub fn read_standings_from_results<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+    let path = path.as_ref();
+    let results = read_results_from(path)?;
+    let updated_at = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or_else(|_| SystemTime::now());
+    let mut teams: Vec<&str> = Vec::new();
+    for result in &results {
+        for team in [result.fixture.home(), result.fixture.away()] {
+            if !teams.contains(&team) {
+                teams.push(team);
+            }
+        }
+    }
+    let table = LeagueTable::from_results(teams, &results)
+        .expect("every team named in the results should be in the table");
+    let snapshot = StandingsSnapshot::new(DataSource::File(path.to_path_buf()), updated_at);
+    Ok((table, snapshot))
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_one_team() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        assert_ne!(league_table.get_team("Liverpool"), None);
+        assert_eq!(
+            "Liverpool",
+            league_table.get_team("Liverpool").unwrap().name
+        );
+    }
+
+    #[test]
+    fn read_team_accessors() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+
+        let liverpool = league_table.get_team("Liverpool").unwrap();
+        assert_eq!("Liverpool", liverpool.name());
+        assert_eq!(67, liverpool.points());
+        assert_eq!(40, liverpool.goal_diff());
+        assert_eq!(None, league_table.get_team("Spurs"));
+        assert_eq!(2, league_table.teams().count());
+        assert_eq!(2, league_table.len());
+    }
+
+    #[test]
+    fn print_league_table() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 27, 28)
+            .unwrap();
+        league_table.print_table();
+    }
+
+    #[test]
+    fn print_reranked_league_table() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 27, 28)
+            .unwrap();
+        league_table.print_table();
+
+        let arsenal = league_table.team_id("Arsenal").unwrap();
+        league_table.teams[arsenal.index()].pts = 70;
+        league_table.print_table();
+    }
+
+    #[test]
+    fn manually_update_team_data() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        assert_ne!(league_table.get_team("Liverpool"), None);
+        assert_eq!(67, league_table.get_team("Liverpool").unwrap().pts);
+        assert_eq!(40, league_table.get_team("Liverpool").unwrap().goal_diff);
+    }
+
+    #[test]
+    fn update_with_match_data() {
+        let new_match = Match {
+            home: "Liverpool".to_string(),
+            away: "Arsenal".to_string(),
+            ..Match::default()
+        };
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 27, 26)
+            .unwrap();
+        league_table.update(&new_match, 2, 0);
+
+        assert_eq!(70, league_table.get_team("Liverpool").unwrap().pts);
+        assert_eq!(42, league_table.get_team("Liverpool").unwrap().goal_diff);
+
+        assert_eq!(27, league_table.get_team("Arsenal").unwrap().pts);
+        assert_eq!(24, league_table.get_team("Arsenal").unwrap().goal_diff);
+
+        let second_match = Match {
+            home: "Liverpool".to_string(),
+            away: "Arsenal".to_string(),
+            ..Match::default()
+        };
+        league_table.update(&second_match, 2, 2);
+
+        assert_eq!(71, league_table.get_team("Liverpool").unwrap().pts);
+        assert_eq!(42, league_table.get_team("Liverpool").unwrap().goal_diff);
+
+        assert_eq!(28, league_table.get_team("Arsenal").unwrap().pts);
+        assert_eq!(24, league_table.get_team("Arsenal").unwrap().goal_diff);
+    }
+
+    #[test]
+    fn update_tracks_match_record() {
+        let mut team = Team::new("Liverpool".to_string(), 67, 40);
+        team.update(2, 0);
+        team.update(1, 1);
+        team.update(0, 3);
+
+        assert_eq!(3, team.games_played());
+        assert_eq!((1, 1, 1), (team.wins(), team.draws(), team.losses()));
+        assert_eq!(3, team.goals_for());
+        assert_eq!(4, team.goals_against());
+        assert_eq!(39, team.goal_diff());
+        assert_eq!(71, team.points());
+    }
+
+    #[test]
+    fn deserialize_team_with_and_without_record() {
+        let minimal: Team =
+            serde_json::from_str(r#"{"name": "Spurs", "pts": 34, "goal_diff": 12}"#).unwrap();
+        assert_eq!(Team::new("Spurs".to_string(), 34, 12), minimal);
+
+        let full: Team = serde_json::from_str(
+            r#"{"name": "Spurs", "pts": 34, "goal_diff": 12, "games_played": 29,
+                "wins": 10, "draws": 4, "losses": 15, "goals_for": 55, "goals_against": 43}"#,
+        )
+        .unwrap();
+        assert_eq!(29, full.games_played());
+        assert_eq!(15, full.losses());
+        assert_eq!(55, full.goals_for());
+        let json = serde_json::to_string(&full).unwrap();
+        assert_eq!(full, serde_json::from_str(&json).unwrap());
+
+        // the totals can be worked out from a full record
+        let derived: Team = serde_json::from_str(
+            r#"{"name": "Spurs", "pts": 34, "wins": 10, "draws": 4, "losses": 15,
+                "goals_for": 55, "goals_against": 43}"#,
+        )
+        .unwrap();
+        assert_eq!(full, derived);
+        let error = read_json::<Vec<Team>, _>(
+            r#"[{"name": "Spurs", "pts": 34, "goals_for": 55}]"#.as_bytes(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, LeagueError::Schema(_)), "{error}");
+        assert!(error.to_string().contains("goal_diff"));
+    }
+
+    #[test]
+    fn get_final_ranking() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+
+        let liverpool_rank = league_table.find_final_rank("Liverpool");
+        let arsenal_rank = league_table.find_final_rank("Arsenal");
+
+        assert_eq!(1, liverpool_rank);
+        assert_eq!(2, arsenal_rank);
+    }
+
+    #[test]
+    fn ordered_standings() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table
+            .add_team("Chelsea".to_string(), 54, 30)
+            .unwrap();
+
+        let standings = league_table.standings();
+        assert_eq!(3, standings.len());
+        assert_eq!("Liverpool", standings[1].name());
+        assert_eq!("Chelsea", standings[2].name());
+        assert_eq!("Arsenal", standings[3].name());
+        assert_eq!(None, standings.get(0));
+        assert_eq!(None, standings.get(4));
+        assert_eq!(Some(3), standings.rank_of("Arsenal"));
+        assert_eq!(54, standings.find("Arsenal").unwrap().points());
+        assert!(standings.to_string().starts_with("Rank"));
+    }
+
+    #[test]
+    fn configured_tiebreakers() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team_struct(
+                "Everton".to_string(),
+                Team {
+                    goals_for: 32,
+                    ..Team::new("Everton".to_string(), 34, 4)
+                },
+            )
+            .unwrap();
+        league_table
+            .add_team_struct(
+                "Spurs".to_string(),
+                Team {
+                    goals_for: 55,
+                    ..Team::new("Spurs".to_string(), 34, 4)
+                },
+            )
+            .unwrap();
+        league_table
+            .add_team("West Ham".to_string(), 34, 5)
+            .unwrap();
+
+        assert_eq!(1, league_table.find_final_rank("West Ham"));
+        assert_eq!(2, league_table.find_final_rank("Spurs"));
+        assert_eq!(3, league_table.find_final_rank("Everton"));
+
+        league_table.set_tiebreakers(Tiebreakers::new(vec![TiebreakRule::Alphabetical]));
+        assert_eq!(1, league_table.find_final_rank("Everton"));
+        assert_eq!(2, league_table.find_final_rank("Spurs"));
+        assert_eq!(3, league_table.find_final_rank("West Ham"));
+    }
+
+    #[test]
+    fn head_to_head_tiebreaker() {
+        let mut league_table = LeagueTable::with_tiebreakers(Tiebreakers::la_liga());
+        league_table
+            .add_team("Barcelona".to_string(), 10, 5)
+            .unwrap();
+        league_table
+            .add_team("Real Madrid".to_string(), 7, -5)
+            .unwrap();
+        league_table.add_team("Girona".to_string(), 0, 0).unwrap();
+        league_table.update(&Match::from("Barcelona", "Real Madrid"), 0, 1);
+
+        assert_eq!(
+            &[(0, 1)],
+            league_table.head_to_head("Barcelona", "Real Madrid")
+        );
+        assert!(league_table
+            .head_to_head("Real Madrid", "Barcelona")
+            .is_empty());
+        assert_eq!(1, league_table.find_final_rank("Real Madrid"));
+        assert_eq!(2, league_table.find_final_rank("Barcelona"));
+
+        league_table.set_tiebreakers(Tiebreakers::premier_league());
+        assert_eq!(1, league_table.find_final_rank("Barcelona"));
+        assert_eq!(2, league_table.find_final_rank("Real Madrid"));
+    }
+
+    #[test]
+    fn validate_fixture_list() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+
+        let valid = [
+            Match::from("Liverpool", "Arsenal"),
+            Match::from("Arsenal", "Liverpool"),
+        ];
+        assert_eq!(Ok(()), league_table.validate_fixtures(&valid));
+
+        let invalid = [
+            Match::from("Liverpool", "Arsenal"),
+            Match::from("Liverpool", "Arsenal"),
+            Match::from("Arsenal", "Arsenal"),
+            Match::from("Spurs", "Arsenal"),
+        ];
+        let error = league_table.validate_fixtures(&invalid).unwrap_err();
+        assert_eq!(
+            &[
+                FixtureIssue::DuplicateFixture(Match::from("Liverpool", "Arsenal")),
+                FixtureIssue::SameTeam(Match::from("Arsenal", "Arsenal")),
+                FixtureIssue::UnknownTeam("Spurs".to_string()),
+                FixtureIssue::TooManyFixtures {
+                    team: "Arsenal".to_string(),
+                    remaining: 5,
+                    max: 2
+                },
+            ],
+            error.issues()
+        );
+    }
+
+    #[test]
+    fn validate_real_fixtures() {
+        let (current_table, _) = read_standings().unwrap();
+        let fixtures = read_fixtures().unwrap();
+        assert_eq!(Ok(()), current_table.validate_fixtures(&fixtures));
+        assert_eq!(Ok(()), current_table.check_schedule(&fixtures, 38));
+    }
+
+    #[test]
+    fn duplicate_team_policies() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table.add_team("Arsenal".to_string(), 3, 2).unwrap();
+        assert_eq!(3, league_table.get_team("Arsenal").unwrap().points());
+
+        league_table.set_duplicate_policy(DuplicatePolicy::Merge);
+        league_table.add_team("Arsenal".to_string(), 3, 2).unwrap();
+        assert_eq!(6, league_table.get_team("Arsenal").unwrap().points());
+        assert_eq!(4, league_table.get_team("Arsenal").unwrap().goal_diff());
+
+        league_table.set_duplicate_policy(DuplicatePolicy::Error);
+        assert_eq!(
+            Err(TableError::DuplicateTeam("Arsenal".to_string())),
+            league_table.add_team("Arsenal".to_string(), 3, 2)
+        );
+        assert_eq!(6, league_table.get_team("Arsenal").unwrap().points());
+    }
+
+    #[test]
+    fn remove_and_rename_teams() {
+        let mut league_table = LeagueTable::with_tiebreakers(Tiebreakers::la_liga());
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenall".to_string(), 54, 28)
+            .unwrap();
+        league_table.add_team("Spurs".to_string(), 34, 12).unwrap();
+        league_table.update(&Match::from("Liverpool", "Arsenall"), 1, 1);
+        let mut fixtures = vec![
+            Match::from("Arsenall", "Spurs"),
+            Match::from("Spurs", "Liverpool"),
+        ];
+
+        league_table
+            .rename_team("Arsenall", "Arsenal", &mut fixtures)
+            .unwrap();
+        assert_eq!(None, league_table.get_team("Arsenall"));
+        assert_eq!("Arsenal", league_table.get_team("Arsenal").unwrap().name());
+        assert_eq!(&[(1, 1)], league_table.head_to_head("Liverpool", "Arsenal"));
+        assert_eq!(Match::from("Arsenal", "Spurs"), fixtures[0]);
+        assert_eq!(Match::from("Spurs", "Liverpool"), fixtures[1]);
+        assert_eq!(
+            Err(TableError::UnknownTeam("Arsenall".to_string())),
+            league_table.rename_team("Arsenall", "Arsenal", &mut fixtures)
+        );
+        assert_eq!(
+            Err(TableError::DuplicateTeam("Spurs".to_string())),
+            league_table.rename_team("Arsenal", "Spurs", &mut fixtures)
+        );
+
+        let removed = league_table.remove_team("Liverpool").unwrap();
+        assert_eq!("Liverpool", removed.name());
+        assert_eq!(2, league_table.len());
+        assert!(league_table.head_to_head("Liverpool", "Arsenal").is_empty());
+        assert_eq!(None, league_table.remove_team("Liverpool"));
+    }
+
+    #[test]
+    fn collect_and_extend_table() {
+        let mut league_table: LeagueTable = vec![
+            Team::new("Liverpool".to_string(), 67, 40),
+            Team::new("Arsenal".to_string(), 54, 28),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(2, league_table.len());
+
+        league_table.extend([
+            Team::new("Forest".to_string(), 48, 18),
+            Team::new("Arsenal".to_string(), 57, 29),
+        ]);
+        assert_eq!(3, league_table.len());
+        assert_eq!(57, league_table.get_team("Arsenal").unwrap().points());
+
+        let total_points: u32 = (&league_table).into_iter().map(Team::points).sum();
+        assert_eq!(172, total_points);
+
+        let mut names: Vec<String> = league_table
+            .into_iter()
+            .map(|team| team.name().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(vec!["Arsenal", "Forest", "Liverpool"], names);
+    }
+
+    #[test]
+    fn render_table_formats() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Wolves".to_string(), 25, -18)
+            .unwrap();
+        league_table
+            .add_team("Brighton & Hove Albion".to_string(), 47, 6)
+            .unwrap();
+
+        let plain = league_table.to_string();
+        assert_eq!(
+            concat!(
+                "Rank  Team                    P  W  D  L  GF  GA   GD  Pts\n",
+                "   1  Brighton & Hove Albion  0  0  0  0   0   0    6   47\n",
+                "   2  Wolves                  0  0  0  0   0   0  -18   25\n",
+            ),
+            plain
+        );
+
+        let markdown = league_table.render(TableFormat::Markdown);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(4, lines.len());
+        assert_eq!(
+            "| ---: | :--------------------- | -: | -: | -: | -: | -: | -: | --: | --: |",
+            lines[1]
+        );
+        assert!(lines[3].starts_with("|    2 | Wolves                 |"));
+
+        league_table
+            .add_team("Forest, Nottm".to_string(), 54, 14)
+            .unwrap();
+        assert_eq!(
+            "Rank,Team,P,W,D,L,GF,GA,GD,Pts\n\
+             1,\"Forest, Nottm\",0,0,0,0,0,0,14,54\n\
+             2,Brighton & Hove Albion,0,0,0,0,0,0,6,47\n\
+             3,Wolves,0,0,0,0,0,0,-18,25\n",
+            league_table.render(TableFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn round_trip_json() {
+        let fixtures = vec![
+            Match::from("Wolves", "West Ham"),
+            Match::from("Arsenal", "Fulham"),
+        ];
+        let json = serde_json::to_string(&fixtures).unwrap();
+        assert_eq!(
+            r#"[{"home":"Wolves","away":"West Ham"},{"home":"Arsenal","away":"Fulham"}]"#,
+            json
+        );
+        assert_eq!(fixtures, serde_json::from_str::<Vec<Match>>(&json).unwrap());
+
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        let json = serde_json::to_value(&league_table).unwrap();
+        assert_eq!("Liverpool", json[0]["name"]);
+        assert_eq!(54, json[1]["pts"]);
+
+        let round_tripped: LeagueTable = serde_json::from_value(json).unwrap();
+        assert_eq!(league_table.standings(), round_tripped.standings());
+    }
+
+    #[test]
+    fn get_all_ranks() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table.add_team("Forest".to_string(), 54, 14).unwrap();
+
+        let ranks = league_table.ranks();
+        assert_eq!(3, ranks.len());
+        assert_eq!(1, ranks["Liverpool"]);
+        assert_eq!(2, ranks["Arsenal"]);
+        assert_eq!(3, ranks["Forest"]);
+    }
+
+    #[test]
+    fn check_schedule_length() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team_struct(
+                "Liverpool".to_string(),
+                Team {
+                    games_played: 1,
+                    ..Team::new("Liverpool".to_string(), 3, 2)
+                },
+            )
+            .unwrap();
+        league_table
+            .add_team_struct(
+                "Arsenal".to_string(),
+                Team {
+                    games_played: 1,
+                    ..Team::new("Arsenal".to_string(), 0, -2)
+                },
+            )
+            .unwrap();
+
+        let fixtures = [Match::from("Arsenal", "Liverpool")];
+        assert_eq!(Ok(()), league_table.check_schedule(&fixtures, 2));
+
+        let error = league_table.check_schedule(&fixtures, 3).unwrap_err();
+        assert_eq!(2, error.issues().len());
+        assert_eq!(
+            FixtureIssue::ScheduleMismatch {
+                team: "Arsenal".to_string(),
+                played: 1,
+                remaining: 1,
+                expected: 3
+            },
+            error.issues()[0]
+        );
+    }
+
+    #[test]
+    fn diff_against_final_table() {
+        let mut current_table = LeagueTable::new();
+        current_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        current_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        current_table
+            .add_team("Brighton".to_string(), 47, 6)
+            .unwrap();
+
+        let mut final_table = current_table.clone();
+        final_table.update(&Match::from("Brighton", "Arsenal"), 3, 0);
+        final_table.update(&Match::from("Brighton", "Liverpool"), 2, 1);
+        final_table.update(&Match::from("Arsenal", "Liverpool"), 1, 1);
+
+        let diff = current_table.diff(&final_table);
+        let ranks: Vec<&str> = diff.iter().map(TeamChange::name).collect();
+        assert_eq!(vec!["Liverpool", "Arsenal", "Brighton"], ranks);
+
+        let brighton = diff.get("Brighton").unwrap();
+        assert_eq!(6, brighton.points_delta());
+        assert_eq!(4, brighton.goal_diff_delta());
+        assert_eq!(0, brighton.positions_gained());
+        assert_eq!("Brighton stays 3rd", brighton.to_string());
+
+        final_table.update(&Match::from("Brighton", "Arsenal"), 2, 0);
+        let diff = current_table.diff(&final_table);
+        assert_eq!(
+            "Brighton climbs from 3rd to 2nd",
+            diff.get("Brighton").unwrap().to_string()
+        );
+        assert_eq!(
+            "Arsenal drops from 2nd to 3rd",
+            diff.get("Arsenal").unwrap().to_string()
+        );
+        assert_eq!("112th", ordinal(112));
+        assert_eq!("21st", ordinal(21));
+    }
+
+    #[test]
+    fn table_from_results() {
+        let results: Vec<MatchResult> = serde_json::from_str(
+            r#"[
+                {"home": "Liverpool", "away": "Arsenal", "home_goals": 2, "away_goals": 2},
+                {"home": "Arsenal", "away": "Spurs", "home_goals": 2, "away_goals": 1},
+                {"home": "Spurs", "away": "Liverpool", "home_goals": 3, "away_goals": 6}
+            ]"#,
+        )
+        .unwrap();
+        let league_table =
+            LeagueTable::from_results(["Liverpool", "Arsenal", "Spurs"], &results).unwrap();
+
+        let liverpool = league_table.get_team("Liverpool").unwrap();
+        assert_eq!(4, liverpool.points());
+        assert_eq!(
+            (1, 1, 0),
+            (liverpool.wins(), liverpool.draws(), liverpool.losses())
+        );
+        assert_eq!(8, liverpool.goals_for());
+        assert_eq!(3, liverpool.goal_diff());
+        assert_eq!(4, league_table.get_team("Arsenal").unwrap().points());
+        assert_eq!(-4, league_table.get_team("Spurs").unwrap().goal_diff());
+        assert_eq!(2, league_table.get_team("Spurs").unwrap().games_played());
+
+        assert_eq!(
+            Err(TableError::UnknownTeam("Spurs".to_string())),
+            LeagueTable::from_results(["Liverpool", "Arsenal"], &results).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn intern_team_ids() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table.add_team("Forest".to_string(), 48, 18).unwrap();
+
+        let arsenal = league_table.team_id("Arsenal").unwrap();
+        let fixture = league_table
+            .resolve(&Match::from("Forest", "Arsenal"))
+            .unwrap();
+        assert_eq!(arsenal, fixture.away());
+        assert_eq!(
+            Err(TableError::UnknownTeam("Spurs".to_string())),
+            league_table.resolve(&Match::from("Spurs", "Arsenal"))
+        );
+
+        let mut simulated_table = league_table.clone();
+        simulated_table.update_resolved(fixture, 5, 0);
+        simulated_table.update_resolved(fixture, 3, 0);
+        assert_eq!(
+            "Forest",
+            simulated_table
+                .get_team_by_id(fixture.home())
+                .unwrap()
+                .name()
+        );
+        assert_eq!(2, simulated_table.rank_by_id(fixture.home()));
+        assert_eq!(3, simulated_table.rank_by_id(arsenal));
+
+        league_table.remove_team("Liverpool").unwrap();
+        assert_eq!(TeamId(0), league_table.team_id("Arsenal").unwrap());
+        assert_eq!(
+            "Forest",
+            league_table.get_team_by_id(TeamId(1)).unwrap().name()
+        );
+        assert_eq!(None, league_table.get_team_by_id(TeamId(2)));
+    }
+
+    #[test]
+    fn alternative_points_schemes() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 0, 0)
+            .unwrap();
+        league_table.add_team("Everton".to_string(), 0, 0).unwrap();
+        league_table.set_points_scheme(PointsScheme::two_for_a_win());
+        league_table.update(&Match::from("Liverpool", "Everton"), 2, 0);
+        league_table.update(&Match::from("Everton", "Liverpool"), 1, 1);
+        assert_eq!(3, league_table.get_team("Liverpool").unwrap().points());
+        assert_eq!(1, league_table.get_team("Everton").unwrap().points());
+
+        league_table.set_points_scheme(PointsScheme {
+            win: 4,
+            draw: 2,
+            loss: 1,
+        });
+        league_table.update(&Match::from("Liverpool", "Everton"), 0, 1);
+        assert_eq!(4, league_table.get_team("Liverpool").unwrap().points());
+        assert_eq!(5, league_table.get_team("Everton").unwrap().points());
+    }
+
+    #[test]
+    fn league_size_agnostic_season() {
+        let mut league_table: LeagueTable = ["Celtic", "Rangers", "Hearts", "Hibernian"]
+            .into_iter()
+            .map(|name| Team::new(name.to_string(), 0, 0))
+            .collect();
+        assert_eq!(6, league_table.matches_per_season());
+
+        let fixtures = FixtureList::round_robin(["Celtic", "Rangers", "Hearts", "Hibernian"], true);
+        assert_eq!(Ok(()), league_table.validate_fixtures(&fixtures));
+        let matches_per_season = league_table.matches_per_season();
+        assert_eq!(
+            Ok(()),
+            league_table.check_schedule(&fixtures, matches_per_season)
+        );
+
+        league_table.add_team("Aberdeen".to_string(), 0, 0).unwrap();
+        assert_eq!(8, league_table.matches_per_season());
+    }
+
+    #[test]
+    fn zones_for_finishing_positions() {
+        let zones = Zones::premier_league();
+        let names = |rank| {
+            zones
+                .zones_for(rank)
+                .map(|zone| zone.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["Champions", "Champions League"], names(1));
+        assert_eq!(vec!["Champions League"], names(4));
+        assert_eq!(vec!["Europa League"], names(5));
+        assert!(names(10).is_empty());
+        assert_eq!(vec!["Relegation"], names(19));
+        assert_eq!(Some(18), zones.get("Relegation").map(Zone::first));
+
+        let mut league_table = LeagueTable::new();
+        assert!(league_table.zones().iter().next().is_none());
+        league_table.add_team("Celtic".to_string(), 80, 50).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 75, 40)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 50, 0).unwrap();
+        league_table.set_zones(Zones::new(vec![
+            Zone::new("Champions", 1, 1),
+            Zone::new("Relegation", 3, 3),
+        ]));
+        assert_eq!(
+            vec![&Zone::new("Champions", 1, 1)],
+            league_table.zones_of("Celtic")
+        );
+        assert!(league_table.zones_of("Rangers").is_empty());
+        assert_eq!(
+            vec![&Zone::new("Relegation", 3, 3)],
+            league_table.zones_of("Hearts")
+        );
+        assert!(league_table.zones_of("Aberdeen").is_empty());
+    }
+
+    #[test]
+    fn expected_table_from_batch() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 30, -20)
+            .unwrap();
+        league_table
+            .add_team("Hearts".to_string(), 10, -40)
+            .unwrap();
+        let fixtures = vec![Match::from("Rangers", "Hearts")];
+
+        let mut report = simulate_batch(&league_table, &fixtures, 50);
+        assert_eq!(50, report.simulations());
+        let expected = report.expected_table();
+        let names: Vec<&str> = expected.iter().map(ExpectedStanding::name).collect();
+        assert_eq!(vec!["Celtic", "Rangers", "Hearts"], names);
+        assert_eq!(90.0, expected[0].points());
+        assert_eq!(60.0, expected[0].goal_diff());
+        assert_eq!(1.0, expected[0].position());
+        assert!((30.0..=33.0).contains(&expected[1].points()));
+        assert_eq!(2.0, expected[1].position());
+
+        report
+            .merge(&simulate_batch(&league_table, &fixtures, 25))
+            .unwrap();
+        assert_eq!(75, report.simulations());
+        assert_eq!(3.0, report.expected_table()[2].position());
+        // reports for other teams, or tracking other key fixtures, are refused
+        let mut other = league_table.clone();
+        other.add_team("Hibs".to_string(), 40, 0).unwrap();
+        assert_eq!(
+            Err(MergeError::DifferentTeams),
+            report.merge(&SimulationReport::new(&other))
+        );
+        let tracked =
+            SimulationReport::new(&league_table).with_key_fixtures("Rangers", 2, &fixtures);
+        assert_eq!(
+            Err(MergeError::DifferentKeyFixtures),
+            report.merge(&tracked)
+        );
+        assert_eq!(75, report.simulations());
+        assert!(SimulationReport::new(&league_table)
+            .expected_table()
+            .is_empty());
+    }
+
+    #[test]
+    fn threaded_batch_statistics() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 31, -20)
+            .unwrap();
+        league_table
+            .add_team("Hearts".to_string(), 30, -40)
+            .unwrap();
+        let fixtures = vec![Match::from("Rangers", "Hearts")];
+
+        // 101 simulations do not split evenly over 4 threads
+        let config = SimulationConfig::new(101).with_threads(4);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(101, report.simulations());
+        assert_eq!(101, report.successes("Celtic", 1));
+        assert_eq!(1.0, report.probability("Hearts", 3));
+        assert_eq!(0, report.successes("Aberdeen", 3));
+        let rangers = report.rank_distribution("Rangers").unwrap();
+        assert_eq!(0.0, rangers[0]);
+        assert!(rangers[1] > 0.5 && (rangers[1] + rangers[2] - 1.0).abs() < 1e-9);
+        assert_eq!(
+            report.probability("Rangers", 2),
+            report.successes("Rangers", 2) as f64 / 101.0
+        );
+
+        let expected = report.expected_table();
+        assert_eq!(0.0, expected[0].wins());
+        let rangers = &expected[1];
+        assert!((rangers.wins() + rangers.draws() + rangers.losses() - 1.0).abs() < 1e-9);
+        assert_eq!(16000, SimulationConfig::default().simulations());
+        assert_eq!(0, SimulationConfig::default().threads());
+        // batches handed the same pool share its threads
+        let pool = SimulationPool::new(4);
+        assert_eq!(4, pool.threads());
+        let pooled = SimulationConfig::new(50).with_pool(pool.clone());
+        assert_eq!(Some(&pool), pooled.pool());
+        assert_ne!(Some(&SimulationPool::new(4)), pooled.pool());
+        assert_eq!(
+            50,
+            run_simulations(&pooled, &league_table, &fixtures).simulations()
+        );
+
+        let calls = std::sync::Mutex::new(Vec::new());
+        let config = config.with_progress_interval(25);
+        run_simulations_with_progress(&config, &league_table, &fixtures, |done, total| {
+            calls.lock().unwrap().push((done, total));
+        });
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort();
+        assert_eq!(
+            vec![(25, 101), (50, 101), (75, 101), (100, 101), (101, 101)],
+            calls
+        );
+
+        // cancelling part way through keeps the simulations already completed
+        let handle = CancellationHandle::new();
+        let config = config.with_threads(1).with_cancellation(handle.clone());
+        let partial =
+            run_simulations_with_progress(&config, &league_table, &fixtures, |done, _| {
+                if done == 50 {
+                    handle.cancel();
+                }
+            });
+        assert!(config.is_cancelled());
+        assert!(report.standard_error("Rangers", 2) > 0.0);
+        assert_eq!(
+            f64::INFINITY,
+            SimulationReport::new(&league_table).standard_error("Rangers", 2)
+        );
+        assert_eq!(50, partial.simulations());
+        assert_eq!(50, partial.successes("Celtic", 1));
+
+        // a batch stopped early reports where it stopped
+        let calls = std::sync::Mutex::new(Vec::new());
+        run_simulations_with_progress(&config, &league_table, &fixtures, |done, total| {
+            calls.lock().unwrap().push((done, total));
+        });
+        assert_eq!(vec![(0, 101)], calls.into_inner().unwrap());
+    }
+
+    #[test]
+    fn streamed_outcomes() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 79, 30)
+            .unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+
+        let config = SimulationConfig::new(300).with_threads(2);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let report = stream_simulations(&config, &league_table, &fixtures, "Leeds", sender);
+        let outcomes: Vec<SimOutcome> = receiver.iter().collect();
+        assert_eq!(300, outcomes.len());
+        let top = outcomes.iter().filter(|outcome| outcome.final_rank() == 1);
+        assert_eq!(report.successes("Leeds", 1), top.count() as u64);
+        assert!(outcomes.iter().all(|outcome| outcome.points() >= 80));
+
+        // a receiver that has gone away does not stop the batch
+        let (sender, receiver) = std::sync::mpsc::channel();
+        drop(receiver);
+        let report = stream_simulations(&config, &league_table, &fixtures, "Leeds", sender);
+        assert_eq!(300, report.simulations());
+    }
+
+    #[test]
+    fn report_json_files() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 79, 30)
+            .unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+        let config = SimulationConfig::new(200).with_key_fixtures("Leeds", 1);
+        let report = run_simulations(&config, &league_table, &fixtures);
+
+        let path = std::env::temp_dir().join(format!("report-{}.json", std::process::id()));
+        report.write_json(&path).unwrap();
+        let saved = SimulationReport::read_json(&path).unwrap();
+        assert_eq!(report, saved);
+        assert_eq!(
+            report.probability("Leeds", 1),
+            saved.probability("Leeds", 1)
+        );
+
+        std::fs::write(&path, r#"{"simulations": 10}"#).unwrap();
+        let error = SimulationReport::read_json(&path).unwrap_err();
+        assert!(matches!(error, LeagueError::Schema(_)), "{error}");
+
+        // the target team is saved by name, and a report whose counts do not
+        // cover every team is refused rather than read in to panic later
+        let mut json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!("Leeds", json["key_fixtures"]["team"]);
+        json["key_fixtures"]["team"] = "Hull".into();
+        std::fs::write(&path, json.to_string()).unwrap();
+        let error = SimulationReport::read_json(&path).unwrap_err();
+        assert!(error.to_string().contains("Hull"), "{error}");
+        let mut json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        json["totals"][1]["rank_counts"] = serde_json::json!([200]);
+        std::fs::write(&path, json.to_string()).unwrap();
+        let error = SimulationReport::read_json(&path).unwrap_err();
+        assert!(matches!(error, LeagueError::Schema(_)), "{error}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rank_probability_matrix() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Hearts".to_string(), 10, -40)
+            .unwrap();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 31, -20)
+            .unwrap();
+        league_table
+            .add_team("Hibernian".to_string(), 30, -30)
+            .unwrap();
+        let fixtures = vec![Match::from("Rangers", "Hibernian")];
+
+        let report = simulate_batch(&league_table, &fixtures, 200);
+        let matrix = report.rank_matrix();
+        let names: Vec<&str> = matrix.iter().map(RankProbabilities::name).collect();
+        assert_eq!(vec!["Celtic", "Rangers", "Hibernian", "Hearts"], names);
+        assert_eq!(&[1.0, 0.0, 0.0, 0.0], matrix[0].probabilities());
+        for row in &matrix {
+            assert!((row.probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(
+            Some(matrix[1].probabilities().to_vec()),
+            report.rank_distribution("Rangers")
+        );
+
+        let json = serde_json::to_value(&matrix).unwrap();
+        assert_eq!("Hearts", json[3]["name"]);
+        assert_eq!(
+            serde_json::json!([0.0, 0.0, 0.0, 1.0]),
+            json[3]["probabilities"]
+        );
+    }
+
+    #[test]
+    fn zone_outcome_summary() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 31, -20)
+            .unwrap();
+        league_table
+            .add_team("Hibernian".to_string(), 30, -30)
+            .unwrap();
+        league_table
+            .add_team("Hearts".to_string(), 10, -40)
+            .unwrap();
+        let fixtures = vec![Match::from("Rangers", "Hibernian")];
+
+        // a table without zones is summarised with a champion and the bottom three relegated
+        let summary = simulate_batch(&league_table, &fixtures, 100).summary();
+        assert_eq!("Celtic", summary[0].name());
+        assert_eq!(1.0, summary[0].probability("Champions"));
+        assert_eq!(0.0, summary[0].probability("Relegation"));
+        assert_eq!(1.0, summary[3].probability("Relegation"));
+        assert_eq!(0.0, summary[3].probability("Champions League"));
+        assert_eq!(2, summary[0].zones().len());
+        assert_eq!(1, Zones::for_teams(3).iter().count());
+        assert_eq!(
+            Some(18),
+            Zones::for_teams(20).get("Relegation").map(Zone::first)
+        );
+
+        league_table.set_zones(Zones::new(vec![
+            Zone::new("Champions", 1, 1),
+            Zone::new("Europe", 2, 3),
+            Zone::new("Relegation", 4, 4),
+        ]));
+        let report = simulate_batch(&league_table, &fixtures, 100);
+        let summary = report.summary();
+        assert_eq!(1.0, summary[1].probability("Europe"));
+        assert_eq!(1.0, summary[3].probability("Relegation"));
+        assert_eq!(0.0, summary[3].probability("Promotion"));
+        let rangers = report.zone_probabilities(&Zones::new(vec![Zone::new("Second", 2, 2)]));
+        let rangers = rangers.iter().find(|row| row.name() == "Rangers").unwrap();
+        assert_eq!(
+            report.probability("Rangers", 2),
+            rangers.probability("Second")
+        );
+    }
+
+    #[test]
+    fn points_percentile_bands() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 30, -20)
+            .unwrap();
+        let fixtures = FixtureList::round_robin(["Celtic", "Rangers"], true);
+
+        let mut report = simulate_batch(&league_table, &fixtures, 200);
+        report
+            .merge(&simulate_batch(&league_table, &fixtures, 200))
+            .unwrap();
+        let expected = report.expected_table();
+        let (low, high) = expected[0].points_band();
+        assert!(90 <= low && low <= expected[0].median_points());
+        assert!(expected[0].median_points() <= high && high <= 96);
+        assert!(f64::from(low) <= expected[0].points() && expected[0].points() <= f64::from(high));
+
+        // with every result pinned there is no spread at all
+        let mut pinned = fixtures.clone();
+        pinned.force_result("Celtic", "Rangers", 1, 1);
+        pinned.force_result("Rangers", "Celtic", 2, 0);
+        let expected = simulate_batch(&league_table, &pinned, 20).expected_table();
+        assert_eq!((91, 91), expected[0].points_band());
+        assert_eq!(34, expected[1].median_points());
+    }
+
+    #[test]
+    fn points_needed_for_rank() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 50, -20)
+            .unwrap();
+        league_table
+            .add_team("Hibernian".to_string(), 49, -30)
+            .unwrap();
+        let mut fixtures = FixtureList::round_robin(["Rangers", "Hibernian"], true);
+        fixtures.force_result("Rangers", "Hibernian", 1, 0);
+
+        // Rangers finish second on 53, 54 or 56 points, depending on the one
+        // match left to play
+        let mut report = simulate_batch(&league_table, &fixtures, 100);
+        report
+            .merge(&simulate_batch(&league_table, &fixtures, 100))
+            .unwrap();
+        assert_eq!(Some(90), report.points_needed(1, 0.9));
+        let needed = report.points_needed(2, 0.9).unwrap();
+        assert!([54, 56].contains(&needed), "{needed}");
+        assert_eq!(Some(53), report.points_needed(2, 0.0));
+        assert_eq!(None, report.points_needed(0, 0.9));
+        assert_eq!(None, report.points_needed(4, 0.9));
+        assert_eq!(
+            None,
+            SimulationReport::new(&league_table).points_needed(1, 0.9)
+        );
+    }
+
+    #[test]
+    fn exact_short_run_in() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        league_table.add_team("Luton".to_string(), 78, 25).unwrap();
+        let mut fixtures = FixtureList::round_robin(["Leeds", "Burnley", "Luton"], false);
+
+        let matrix = enumerate_season(&league_table, &fixtures).unwrap();
+        for row in &matrix {
+            assert!((row.probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert_eq!(1.0_f64.min(row.at_or_above(3)), row.at_or_above(3));
+        }
+        for position in 0..3 {
+            let total: f64 = matrix.iter().map(|row| row.probabilities()[position]).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        // exact probabilities agree with simulation
+        let report = simulate_batch(&league_table, &fixtures, 4000);
+        let luton = matrix.iter().find(|row| row.name() == "Luton").unwrap();
+        assert!((luton.at_or_above(1) - report.probability("Luton", 1)).abs() < 0.03);
+
+        // a pinned fixture has one outcome, and a finished one is certain
+        for game in fixtures.clone().iter() {
+            fixtures.force_result(game.home(), game.away(), 0, 2);
+        }
+        let matrix = enumerate_season(&league_table, &fixtures).unwrap();
+        assert_eq!(1.0, matrix[0].probabilities()[0]);
+        let too_many =
+            FixtureList::round_robin(["Leeds", "Burnley", "Luton", "Derby", "Hull"], true);
+        assert!(enumerate_season(&league_table, &too_many).is_none());
+
+        // a single target is worked out exactly only when points alone decide it
+        let mut league_table = LeagueTable::new();
+        for (name, pts) in [("Leeds", 90), ("Burnley", 80), ("Luton", 78)] {
+            league_table.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        let fixtures = [Match::from("Luton", "Burnley")];
+        let matrix = enumerate_season(&league_table, &fixtures).unwrap();
+        let burnley = matrix.iter().find(|row| row.name() == "Burnley").unwrap();
+        let second = enumerate_target(&league_table, &fixtures, "Burnley", &Target::Rank(2));
+        assert_eq!(Some(burnley.at_or_above(2)), second);
+        assert!(burnley.at_or_above(2) > 0.0 && burnley.at_or_above(2) < 1.0);
+        let above = Target::Above("Luton".to_string());
+        assert_eq!(
+            second,
+            enumerate_target(&league_table, &fixtures, "Burnley", &above)
+        );
+        let leeds = enumerate_target(&league_table, &fixtures, "Leeds", &Target::Rank(1));
+        assert!((leeds.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(
+            Some(0.0),
+            enumerate_target(&league_table, &fixtures, "Hull", &Target::Rank(1))
+        );
+        // a Luton win would leave them level with Burnley on 80
+        let mut league_table = LeagueTable::new();
+        for (name, pts) in [("Leeds", 90), ("Burnley", 80), ("Luton", 77)] {
+            league_table.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        for target in [Target::Rank(2), above] {
+            assert_eq!(
+                None,
+                enumerate_target(&league_table, &fixtures, "Burnley", &target)
+            );
+        }
+        let leeds = enumerate_target(&league_table, &fixtures, "Leeds", &Target::Rank(1));
+        assert!((leeds.unwrap() - 1.0).abs() < 1e-9);
+
+        // thinning a full match by half keeps each goal with even chances
+        assert_eq!(vec![0.25, 0.5, 0.25], thinned(&[0.0, 0.0, 1.0], 0.5));
+        assert_eq!(vec![0.0, 0.0, 1.0], thinned(&[0.0, 0.0, 1.0], 1.0));
+    }
+
+    #[test]
+    fn conditional_batches() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 60, 20).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 59, 15)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 40, -5).unwrap();
+        let fixtures = vec![
+            Match::from("Celtic", "Rangers"),
+            Match::from("Rangers", "Hearts"),
+        ];
+
+        // if Rangers win both they go top, whatever else happens
+        let given = [
+            MatchResult::new(Match::from("Celtic", "Rangers"), 0, 2),
+            MatchResult::new(Match::from("Rangers", "Hearts"), 1, 0),
+            MatchResult::new(Match::from("Hearts", "Celtic"), 5, 0),
+        ];
+        let config = SimulationConfig::new(50).given(&given);
+        assert_eq!(3, config.given_results().len());
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(1.0, report.probability("Rangers", 1));
+        assert_eq!(0.0, report.probability("Celtic", 1));
+
+        // with only the first pinned, the second is still sampled
+        let config = SimulationConfig::new(200).given(&given[..1]);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(1.0, report.probability("Rangers", 1));
+        assert_eq!(0, report.successes("Celtic", 1));
+    }
+
+    #[test]
+    fn multi_season_projection() {
+        let division = |teams: [(&str, u32); 4]| {
+            let mut table = LeagueTable::new();
+            for (name, pts) in teams {
+                table.add_team(name.to_string(), pts, 0).unwrap();
+            }
+            table
+        };
+        let upper = division([
+            ("Arsenal", 90),
+            ("Spurs", 70),
+            ("Fulham", 50),
+            ("Luton", 20),
+        ]);
+        let lower = division([("Leeds", 95), ("Hull", 60), ("Stoke", 55), ("Wigan", 40)]);
+
+        // with the current seasons already decided, the first exchange is certain
+        let system = LeagueSystem::new(upper, &[], lower, &[]).with_exchanged(1);
+        assert_eq!(1, system.exchanged());
+        let projection = system.project_with_rng(3, 200, &mut StdRng::seed_from_u64(7));
+        assert_eq!((200, 3), (projection.simulations(), projection.seasons()));
+        assert_eq!(1.0, projection.upper_division_probability("Leeds", 1));
+        assert_eq!(0.0, projection.upper_division_probability("Luton", 1));
+        assert_eq!(0.0, projection.upper_division_probability("Wigan", 1));
+        assert_eq!(1.0, projection.upper_division_probability("Arsenal", 1));
+
+        // later seasons are played out from scratch, so anything can happen
+        let luton = projection.upper_division_probability("Luton", 3);
+        assert!(luton > 0.0 && luton < 1.0);
+        for season in 1..=3 {
+            let total: f64 = ["Arsenal", "Spurs", "Fulham", "Luton"]
+                .into_iter()
+                .chain(["Leeds", "Hull", "Stoke", "Wigan"])
+                .map(|team| projection.upper_division_probability(team, season))
+                .sum();
+            assert!((total - 4.0).abs() < 1e-9);
+        }
+        assert_eq!(0.0, projection.upper_division_probability("Arsenal", 4));
+        assert_eq!(0.0, projection.upper_division_probability("Arsenal", 0));
+        assert_eq!(0.0, projection.upper_division_probability("Barnet", 1));
+        assert_eq!(1, projection.exchanges().len());
+        let exchange = &projection.exchanges()[0];
+        assert_eq!(["Leeds"], exchange.promoted());
+        assert_eq!(["Luton"], exchange.relegated());
+        assert_eq!(1.0, exchange.probability());
+
+        // later seasons are played with the model given, so a far stronger side stays up
+        let model = EloModel::new(HashMap::from([("Arsenal".to_string(), 2600.0)]));
+        let projection = system.project_with_model(3, 200, &model, &mut StdRng::seed_from_u64(7));
+        assert!(projection.upper_division_probability("Arsenal", 3) > 0.95);
+
+        // with both races still open, each exchange is one both tables agree on
+        let upper = division([
+            ("Arsenal", 90),
+            ("Spurs", 70),
+            ("Fulham", 21),
+            ("Luton", 20),
+        ]);
+        let lower = division([("Leeds", 80), ("Hull", 79), ("Stoke", 55), ("Wigan", 40)]);
+        let system = LeagueSystem::new(
+            upper,
+            &[Match::from("Fulham", "Luton")],
+            lower,
+            &[Match::from("Hull", "Leeds")],
+        )
+        .with_exchanged(1);
+        let projection = system.project_with_rng(1, 400, &mut StdRng::seed_from_u64(5));
+        let exchanges = projection.exchanges();
+        assert!(exchanges.len() > 1 && exchanges.len() <= 4);
+        assert!((exchanges.iter().map(Exchange::probability).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(exchanges
+            .windows(2)
+            .all(|pair| pair[0].probability() >= pair[1].probability()));
+        let leeds_up: f64 = exchanges
+            .iter()
+            .filter(|exchange| exchange.promoted() == ["Leeds"])
+            .map(Exchange::probability)
+            .sum();
+        assert!((leeds_up - projection.upper_division_probability("Leeds", 1)).abs() < 1e-9);
+        assert!(exchanges
+            .iter()
+            .all(|exchange| ["Fulham", "Luton"].contains(&exchange.relegated()[0].as_str())));
+    }
+
+    #[test]
+    fn championship_playoffs() {
+        let mut table = LeagueTable::new();
+        let teams = [
+            "Leeds", "Burnley", "Luton", "Hull", "Stoke", "Wigan", "Derby", "Bury",
+        ];
+        for (name, pts) in teams.iter().zip((60..100).rev().step_by(5)) {
+            table.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        let playoffs = PlayOffs::championship();
+        assert_eq!((3, 6), (playoffs.first(), playoffs.last()));
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut winners = HashMap::new();
+        for _ in 0..400 {
+            let winner = playoffs.winner_with_rng(&table, &mut rng).unwrap();
+            *winners.entry(winner).or_insert(0) += 1;
+        }
+        let mut finalists: Vec<&str> = winners.keys().map(String::as_str).collect();
+        finalists.sort();
+        assert_eq!(vec!["Hull", "Luton", "Stoke", "Wigan"], finalists);
+        assert_eq!(None, PlayOffs::new(9, 12).winner(&table));
+        assert_eq!(
+            Some("Derby".to_string()),
+            PlayOffs::new(7, 7).winner(&table)
+        );
+        // the ties are played with the model given, so the far stronger side usually goes up
+        let model = EloModel::new(HashMap::from([("Wigan".to_string(), 2600.0)]));
+        let wigan = (0..100)
+            .filter(|_| {
+                playoffs
+                    .winner_with_model(&table, &model, &mut rng)
+                    .as_deref()
+                    == Some("Wigan")
+            })
+            .count();
+        assert!(wigan > 60);
+
+        // a single league's report counts the play-off winners towards promotion
+        let config = SimulationConfig::new(400)
+            .with_threads(1)
+            .with_play_offs(playoffs.clone());
+        assert_eq!(Some(&playoffs), config.play_offs());
+        let report = run_simulations(&config, &table, &[]);
+        let play_off_total: f64 = teams
+            .iter()
+            .map(|team| report.play_off_probability(team))
+            .sum();
+        assert!((play_off_total - 1.0).abs() < 1e-9);
+        assert_eq!(1.0, report.promotion_probability("Burnley", 2));
+        assert_eq!(0.0, report.promotion_probability("Derby", 2));
+        let luton = report.promotion_probability("Luton", 2);
+        assert!(luton > 0.0 && luton == report.play_off_probability("Luton"));
+
+        // the play-off winner takes the last promotion place, the top two go up outright
+        let mut upper = LeagueTable::new();
+        for (name, pts) in [
+            ("Arsenal", 90),
+            ("Spurs", 70),
+            ("Fulham", 50),
+            ("Everton", 40),
+        ] {
+            upper.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        let system = LeagueSystem::new(upper, &[], table, &[]).with_playoffs(playoffs.clone());
+        assert_eq!(Some(&playoffs), system.playoffs());
+        let projection = system.project_with_rng(1, 400, &mut rng);
+        for team in ["Leeds", "Burnley"] {
+            assert_eq!(1.0, projection.upper_division_probability(team, 1));
+        }
+        let playoff_chances: Vec<f64> = ["Luton", "Hull", "Stoke", "Wigan"]
+            .into_iter()
+            .map(|team| projection.upper_division_probability(team, 1))
+            .collect();
+        assert!(playoff_chances
+            .iter()
+            .all(|&chance| chance > 0.0 && chance < 1.0));
+        assert!((playoff_chances.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, projection.upper_division_probability("Derby", 1));
+        assert_eq!(0.0, projection.upper_division_probability("Spurs", 1));
+    }
+
+    #[test]
+    fn relegation_playoff() {
+        let division = |teams: [(&str, u32); 4]| {
+            let mut table = LeagueTable::new();
+            for (name, pts) in teams {
+                table.add_team(name.to_string(), pts, 0).unwrap();
+            }
+            table
+        };
+        let upper = division([
+            ("Bayern", 90),
+            ("Dortmund", 70),
+            ("Bochum", 30),
+            ("Koln", 20),
+        ]);
+        let lower = division([
+            ("St Pauli", 80),
+            ("Kiel", 75),
+            ("Hamburg", 60),
+            ("Hertha", 40),
+        ]);
+        let system = LeagueSystem::new(upper, &[], lower, &[])
+            .with_exchanged(1)
+            .with_relegation_playoff(true);
+        assert!(system.relegation_playoff());
+        let projection = system.project_with_rng(1, 400, &mut StdRng::seed_from_u64(9));
+
+        // Koln go down and St Pauli up outright, Bochum and Kiel play off
+        assert_eq!(0.0, projection.upper_division_probability("Koln", 1));
+        assert_eq!(1.0, projection.upper_division_probability("St Pauli", 1));
+        let bochum = projection.upper_division_probability("Bochum", 1);
+        let kiel = projection.upper_division_probability("Kiel", 1);
+        assert!(bochum > 0.0 && kiel > 0.0);
+        assert!((bochum + kiel - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, projection.upper_division_probability("Hamburg", 1));
+        for exchange in projection.exchanges() {
+            assert_eq!(exchange.promoted().len(), exchange.relegated().len());
+        }
+        let both = projection
+            .exchanges()
+            .iter()
+            .find(|exchange| exchange.promoted() == ["Kiel", "St Pauli"])
+            .unwrap();
+        assert_eq!(["Bochum", "Koln"], both.relegated());
+        assert!((both.probability() - kiel).abs() < 1e-9);
+
+        // the play-off is sampled from the model given, so the far stronger side usually wins it
+        let model = EloModel::new(HashMap::from([("Kiel".to_string(), 2600.0)]));
+        let projection = system.project_with_model(1, 400, &model, &mut StdRng::seed_from_u64(9));
+        assert!(projection.upper_division_probability("Kiel", 1) > 0.9);
+    }
+
+    #[test]
+    fn conference_playoffs() {
+        let east = ["Atlanta", "Boston", "Chicago", "Dallas"];
+        let west = ["Eugene", "Fresno", "Glendale", "Houston"];
+        let mut table = LeagueTable::new();
+        for (name, pts) in east
+            .iter()
+            .chain(&west)
+            .zip([60, 40, 30, 20, 55, 50, 45, 10])
+        {
+            table.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        let league = ConferenceLeague::new()
+            .with_conference("East", east)
+            .with_conference("West", west)
+            .with_playoff_places(3)
+            .with_rounds(&[SeriesFormat::BestOf(3)]);
+        assert_eq!(
+            vec!["East", "West"],
+            league.conferences().collect::<Vec<_>>()
+        );
+        assert_eq!(Some("West"), league.conference_of("Fresno"));
+        assert_eq!(None, league.conference_of("Portland"));
+        let west_standings = league.conference_standings(&table, "West").unwrap();
+        assert_eq!(Some(1), west_standings.rank_of("Eugene"));
+        assert_eq!(4, west_standings.len());
+        assert!(league.conference_standings(&table, "North").is_none());
+        assert_eq!(
+            vec!["Eugene", "Fresno", "Glendale"],
+            league.seeds(&table, "West")
+        );
+
+        // three teams from each conference can win, Dallas and Houston never qualify
+        let projection = league.project_with_rng(&table, &[], 600, &mut StdRng::seed_from_u64(2));
+        assert_eq!(600, projection.simulations());
+        assert_eq!(1.0, projection.playoff_probability("Atlanta"));
+        assert_eq!(0.0, projection.playoff_probability("Dallas"));
+        assert_eq!(0.0, projection.champion_probability("Dallas"));
+        let champions: Vec<f64> = east
+            .iter()
+            .chain(&west)
+            .map(|team| projection.champion_probability(team))
+            .collect();
+        assert!((champions.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(6, champions.iter().filter(|&&chance| chance > 0.0).count());
+        // the playoffs are played with the model given, so a far stronger third seed usually wins
+        let model = EloModel::new(HashMap::from([("Glendale".to_string(), 2600.0)]));
+        let projection =
+            league.project_with_model(&table, &[], 300, &model, &mut StdRng::seed_from_u64(2));
+        assert!(projection.champion_probability("Glendale") > 0.6);
+
+        // a single conference's champion takes the title
+        let solo = ConferenceLeague::new()
+            .with_conference("East", east)
+            .with_playoff_places(1);
+        assert_eq!(Some("Atlanta".to_string()), solo.champion(&table));
+        assert_eq!(None, ConferenceLeague::new().champion(&table));
+    }
+
+    #[test]
+    fn split_league() {
+        let mut table = LeagueTable::new();
+        let teams = [
+            "Celtic",
+            "Rangers",
+            "Hearts",
+            "Hibs",
+            "Ross County",
+            "Dundee",
+        ];
+        for (name, pts) in teams.iter().zip([80, 70, 50, 49, 20, 10]) {
+            table.add_team(name.to_string(), pts, 0).unwrap();
+        }
+        let split = SplitLeague::new(3);
+        assert_eq!(6, SplitLeague::scottish_premiership().top_group());
+        let (top, bottom) = split.groups(&table);
+        assert_eq!(vec!["Celtic", "Rangers", "Hearts"], top);
+        assert_eq!(vec!["Hibs", "Ross County", "Dundee"], bottom);
+        let fixtures = split.split_fixtures(&top, &bottom);
+        assert_eq!(6, fixtures.len());
+        assert!(fixtures.iter().all(|game| {
+            top.contains(&game.home().to_string()) == top.contains(&game.away().to_string())
+        }));
+
+        // Hibs can pass Hearts on points after the split but still finish below them
+        let mut after = table.clone();
+        after
+            .apply_results(&[MatchResult::new(Match::from("Hibs", "Dundee"), 2, 0)])
+            .unwrap();
+        let standings = split.final_standings(&after, &top);
+        assert_eq!(Some(3), standings.rank_of("Hearts"));
+        assert_eq!(Some(4), standings.rank_of("Hibs"));
+
+        // with the split already reached, the groups are fixed
+        let matrix = split.rank_matrix_with_rng(&table, &[], 300, &mut StdRng::seed_from_u64(4));
+        let row = |name: &str| matrix.iter().find(|row| row.name() == name).unwrap();
+        assert_eq!(1.0, row("Hearts").at_or_above(3));
+        assert_eq!(0.0, row("Hibs").at_or_above(3));
+        assert!(row("Hibs").at_or_above(4) > 0.0);
+        assert_eq!("Celtic", matrix[0].name());
+
+        // past the split, the real groups stand even where the table has since
+        // changed, and only the real post-split fixtures are played
+        let split = SplitLeague::new(3).with_top_teams(["Celtic", "Rangers", "Hibs"]);
+        assert_eq!(3, split.top_group());
+        assert_eq!(
+            Some(&["Celtic", "Rangers", "Hibs"].map(String::from)[..]),
+            split.top_teams()
+        );
+        let remaining = [Match::from("Hearts", "Dundee")];
+        let matrix =
+            split.rank_matrix_with_rng(&table, &remaining, 300, &mut StdRng::seed_from_u64(5));
+        let row = |name: &str| matrix.iter().find(|row| row.name() == name).unwrap();
+        assert_eq!(1.0, row("Hibs").at_or_above(3));
+        assert_eq!(0.0, row("Hearts").at_or_above(3));
+        assert_eq!(1.0, row("Celtic").at_or_above(1));
+        assert_eq!(1.0, row("Rangers").at_or_above(2));
+        assert_eq!(0.0, row("Rangers").at_or_above(1));
+        let chance = |team: &str, target: &Target| {
+            let mut rng = StdRng::seed_from_u64(6);
+            split.target_probability_with_rng(&table, &remaining, team, target, 100, &mut rng)
+        };
+        assert_eq!(1.0, chance("Hibs", &Target::Rank(3)));
+        assert_eq!(0.0, chance("Hearts", &Target::Rank(3)));
+        assert_eq!(1.0, chance("Hibs", &Target::Above("Hearts".to_string())));
+
+        // the season's length depends on which group a team ends up in
+        let format = LeagueFormat::Split(split.clone().with_rounds_before_split(0));
+        let post_split = split.split_fixtures(
+            &["Celtic", "Rangers", "Hibs"].map(String::from),
+            &["Hearts", "Ross County", "Dundee"].map(String::from),
+        );
+        assert_eq!(Ok(()), format.check_schedule(&table, &post_split));
+        assert!(format.check_schedule(&table, &post_split[1..]).is_err());
+        let format = LeagueFormat::Split(SplitLeague::new(3).with_rounds_before_split(0));
+        assert_eq!(Ok(()), format.check_schedule(&table, &[]));
+        assert!(LeagueFormat::default().check_schedule(&table, &[]).is_err());
+        let mut twelve = LeagueTable::new();
+        for team in 0..12 {
+            twelve.add_team(format!("Team {team}"), 0, 0).unwrap();
+        }
+        assert_eq!(
+            33,
+            SplitLeague::scottish_premiership().matches_before_split(&twelve)
+        );
+
+        // a league's format is read from json, a split taking three rounds unless told otherwise
+        let format: LeagueFormat = serde_json::from_str(r#"{"split": {"top_group": 6}}"#).unwrap();
+        assert_eq!(
+            LeagueFormat::Split(SplitLeague::scottish_premiership()),
+            format
+        );
+        let format: LeagueFormat = serde_json::from_str(r#""round_robin""#).unwrap();
+        assert_eq!(LeagueFormat::RoundRobin, format);
+
+        // before the split, a far stronger Hibs usually beat Hearts to the top group
+        let model = EloModel::new(HashMap::from([("Hibs".to_string(), 2600.0)]));
+        let split = SplitLeague::new(3);
+        let decider = [Match::from("Hibs", "Hearts")];
+        let mut rng = StdRng::seed_from_u64(7);
+        let top = (0..100)
+            .filter(|_| {
+                let standings = split.simulate_with_model(&table, &decider, &model, &mut rng);
+                standings.rank_of("Hibs").unwrap() <= 3
+            })
+            .count();
+        assert!(top > 80);
+    }
+
+    #[test]
+    fn champions_league_knockouts() {
+        let mut table = LeagueTable::new();
+        for rank in 1..=36 {
+            table
+                .add_team(format!("Team {rank}"), 100 - rank, 0)
+                .unwrap();
+        }
+        let competition = ChampionsLeague::new();
+        assert_eq!(ExtraTime::default(), competition.extra_time());
+        let champion = competition.champion(&table).unwrap();
+        let champion_rank: u32 = champion["Team ".len()..].parse().unwrap();
+        assert!(champion_rank <= 24);
+        let mut small = LeagueTable::new();
+        small.add_team("Team 1".to_string(), 10, 0).unwrap();
+        assert_eq!(None, competition.champion(&small));
+
+        let projection =
+            competition.project_with_rng(&table, &[], 300, &mut StdRng::seed_from_u64(8));
+        assert_eq!(300, projection.simulations());
+        let chance =
+            |rank: u32, stage| projection.stage_probability(&format!("Team {rank}"), stage);
+        assert_eq!(1.0, chance(1, KnockoutStage::RoundOf16));
+        assert_eq!(1.0, chance(1, KnockoutStage::KnockoutPlayOffs));
+        assert_eq!(1.0, chance(9, KnockoutStage::KnockoutPlayOffs));
+        assert!(
+            chance(9, KnockoutStage::RoundOf16) > 0.0 && chance(9, KnockoutStage::RoundOf16) < 1.0
+        );
+        assert_eq!(0.0, chance(25, KnockoutStage::KnockoutPlayOffs));
+
+        // every stage holds the right number of teams
+        for (stage, teams) in [
+            (KnockoutStage::KnockoutPlayOffs, 24.0),
+            (KnockoutStage::RoundOf16, 16.0),
+            (KnockoutStage::QuarterFinals, 8.0),
+            (KnockoutStage::SemiFinals, 4.0),
+            (KnockoutStage::Final, 2.0),
+            (KnockoutStage::Winner, 1.0),
+        ] {
+            let total: f64 = (1..=36).map(|rank| chance(rank, stage)).sum();
+            assert!((total - teams).abs() < 1e-9);
+        }
+        let stages = projection.stage_probabilities("Team 1");
+        assert_eq!(6, stages.len());
+        assert_eq!(
+            (KnockoutStage::Winner, chance(1, KnockoutStage::Winner)),
+            stages[5]
+        );
+
+        // the ties are played with the model given, so a far stronger side
+        // usually wins from a play-off place
+        let model = EloModel::new(HashMap::from([("Team 24".to_string(), 2600.0)]));
+        let projection =
+            competition.project_with_model(&table, &[], 300, &model, &mut StdRng::seed_from_u64(8));
+        assert!(projection.stage_probability("Team 24", KnockoutStage::Winner) > 0.5);
+        let mut rng = StdRng::seed_from_u64(8);
+        let champions = (0..50)
+            .filter(|_| {
+                competition
+                    .champion_with_model(&table, &model, &mut rng)
+                    .as_deref()
+                    == Some("Team 24")
+            })
+            .count();
+        assert!(champions > 25);
+    }
+
+    #[test]
+    fn scenario_comparison() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 60, 20).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 59, 15)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 40, -5).unwrap();
+        let fixtures = vec![
+            Match::from("Celtic", "Rangers"),
+            Match::from("Rangers", "Hearts"),
+        ];
+        let derby = Match::from("Celtic", "Rangers");
+
+        let scenarios = ScenarioSet::new(400, 7)
+            .with_scenario("Rangers win", &[MatchResult::new(derby.clone(), 0, 1)])
+            .with_scenario("Celtic win", &[MatchResult::new(derby.clone(), 1, 0)])
+            .with_scenario("Unchanged", &[])
+            .with_model_scenario("Goalless", &[], PoissonModel::new(0.0, 1.0));
+        assert_eq!(
+            vec!["Rangers win", "Celtic win", "Unchanged", "Goalless"],
+            scenarios.names().collect::<Vec<_>>()
+        );
+        let comparison = scenarios.run(&league_table, &fixtures);
+        assert_eq!(400, comparison.baseline().simulations());
+
+        // the same seeds with the same assumptions reproduce the baseline exactly
+        assert_eq!(Some(0.0), comparison.delta("Unchanged", "Celtic", 1));
+        assert_eq!(
+            Some(comparison.baseline()),
+            comparison.scenario("Unchanged")
+        );
+        assert!(comparison.delta("Rangers win", "Rangers", 1).unwrap() > 0.0);
+        // assuming the derby leaves every draw of the fixture after it alone
+        let hearts = |report: &SimulationReport| {
+            let table = report.expected_table();
+            let hearts = table.iter().find(|row| row.name() == "Hearts").unwrap();
+            (hearts.points(), hearts.goal_diff())
+        };
+        assert_eq!(
+            hearts(comparison.baseline()),
+            hearts(comparison.scenario("Rangers win").unwrap())
+        );
+        assert_eq!(
+            Some(1.0 - comparison.baseline().probability("Celtic", 1)),
+            comparison.delta("Celtic win", "Celtic", 1)
+        );
+        // two goalless draws leave Celtic top
+        assert_eq!(
+            Some(1.0 - comparison.baseline().probability("Celtic", 1)),
+            comparison.delta("Goalless", "Celtic", 1)
+        );
+        assert_eq!(None, comparison.delta("Hearts win", "Celtic", 1));
+
+        let rows = comparison.deltas(1);
+        assert_eq!("Celtic", rows[0].name());
+        assert_eq!(
+            comparison.baseline().probability("Celtic", 1),
+            rows[0].baseline()
+        );
+        assert_eq!(4, rows[0].deltas().len());
+        assert_eq!(
+            comparison.delta("Rangers win", "Rangers", 1).unwrap(),
+            rows[1].delta("Rangers win")
+        );
+        assert_eq!(0.0, rows[2].delta("Hearts win"));
+    }
+
+    #[test]
+    fn key_fixture_influence() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 60, 20).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 59, 15)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 40, -5).unwrap();
+        league_table
+            .add_team("Aberdeen".to_string(), 30, -30)
+            .unwrap();
+        let fixtures = vec![
+            Match::from("Hearts", "Aberdeen"),
+            Match::from("Celtic", "Rangers"),
+            Match::from("Aberdeen", "Celtic"),
+        ];
+
+        let config = SimulationConfig::new(2000)
+            .with_threads(2)
+            .with_key_fixtures("Rangers", 1);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        let key = report.key_fixtures();
+        assert_eq!(3, key.len());
+        // the title decider matters most, the mid-table match hardly at all
+        assert_eq!(("Celtic", "Rangers"), (key[0].home(), key[0].away()));
+        assert_eq!(Some(0.0), key[0].home_win());
+        assert!(key[0].away_win().unwrap() > 0.5);
+        let hearts = key
+            .iter()
+            .find(|fixture| fixture.home() == "Hearts")
+            .unwrap();
+        assert!(hearts.influence() < 0.15);
+        assert!(key[0].influence() > key[1].influence());
+
+        // a forced result only ever has one outcome
+        let given = [MatchResult::new(Match::from("Celtic", "Rangers"), 2, 2)];
+        let report = run_simulations(&config.clone().given(&given), &league_table, &fixtures);
+        let derby = report
+            .key_fixtures()
+            .into_iter()
+            .find(|fixture| fixture.home() == "Celtic")
+            .unwrap();
+        assert_eq!((None, None), (derby.home_win(), derby.away_win()));
+        assert_eq!(0.0, derby.influence());
+        assert!(
+            run_simulations(&SimulationConfig::new(10), &league_table, &fixtures)
+                .key_fixtures()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn must_win_fixtures() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 60, 20).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 55, 15)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 40, -5).unwrap();
+        let fixtures = vec![
+            Match::from("Hearts", "Celtic"),
+            Match::from("Celtic", "Rangers"),
+            Match::from("Rangers", "Hearts"),
+        ];
+
+        let config = SimulationConfig::new(2000).with_key_fixtures("Rangers", 1);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        // Rangers can only go top by winning both of their games
+        let must_win = report.must_win_fixtures(0.01);
+        assert_eq!(2, must_win.len());
+        assert!(must_win
+            .iter()
+            .all(|fixture| fixture.home() == "Rangers" || fixture.away() == "Rangers"));
+        assert!(report.must_win_fixtures(0.0).is_empty());
+
+        // once the win over Celtic is pinned only the Hearts game is in doubt
+        let given = [MatchResult::new(Match::from("Celtic", "Rangers"), 0, 1)];
+        let report = run_simulations(&config.given(&given), &league_table, &fixtures);
+        let must_win = report.must_win_fixtures(0.01);
+        assert_eq!(1, must_win.len());
+        assert_eq!(
+            ("Rangers", "Hearts"),
+            (must_win[0].home(), must_win[0].away())
+        );
+    }
+
+    #[test]
+    fn finish_above_rival() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 70, 30)
+            .unwrap();
+        league_table
+            .add_team("Tottenham".to_string(), 68, 10)
+            .unwrap();
+        league_table.add_team("Chelsea".to_string(), 50, 0).unwrap();
+        let fixtures = vec![Match::from("Tottenham", "Arsenal")];
+
+        let report = run_simulations(&SimulationConfig::new(500), &league_table, &fixtures);
+        let above = report.probability_above("Arsenal", "Tottenham");
+        assert!(above > 0.0 && above < 1.0);
+        assert_eq!(
+            report.successes_above("Arsenal", "Tottenham")
+                + report.successes_above("Tottenham", "Arsenal"),
+            500
+        );
+        assert_eq!(1.0, report.probability_above("Tottenham", "Chelsea"));
+        assert_eq!(0.0, report.probability_above("Chelsea", "Arsenal"));
+        assert_eq!(0, report.successes_above("Arsenal", "Hull"));
+        assert_eq!(
+            report.probability("Arsenal", 1),
+            report.target_probability("Arsenal", &Target::Above("Tottenham".to_string()))
+        );
+
+        // already settled, so the batch stops as soon as it is allowed to
+        let config = SimulationConfig::new(5000).with_convergence_on(
+            "Chelsea",
+            Target::Above("Arsenal".to_string()),
+            0.01,
+        );
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(MIN_CONVERGED_SIMULATIONS, report.simulations());
+        let error = report.target_standard_error("Chelsea", &Target::Above("Arsenal".to_string()));
+        assert!(error > 0.0 && error < 0.01);
+    }
+
+    #[test]
+    fn matchday_trajectory() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 70, 30)
+            .unwrap();
+        league_table
+            .add_team("Tottenham".to_string(), 69, 10)
+            .unwrap();
+        league_table.add_team("Chelsea".to_string(), 50, 0).unwrap();
+        let fixtures = vec![
+            Match::from("Chelsea", "Arsenal").with_matchday(37),
+            Match::from("Tottenham", "Chelsea")
+                .with_matchday(36)
+                .with_forced_result(2, 0),
+            Match::from("Arsenal", "Tottenham"),
+        ];
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let target = Target::Rank(1);
+        let trajectory = simulate_trajectory_with_rng(
+            &league_table,
+            &fixtures,
+            "Arsenal",
+            &target,
+            400,
+            &mut rng,
+        );
+        let matchdays: Vec<_> = trajectory.iter().map(TrajectoryPoint::matchday).collect();
+        assert_eq!(vec![Some(36), Some(37), None], matchdays);
+        // Tottenham's forced win takes them top after matchday 36
+        assert_eq!(0.0, trajectory[0].probability());
+        assert!(trajectory[1].probability() > 0.0);
+
+        let above = Target::Above("Chelsea".to_string());
+        let trajectory = simulate_trajectory(&league_table, &fixtures, "Tottenham", &above, 50);
+        assert!(trajectory.iter().all(|point| point.probability() == 1.0));
+        let unknown = Target::Above("Hull".to_string());
+        assert!(
+            simulate_trajectory(&league_table, &fixtures, "Tottenham", &unknown, 50)
+                .iter()
+                .all(|point| point.probability() == 0.0)
+        );
+        assert!(
+            simulate_trajectory(&league_table, &fixtures, "Hull", &target, 50)
+                .iter()
+                .all(|point| point.probability() == 0.0)
+        );
+    }
+
+    #[test]
+    fn season_replays() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 70, 30)
+            .unwrap();
+        league_table
+            .add_team("Tottenham".to_string(), 69, 10)
+            .unwrap();
+        let fixtures = vec![
+            Match::from("Arsenal", "Tottenham"),
+            Match::from("Tottenham", "Arsenal").with_forced_result(3, 3),
+        ];
+
+        let replay =
+            replay_season_with_rng(&league_table, &fixtures, &mut StdRng::seed_from_u64(1));
+        assert_eq!(2, replay.results().len());
+        assert_eq!(
+            (3, 3),
+            (
+                replay.results()[1].home_goals(),
+                replay.results()[1].away_goals()
+            )
+        );
+        let played: u32 = replay.final_table().teams().map(Team::games_played).sum();
+        assert_eq!(4, played);
+        let json: serde_json::Value = serde_json::from_str(&replay.to_json()).unwrap();
+        assert_eq!("Tottenham", json["results"][1]["home"]);
+        assert_eq!(3, json["results"][1]["home_goals"]);
+        assert_eq!(2, json["final_table"].as_array().unwrap().len());
+
+        // Arsenal finish on 71, 72 or 74, and the typical season sits mid-range
+        let mut rng = StdRng::seed_from_u64(2);
+        let typical = median_replay_with_rng(&league_table, &fixtures, "Arsenal", 101, &mut rng);
+        let arsenal = typical.final_table().get_team("Arsenal").unwrap();
+        let mut points: Vec<u32> = (0..101)
+            .map(|_| {
+                replay_season_with_rng(&league_table, &fixtures, &mut rng)
+                    .final_table()
+                    .get_team("Arsenal")
+                    .unwrap()
+                    .points()
+            })
+            .collect();
+        points.sort_unstable();
+        assert!(arsenal.points().abs_diff(points[50]) <= 3);
+        assert_eq!(
+            2,
+            median_replay(&league_table, &fixtures, "Arsenal", 5)
+                .results()
+                .len()
+        );
+    }
+
+    #[test]
+    fn season_state_matches_table() {
+        let mut league_table = LeagueTable::with_tiebreakers(Tiebreakers::la_liga());
+        for name in ["Betis", "Sevilla", "Girona", "Osasuna"] {
+            league_table.add_team(name.to_string(), 40, 0).unwrap();
+        }
+        let fixtures = FixtureList::round_robin(["Betis", "Sevilla", "Girona", "Osasuna"], true);
+        let resolved = league_table.resolve_all(&fixtures).unwrap();
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (season, scores) = play_season(&league_table, &resolved, &mut rng, |_, rng| {
+                (rng.random_range(0..3), rng.random_range(0..3))
+            });
+            let mut table = league_table.clone();
+            for (&game, &(home_goals, away_goals)) in resolved.iter().zip(&scores) {
+                table.update_resolved(game, home_goals, away_goals);
+            }
+            assert_eq!(table.ranked_ids(), season.ranked_ids());
+            let rebuilt = season.into_table();
+            assert_eq!(table.standings(), rebuilt.standings());
+            assert_eq!(
+                table.head_to_head("Betis", "Girona"),
+                rebuilt.head_to_head("Betis", "Girona")
+            );
+        }
+    }
+
+    #[test]
+    fn fast_rng_batches() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        league_table.add_team("Luton".to_string(), 20, -30).unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+
+        let config = SimulationConfig::new(4000)
+            .with_threads(2)
+            .with_rng_backend(RngBackend::Fast);
+        assert_eq!(RngBackend::Fast, config.rng_backend());
+        assert_eq!(
+            RngBackend::Standard,
+            SimulationConfig::default().rng_backend()
+        );
+        let fast = run_simulations(&config, &league_table, &fixtures);
+        let standard = run_simulations(
+            &config.clone().with_rng_backend(RngBackend::Standard),
+            &league_table,
+            &fixtures,
+        );
+        assert_eq!(4000, fast.simulations());
+        assert_eq!(1.0, fast.probability("Burnley", 2));
+        assert!((fast.probability("Leeds", 1) - standard.probability("Leeds", 1)).abs() < 0.05);
+    }
+
+    #[test]
+    fn time_budgeted_batches() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+
+        // no time at all leaves nothing run
+        let config = SimulationConfig::new(1000).with_time_budget(Duration::ZERO);
+        assert_eq!(Some(Duration::ZERO), config.time_budget());
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(0, report.simulations());
+
+        // a generous budget runs the lot
+        let config = SimulationConfig::new(1000).with_time_budget(Duration::from_secs(60));
+        assert_eq!(
+            1000,
+            run_simulations(&config, &league_table, &fixtures).simulations()
+        );
+
+        // far more simulations than fit stop soon after the budget runs out
+        let config = SimulationConfig::new(u32::MAX)
+            .with_threads(2)
+            .with_time_budget(Duration::from_millis(50));
+        let start = Instant::now();
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(report.simulations() > 0 && report.simulations() < u32::MAX);
+        let leeds = report.probability("Leeds", 1);
+        assert!(leeds > 0.0 && leeds < 1.0);
+    }
+
+    #[test]
+    fn adaptive_batches_converge() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+
+        // a coin flip needs about 2500 simulations for a standard error of 0.01
+        let config = SimulationConfig::new(100_000).with_convergence("Leeds", 1, 0.01);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert!(report.simulations() < 100_000);
+        assert_eq!(0, report.simulations() % CONVERGENCE_ROUND);
+        assert!(report.standard_error("Leeds", 1) < 0.01);
+
+        // an unreachable threshold stops at the cap instead
+        let config = SimulationConfig::new(1200).with_convergence("Leeds", 1, 0.0);
+        assert_eq!(
+            1200,
+            run_simulations(&config, &league_table, &fixtures).simulations()
+        );
+    }
+
+    #[test]
+    fn antithetic_batches() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        let fixtures = vec![Match::from("Leeds", "Burnley")];
+
+        // an odd batch leaves one simulation without a partner, and Leeds top
+        // the table with a home win or a draw
+        let config =
+            SimulationConfig::new(1001).with_variance_reduction(VarianceReduction::Antithetic);
+        assert_eq!(VarianceReduction::Antithetic, config.variance_reduction());
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(1001, report.simulations());
+        let leeds = report.probability("Leeds", 1);
+        assert!((0.6..0.9).contains(&leeds), "{leeds}");
+
+        // opposite draws give opposite ends of the goal distribution
+        let model = HistoricalAverageModel::new();
+        assert_eq!((0, 0), model.quantile_at(1.0, (0.0, 0.0)));
+        assert_eq!((7, 7), model.quantile_at(1.0, (1.0, 1.0)));
+        assert_eq!((1, 0), model.quantile_at(1.0, (0.3, 0.3)));
+    }
+
+    #[test]
+    fn halton_batches() {
+        assert_eq!(vec![2, 3, 5, 7, 11], primes(5));
+        let base_two: Vec<f64> = (1..=4).map(|index| radical_inverse(index, 2)).collect();
+        assert_eq!(vec![0.5, 0.25, 0.75, 0.125], base_two);
+        assert!((radical_inverse(5, 3) - 7.0 / 9.0).abs() < 1e-12);
+
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Leeds".to_string(), 80, 30).unwrap();
+        league_table
+            .add_team("Burnley".to_string(), 80, 30)
+            .unwrap();
+        league_table.add_team("Luton".to_string(), 78, 20).unwrap();
+        let fixtures = FixtureList::round_robin(["Leeds", "Burnley", "Luton"], false);
+        let config = SimulationConfig::new(2000)
+            .with_variance_reduction(VarianceReduction::Halton)
+            .with_convergence("Luton", 1, 0.0);
+        let report = run_simulations(&config, &league_table, &fixtures);
+        assert_eq!(2000, report.simulations());
+        let luton = report.probability("Luton", 1);
+        assert!(luton > 0.0 && luton < 0.5, "{luton}");
+    }
+
+    #[test]
+    fn standings_snapshot_freshness() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let source = DataSource::Api("https://example.com/standings".to_string());
+        let old = StandingsSnapshot::new(source.clone(), SystemTime::now() - 10 * day);
+        assert!(old.is_stale(7 * day));
+        assert!(!old.is_stale(14 * day));
+        assert_eq!(&source, old.source());
+        assert_eq!("https://example.com/standings", old.source().to_string());
+
+        let future = StandingsSnapshot::new(source, SystemTime::now() + day);
+        assert_eq!(Duration::ZERO, future.age());
+
+        let (_, snapshot) = read_standings().unwrap();
+        assert!(
+            matches!(snapshot.source(), DataSource::File(path) if path.ends_with("standings.json"))
+        );
+        assert!(snapshot.updated_at() <= SystemTime::now());
+    }
+
+    #[test]
+    fn match_metadata() {
+        let kickoff = DateTime::parse_from_rfc3339("2025-03-08T15:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let fixture = Match::from("Brighton", "Fulham")
+            .with_kickoff(kickoff)
+            .with_matchday(28)
+            .with_venue("Amex Stadium");
+        assert_eq!(Some(kickoff), fixture.kickoff());
+        assert_eq!(Some(28), fixture.matchday());
+        assert_eq!(Some("Amex Stadium"), fixture.venue());
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        assert_eq!(
+            concat!(
+                r#"{"home":"Brighton","away":"Fulham","kickoff":"2025-03-08T15:00:00Z","#,
+                r#""matchday":28,"venue":"Amex Stadium"}"#
+            ),
+            json
+        );
+        assert_eq!(fixture, serde_json::from_str::<Match>(&json).unwrap());
+
+        // fixtures without metadata still read, and sort before dated ones
+        let bare: Match = serde_json::from_str(r#"{"home":"Spurs","away":"Chelsea"}"#).unwrap();
+        assert_eq!(None, bare.kickoff());
+        assert_eq!(
+            r#"{"home":"Spurs","away":"Chelsea"}"#,
+            serde_json::to_string(&bare).unwrap()
+        );
+        let later =
+            Match::from("Arsenal", "Chelsea").with_kickoff(kickoff + chrono::Duration::days(7));
+        let mut fixtures = vec![later.clone(), fixture.clone(), bare.clone()];
+        fixtures.sort_by_key(Match::kickoff);
+        assert_eq!(vec![bare, fixture.clone(), later], fixtures);
+
+        // the same pairing on another date is still a duplicate
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Brighton".to_string(), 0, 0).unwrap();
+        league_table.add_team("Fulham".to_string(), 0, 0).unwrap();
+        let issues = league_table
+            .validate_fixtures(&[fixture.clone(), Match::from("Brighton", "Fulham")])
+            .unwrap_err();
+        assert!(issues
+            .issues()
+            .contains(&FixtureIssue::DuplicateFixture(Match::from(
+                "Brighton", "Fulham"
+            ))));
+    }
+
+    #[test]
+    fn apply_simulated_results() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Brighton".to_string(), 40, 5)
+            .unwrap();
+        league_table.add_team("Fulham".to_string(), 38, 3).unwrap();
+        let fixtures = vec![
+            Match::from("Brighton", "Fulham"),
+            Match::from("Fulham", "Brighton"),
+        ];
+
+        let results = simulate_results(&fixtures);
+        assert_eq!(2, results.len());
+        for (result, fixture) in results.iter().zip(&fixtures) {
+            assert_eq!(fixture, result.fixture());
+            league_table.apply_result(result).unwrap();
+        }
+        assert_eq!(2, league_table.get_team("Brighton").unwrap().games_played());
+        let goals: u32 = results
+            .iter()
+            .map(|r| r.home_goals() + r.away_goals())
+            .sum();
+        let brighton = league_table.get_team("Brighton").unwrap();
+        let fulham = league_table.get_team("Fulham").unwrap();
+        assert_eq!(goals, brighton.goals_for() + fulham.goals_for());
+
+        let unknown = MatchResult::new(Match::from("Brighton", "Spurs"), 1, 0);
+        assert_eq!(
+            Err(TableError::UnknownTeam("Spurs".to_string())),
+            league_table.apply_result(&unknown)
+        );
+        assert_eq!(2, league_table.get_team("Brighton").unwrap().games_played());
+    }
+
+    #[test]
+    fn fixture_list_filters_and_keeps_repeats() {
+        let mut fixtures: FixtureList = vec![
+            Match::from("Arsenal", "Chelsea").with_matchday(1),
+            Match::from("Spurs", "Fulham").with_matchday(1),
+            Match::from("Chelsea", "Spurs").with_matchday(2),
+            Match::from("Arsenal", "Chelsea").with_matchday(3),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(4, fixtures.len());
+        fixtures.push(Match::from("Arsenal", "Chelsea"));
+        assert_eq!(2, fixtures.dedup());
+        assert_eq!(3, fixtures.len());
+        fixtures.push(Match::from("Chelsea", "Arsenal").with_matchday(2));
+
+        let chelsea = fixtures.involving("Chelsea");
+        let homes: Vec<&str> = chelsea.iter().map(Match::home).collect();
+        assert_eq!(vec!["Arsenal", "Chelsea", "Chelsea"], homes);
+        assert_eq!(2, fixtures.on_matchday(2).len());
+        assert!(fixtures.on_matchday(3).is_empty());
+
+        let json = r#"[{"home":"Arsenal","away":"Spurs"},{"home":"Arsenal","away":"Spurs"}]"#;
+        let mut repeated = serde_json::from_str::<FixtureList>(json).unwrap();
+        assert_eq!(2, repeated.len());
+        assert_eq!(1, repeated.dedup());
+        assert_eq!(1, repeated.len());
+
+        let mut league_table = LeagueTable::new();
+        for team in ["Arsenal", "Chelsea", "Spurs"] {
+            league_table.add_team(team.to_string(), 0, 0).unwrap();
+        }
+        let error = fixtures.validate(&league_table).unwrap_err();
+        assert_eq!(
+            &[FixtureIssue::UnknownTeam("Fulham".to_string())],
+            error.issues()
+        );
+    }
+
+    #[test]
+    fn round_robin_schedules() {
+        let teams = ["Arsenal", "Chelsea", "Fulham", "Spurs", "West Ham"];
+        let single = FixtureList::round_robin(teams, false);
+        assert_eq!(10, single.len());
+        for (i, first) in teams.iter().enumerate() {
+            for second in &teams[i + 1..] {
+                let meetings = single.involving(first).involving(second).len();
+                assert_eq!(1, meetings, "{first} v {second}");
+            }
+        }
+        // with an odd number of teams one sits out each of the five matchdays
+        for matchday in 1..=5 {
+            let playing = single.on_matchday(matchday);
+            assert_eq!(2, playing.len());
+            let mut names: Vec<&str> = playing.iter().flat_map(|m| [m.home(), m.away()]).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(4, names.len());
+        }
+
+        let double = FixtureList::round_robin(teams, true);
+        assert_eq!(20, double.len());
+        assert_eq!(Some(10), double.iter().filter_map(Match::matchday).max());
+        let league_table: LeagueTable = teams
+            .into_iter()
+            .map(|name| Team::new(name.to_string(), 0, 0))
+            .collect();
+        assert_eq!(Ok(()), double.validate(&league_table));
+        assert_eq!(
+            Ok(()),
+            league_table.check_schedule(&double, league_table.matches_per_season())
+        );
+
+        assert!(FixtureList::round_robin(Vec::<String>::new(), true).is_empty());
+        assert!(FixtureList::round_robin(["Arsenal"], true).is_empty());
+    }
+
+    #[test]
+    fn closed_door_matches() {
+        let json = r#"[{"home": "Millwall", "away": "Leeds", "home_advantage": 0},
+            {"home": "Leeds", "away": "Millwall", "home_advantage": 40},
+            {"home": "Leeds", "away": "Hull"}]"#;
+        let fixtures = parse_fixtures(json.as_bytes()).unwrap();
+        assert_eq!(
+            Match::from("Millwall", "Leeds").behind_closed_doors(),
+            fixtures[0]
+        );
+        assert_eq!(0.0, fixtures[0].home_advantage_share());
+        assert_eq!(0.4, fixtures[1].home_advantage_share());
+        assert_eq!(1.0, fixtures[2].home_advantage_share());
+        let capped = Match::from("Leeds", "Hull").with_home_advantage(250);
+        assert_eq!(1.0, capped.home_advantage_share());
+        let neutral = Match::from("Leeds", "Hull").with_site(Site::Neutral);
+        assert_eq!(0.0, neutral.with_home_advantage(50).home_advantage_share());
+
+        // behind closed doors both sides draw from the neutral distribution
+        let model = HistoricalAverageModel::new();
+        let (neutral_home, neutral_away) = model.goal_probabilities(0.0);
+        assert_eq!(neutral_home, neutral_away);
+        let (home, away) = model.goal_probabilities(0.5);
+        let (full_home, full_away) = model.goal_probabilities(1.0);
+        let mean = |probabilities: &[f64]| -> f64 {
+            probabilities
+                .iter()
+                .enumerate()
+                .map(|(goals, probability)| goals as f64 * probability)
+                .sum()
+        };
+        assert!((mean(&home) - (mean(&full_home) + mean(&neutral_home)) / 2.0).abs() < 1e-9);
+        assert!(mean(&away) > mean(&full_away) && mean(&away) < mean(&neutral_home));
+
+        let ratings = PoissonModel::new(1.4, 1.3);
+        let (home_goals, away_goals) = ratings.expected_goals(&fixtures[0]);
+        assert!((home_goals - away_goals).abs() < 1e-9);
+    }
+
+    #[test]
+    fn neutral_venue_matches() {
+        let final_match = Match::from("Palace", "City").with_site(Site::Neutral);
+        assert_eq!(Site::Neutral, final_match.site());
+        assert_eq!(Site::Home, Match::from("Palace", "City").site());
+
+        let json = serde_json::to_string(&final_match).unwrap();
+        assert_eq!(r#"{"home":"Palace","away":"City","site":"Neutral"}"#, json);
+        assert_eq!(final_match, serde_json::from_str::<Match>(&json).unwrap());
+        // the venue keeps naming the ground, as fixtures files have always had it
+        let named: Match = serde_json::from_str(
+            r#"{"home":"Palace","away":"City","venue":"Wembley","site":"Neutral"}"#,
+        )
+        .unwrap();
+        assert_eq!(Some("Wembley"), named.venue());
+        assert_eq!(Site::Neutral, named.site());
+
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Palace".to_string(), 0, 0).unwrap();
+        league_table.add_team("City".to_string(), 0, 0).unwrap();
+        let resolved = league_table.resolve(&final_match).unwrap();
+        assert_eq!(Site::Neutral, resolved.site());
+
+        // both sides draw from the same blended distribution
+        let mean = |weights: [f32; 8]| {
+            weights
+                .iter()
+                .enumerate()
+                .map(|(goals, weight)| weight * goals as f32)
+                .sum::<f32>()
+                / weights.iter().sum::<f32>()
+        };
+        let neutral_mean = mean(NEUTRAL_WEIGHTS);
+        assert!(mean(AWAY_WEIGHTS) < neutral_mean && neutral_mean < mean(HOME_WEIGHTS));
+    }
+
+    #[test]
+    fn forced_results_skip_sampling() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 58, 29)
+            .unwrap();
+        league_table.add_team("City".to_string(), 60, 30).unwrap();
+        let mut fixtures = FixtureList::round_robin(["Arsenal", "City"], true);
+        assert!(fixtures.force_result("Arsenal", "City", 2, 0));
+        assert!(fixtures.force_result("City", "Arsenal", 1, 1));
+        assert!(!fixtures.force_result("Arsenal", "Spurs", 1, 0));
+
+        // with every fixture pinned each simulation ends the same way
+        let report = simulate_batch(&league_table, &fixtures, 10);
+        let expected = report.expected_table();
+        assert_eq!("Arsenal", expected[0].name());
+        assert_eq!(62.0, expected[0].points());
+        assert_eq!(31.0, expected[0].goal_diff());
+        assert_eq!(61.0, expected[1].points());
+        let outcome = run_simulation("Arsenal", &league_table, &fixtures, &mut rand::rng());
+        assert_eq!(1, outcome.final_rank());
+        assert_eq!(
+            (62, 1, 1, 0),
+            (
+                outcome.points(),
+                outcome.wins(),
+                outcome.draws(),
+                outcome.losses()
+            )
+        );
+
+        let results = simulate_results(&fixtures);
+        assert_eq!((2, 0), (results[0].home_goals(), results[0].away_goals()));
+        assert_eq!(Some((1, 1)), results[1].fixture().forced_result());
+
+        fixtures.clear_forced_results();
+        assert!(fixtures
+            .iter()
+            .all(|fixture| fixture.forced_result().is_none()));
+    }
+
+    #[test]
+    fn fixture_difficulty_ratings() {
+        let mut league_table = LeagueTable::new();
+        for (name, pts, played) in [("Liverpool", 30, 10), ("Fulham", 15, 10), ("Ipswich", 0, 0)] {
+            let team = Team {
+                games_played: played,
+                ..Team::new(name.to_string(), pts, 0)
+            };
+            league_table
+                .add_team_struct(name.to_string(), team)
+                .unwrap();
+        }
+        let fixtures: FixtureList = vec![
+            Match::from("Fulham", "Liverpool"),
+            Match::from("Ipswich", "Fulham"),
+        ]
+        .into_iter()
+        .collect();
+
+        let difficulty = fixtures.difficulty(&league_table).unwrap();
+        let first = &difficulty.fixtures()[0];
+        assert_eq!(&Match::from("Fulham", "Liverpool"), first.fixture());
+        assert_eq!(1.0, first.home());
+        assert_eq!(0.5, first.away());
+        // Ipswich have not played yet, so rate as average
+        assert_eq!(0.5, difficulty.fixtures()[1].away());
+
+        assert_eq!(Some(0.75), difficulty.strength_of_schedule("Fulham"));
+        assert_eq!(None, difficulty.strength_of_schedule("Spurs"));
+        let order: Vec<&str> = difficulty.teams().map(|(name, _)| name).collect();
+        assert_eq!(vec!["Fulham", "Ipswich", "Liverpool"], order);
+
+        let unknown: FixtureList = vec![Match::from("Fulham", "Spurs")].into_iter().collect();
+        assert_eq!(
+            Err(TableError::UnknownTeam("Spurs".to_string())),
+            unknown.difficulty(&league_table)
+        );
+    }
+
+    #[test]
+    fn fixtures_by_matchday_range() {
+        let mut fixtures =
+            FixtureList::round_robin(["Arsenal", "Chelsea", "Fulham", "Spurs"], true);
+        fixtures.push(Match::from("Spurs", "Brentford"));
+        assert_eq!(13, fixtures.len());
+
+        let run_in = fixtures.from_matchday(5);
+        assert_eq!(4, run_in.len());
+        assert!(run_in.iter().all(|fixture| fixture.matchday() >= Some(5)));
+
+        let first_half = fixtures.up_to_matchday(3);
+        assert_eq!(6, first_half.len());
+        assert_eq!(
+            fixtures.on_matchday(3).len(),
+            first_half.from_matchday(3).len()
+        );
+        assert!(fixtures.from_matchday(7).is_empty());
+    }
+
+    #[test]
+    fn complete_live_matches() {
+        let model = HistoricalAverageModel::new();
+        let mut rng = rand::rng();
+
+        // at full time the live score stands
+        let full_time = LiveScore::new(2, 1, 90);
+        for _ in 0..20 {
+            let full_match = model.sample_at(1.0, &mut rng);
+            assert_eq!((2, 1), complete(full_time, full_match, &mut rng));
+        }
+
+        // late on only a few more goals can be added to the live score
+        let late = LiveScore::new(0, 3, 85);
+        let mut added = 0;
+        for _ in 0..200 {
+            let full_match = model.sample_at(1.0, &mut rng);
+            let (home_goals, away_goals) = complete(late, full_match, &mut rng);
+            assert!(away_goals >= 3);
+            added += home_goals + away_goals - 3;
+        }
+        assert!(added < 100, "{added} goals added in the last 5 minutes");
+
+        let mut fixtures = FixtureList::round_robin(["Everton", "Wolves"], false);
+        assert!(fixtures.set_live_score("Everton", "Wolves", LiveScore::new(1, 0, 90)));
+        assert!(!fixtures.set_live_score("Wolves", "Everton", LiveScore::new(1, 0, 90)));
+        let result = &simulate_results(&fixtures)[0];
+        assert_eq!((1, 0), (result.home_goals(), result.away_goals()));
+        assert_eq!(
+            Some(90),
+            result.fixture().live_score().map(|live| live.minute())
+        );
+
+        // a forced result takes precedence over the live score
+        fixtures.force_result("Everton", "Wolves", 0, 0);
+        let result = &simulate_results(&fixtures)[0];
+        assert_eq!((0, 0), (result.home_goals(), result.away_goals()));
+    }
+
+    #[test]
+    fn parse_fixtures_with_errors() {
+        let json =
+            r#"[{"home":"Arsenal","away":"Spurs","matchday":3},{"home":"Spurs","away":"Arsenal"}]"#;
+        let fixtures = parse_fixtures(json.as_bytes()).unwrap();
+        assert_eq!(2, fixtures.len());
+        assert_eq!(Some(3), fixtures[0].matchday());
+
+        let wrong_type = r#"[{"home":"Arsenal","away":"Spurs"},{"home":"Spurs","away":7}]"#;
+        let error = parse_fixtures(wrong_type.as_bytes()).unwrap_err();
+        assert_eq!("[1].away", error.path());
+        assert!(error
+            .to_string()
+            .starts_with("invalid value at [1].away: invalid type"));
+
+        let missing = r#"[{"home":"Arsenal"}]"#;
+        let error = parse_fixtures(missing.as_bytes()).unwrap_err();
+        assert_eq!("[0]", error.path());
+        assert!(error.to_string().contains("missing field `away`"));
+    }
+
+    #[test]
+    fn schedule_completeness() {
+        let teams = ["Arsenal", "Chelsea", "Spurs"];
+        let league_table: LeagueTable = teams
+            .into_iter()
+            .map(|name| Team::new(name.to_string(), 0, 0))
+            .collect();
+        let schedule = FixtureList::round_robin(teams, true);
+        let played: Vec<MatchResult> = schedule
+            .up_to_matchday(3)
+            .into_iter()
+            .map(|fixture| MatchResult::new(fixture, 1, 0))
+            .collect();
+        let remaining = schedule.from_matchday(4);
+        assert_eq!(Ok(()), league_table.check_completeness(&played, &remaining));
+
+        // drop one remaining fixture and replay one already played
+        let mut corrupted: Vec<Match> = remaining[1..].to_vec();
+        corrupted.push(played[0].fixture().clone());
+        let error = league_table
+            .check_completeness(&played, &corrupted)
+            .unwrap_err();
+        let dropped = &remaining[0];
+        assert_eq!(2, error.issues().len());
+        assert!(error
+            .issues()
+            .contains(&FixtureIssue::MissingPairing(Match::from(
+                dropped.home(),
+                dropped.away()
+            ))));
+        let repeated = played[0].fixture();
+        assert!(error.issues().contains(&FixtureIssue::RepeatedPairing {
+            fixture: Match::from(repeated.home(), repeated.away()),
+            count: 2
+        }));
+    }
+
+    #[test]
+    fn two_legged_ties() {
+        let tie = TwoLeggedTie::new("Arsenal", "Real Madrid");
+        assert_eq!(&Match::from("Real Madrid", "Arsenal"), tie.second_leg());
+
+        let result = tie.decide((3, 0), (1, 2)).unwrap();
+        assert_eq!("Arsenal", result.winner());
+        assert_eq!((5, 1), result.aggregate());
+        assert_eq!(TieDecider::Aggregate, result.decided_by());
+
+        // level on aggregate, with Real Madrid scoring twice at Arsenal
+        assert_eq!(None, tie.decide((2, 2), (1, 1)));
+        let away_goals = tie.clone().with_away_goals_rule(true);
+        let result = away_goals.decide((2, 2), (1, 1)).unwrap();
+        assert_eq!("Real Madrid", result.winner());
+        assert_eq!(TieDecider::AwayGoals, result.decided_by());
+        assert_eq!(None, away_goals.decide((1, 1), (1, 1)));
+
+        let mut pinned = tie.clone();
+        pinned.first_leg = pinned.first_leg.with_forced_result(0, 1);
+        pinned.second_leg = pinned.second_leg.with_forced_result(0, 1);
+        for _ in 0..20 {
+            let result = pinned.simulate();
+            assert_ne!(TieDecider::Aggregate, result.decided_by());
+            let (arsenal, real) = result.aggregate();
+            assert!(arsenal >= 1 && real >= 1);
+            if result.decided_by() == TieDecider::Penalties {
+                assert_eq!(arsenal, real);
+            }
+        }
+
+        // without extra time, or with goalless extra time, level ties go to penalties
+        for extra_time in [ExtraTime::none(), ExtraTime::new(30, 0.0)] {
+            let pinned = pinned.clone().with_extra_time(extra_time);
+            assert_eq!(extra_time, pinned.extra_time());
+            let result = pinned.simulate();
+            assert_eq!(TieDecider::Penalties, result.decided_by());
+            assert_eq!((1, 1), result.aggregate());
+        }
+        assert_eq!(ExtraTime::new(30, 1.0), tie.extra_time());
+
+        // a full 90 minutes at the normal rate keeps every goal of the sample
+        let mut rng = StdRng::seed_from_u64(11);
+        let model = HistoricalAverageModel::new();
+        let fixture = Match::from("Arsenal", "Real Madrid");
+        let extra_time = ExtraTime::new(90, 1.0).sample(&fixture, &model, &mut rng);
+        let mut rng = StdRng::seed_from_u64(11);
+        assert_eq!(model.sample_score(&fixture, &mut rng), extra_time);
+    }
+
+    #[test]
+    fn seeded_simulations_repeat() {
+        let (current_table, _) = read_standings().unwrap();
+        let fixtures = read_fixtures().unwrap();
+
+        let batch = |seed| {
+            simulate_batch_with_rng(
+                &current_table,
+                &fixtures,
+                20,
+                &mut StdRng::seed_from_u64(seed),
+            )
+        };
+        assert_eq!(batch(7), batch(7));
+
+        let ranks = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| run_simulation("Brighton", &current_table, &fixtures, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranks(99), ranks(99));
+
+        let results = |seed| simulate_results_with_rng(&fixtures, &mut StdRng::seed_from_u64(seed));
+        assert_eq!(results(3), results(3));
+        assert_ne!(results(3), results(4));
+
+        let tie = TwoLeggedTie::new("Arsenal", "Chelsea");
+        let ties = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| tie.simulate_with_rng(&mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ties(11), ties(11));
+    }
+
+    #[test]
+    fn simulate_full_table() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Everton".to_string(), 30, -5)
+            .unwrap();
+        league_table.add_team("Wolves".to_string(), 31, -2).unwrap();
+        league_table.add_team("Fulham".to_string(), 29, 1).unwrap();
+        let matches = FixtureList::round_robin(["Everton", "Wolves", "Fulham"], false);
+
+        let table =
+            simulate_table_with_rng(&league_table, &matches, &mut StdRng::seed_from_u64(51));
+        assert_eq!(3, table.len());
+        let standings = table.standings();
+        assert!(standings.iter().all(|team| team.games_played() == 2));
+        let points: u32 = standings.iter().map(|team| team.points()).sum();
+        assert!((96..=99).contains(&points));
+        // the same draws give the same final position as run_simulation, and a
+        // model that never scores draws every match
+        let rank = run_simulation(
+            "Wolves",
+            &league_table,
+            &matches,
+            &mut StdRng::seed_from_u64(51),
+        );
+        assert_eq!(table.find_final_rank("Wolves"), rank.final_rank() as i32);
+        assert_eq!(table.get_team("Wolves").unwrap().points(), rank.points());
+        let missing = run_simulation("Leeds", &league_table, &matches, &mut rand::rng());
+        assert_eq!((4, 0), (missing.final_rank(), missing.points()));
+
+        let fixed = simulate_table_with_model(
+            &league_table,
+            &matches,
+            &PoissonModel::new(0.0, 1.0),
+            &mut StdRng::seed_from_u64(51),
+        );
+        assert_eq!(32, fixed.get_team("Everton").unwrap().points());
+    }
+
+    #[test]
+    fn clinched_and_eliminated() {
+        let mut league_table = LeagueTable::new();
+        league_table.add_team("Celtic".to_string(), 90, 60).unwrap();
+        league_table
+            .add_team("Rangers".to_string(), 80, 30)
+            .unwrap();
+        league_table.add_team("Hearts".to_string(), 78, 5).unwrap();
+        league_table
+            .add_team("Aberdeen".to_string(), 70, -10)
+            .unwrap();
+        let mut fixtures = FixtureList::round_robin(["Rangers", "Hearts", "Aberdeen"], true);
+        let status = |team, rank, fixtures: &FixtureList| {
+            league_table.rank_status(team, rank, fixtures).unwrap()
+        };
+
+        // Rangers and Hearts can reach 92 and 90, Aberdeen at most 82
+        assert_eq!(RankStatus::Undecided, status("Celtic", 1, &fixtures));
+        assert_eq!(RankStatus::Clinched, status("Celtic", 3, &fixtures));
+        assert_eq!(RankStatus::Eliminated, status("Aberdeen", 1, &fixtures));
+        assert_eq!(RankStatus::Undecided, status("Aberdeen", 3, &fixtures));
+        assert_eq!(RankStatus::Clinched, status("Hearts", 4, &fixtures));
+
+        // once Rangers and Hearts have settled their meetings neither can catch Celtic
+        fixtures.force_result("Rangers", "Hearts", 0, 1);
+        fixtures.force_result("Hearts", "Rangers", 0, 0);
+        assert_eq!(RankStatus::Eliminated, status("Rangers", 1, &fixtures));
+        assert_eq!(RankStatus::Eliminated, status("Hearts", 1, &fixtures));
+        assert_eq!(RankStatus::Undecided, status("Hearts", 2, &fixtures));
+        assert_eq!(
+            Err(TableError::UnknownTeam("Hibs".to_string())),
+            league_table.rank_status("Hibs", 1, &fixtures)
+        );
+    }
+
+    #[test]
+    fn small_simulation() {
+        let mut league_table = LeagueTable::new();
+        league_table
+            .add_team("Liverpool".to_string(), 67, 40)
+            .unwrap();
+        league_table
+            .add_team("Arsenal".to_string(), 54, 28)
+            .unwrap();
+        league_table
+            .add_team("Nottingham Forest".to_string(), 48, 18)
+            .unwrap();
+        league_table
+            .add_team("Manchester City".to_string(), 47, 16)
+            .unwrap();
+
+        let matches = vec![
+            Match::from("Liverpool", "Arsenal"),
+            Match::from("Liverpool", "Nottingham Forest"),
+            Match::from("Liverpool", "Manchester City"),
+            Match::from("Arsenal", "Liverpool"),
+            Match::from("Arsenal", "Nottingham Forest"),
+            Match::from("Arsenal", "Manchester City"),
+            Match::from("Nottingham Forest", "Liverpool"),
+            Match::from("Nottingham Forest", "Arsenal"),
+            Match::from("Nottingham Forest", "Manchester City"),
+            Match::from("Manchester City", "Liverpool"),
+            Match::from("Manchester City", "Arsenal"),
+            Match::from("Manchester City", "Nottingham Forest"),
+        ];
+
+        let target = "Arsenal".to_string();
+        let mut count = 0.0;
+        for _x in 1..50 {
+            if run_simulation(&target, &league_table, &matches, &mut rand::rng()).final_rank() <= 1
+            {
+                count += 1.0;
+            }
+        }
+
+        println!("{} {}%", target, count / 50.0 * 100.0);
     }
 
     #[test]
     fn read_in_table() {
-        let mut new_league_table = LeagueTable::new();
-        read_standings(&mut new_league_table);
+        let (new_league_table, _) = read_standings().unwrap();
         new_league_table.print_table();
     }
 
+    #[test]
+    fn data_file_errors() {
+        let error = read_json::<Vec<Team>, _>(r#"[{"name": "Leeds", "#.as_bytes()).unwrap_err();
+        assert!(matches!(error, LeagueError::Json(_)), "{error}");
+        let json = r#"[{"name": "Leeds", "pts": 10, "goal_diff": 4}, {"pts": 8, "goal_diff": 1}]"#;
+        let error = read_json::<Vec<Team>, _>(json.as_bytes()).unwrap_err();
+        match &error {
+            LeagueError::Schema(format) => assert_eq!("[1]", format.path()),
+            _ => panic!("expected a schema error, got {error}"),
+        }
+        assert!(error.to_string().contains("name"));
+        let error = read_fixtures_from("data/missing.json").unwrap_err();
+        assert!(matches!(error, LeagueError::Io(_)), "{error}");
+    }
+
+    #[test]
+    fn standings_from_results_feed() {
+        let directory = std::env::temp_dir().join(format!("results-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let csv = directory.join("results.csv");
+        std::fs::write(
+            &csv,
+            "home,away,home_goals,away_goals,matchday\n\
+             Hearts,Hibs,2,1,1\n\
+             Aberdeen,Hearts,0,0,2\n\
+             Hibs,Aberdeen,3,1,3\n",
+        )
+        .unwrap();
+        let json = directory.join("results.json");
+        let results = read_results_from(&csv).unwrap();
+        assert_eq!(Some(3), results[2].fixture().matchday());
+        std::fs::write(&json, serde_json::to_string(&results).unwrap()).unwrap();
+
+        for path in [&csv, &json] {
+            let (table, snapshot) = read_standings_from_results(path).unwrap();
+            let names: Vec<&str> = table.teams().map(|team| team.name()).collect();
+            assert_eq!(vec!["Hearts", "Hibs", "Aberdeen"], names);
+            let hearts = table.get_team("Hearts").unwrap();
+            assert_eq!(
+                (4, 1, 2),
+                (hearts.points(), hearts.goal_diff(), hearts.games_played())
+            );
+            assert!(matches!(snapshot.source(), DataSource::File(source) if source == path));
+        }
+
+        std::fs::write(&csv, "home,away,home_goals,away_goals\nHearts,Hibs,two,1\n").unwrap();
+        match read_standings_from_results(&csv).unwrap_err() {
+            LeagueError::Schema(error) => assert_eq!("line 2", error.path()),
+            error => panic!("expected a schema error, got {error}"),
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn league_data_directories() {
+        let premier_league = LeagueId::default();
+        assert_eq!("premier-league", premier_league.as_str());
+        assert_eq!(
+            Some(premier_league.clone()),
+            LeagueId::new("premier-league")
+        );
+        assert_eq!(None, LeagueId::new("../secrets"));
+        assert_eq!(None, LeagueId::new(""));
+        assert!(serde_json::from_str::<LeagueId>(r#""Premier League""#).is_err());
+        assert!(premier_league
+            .path("standings.json")
+            .unwrap()
+            .ends_with("data/premier-league/standings.json"));
+
+        assert!(LeagueId::available().unwrap().contains(&premier_league));
+        let (table, _) = read_league_standings(&premier_league).unwrap();
+        let fixtures = read_league_fixtures(&premier_league).unwrap();
+        assert_eq!(Ok(()), table.validate_fixtures(&fixtures));
+        let missing = LeagueId::new("scottish-premiership").unwrap();
+        assert!(matches!(
+            read_league_standings(&missing),
+            Err(LeagueError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn async_readers() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let league = LeagueId::default();
+        let (table, snapshot) = runtime
+            .block_on(read_league_standings_async(&league))
+            .unwrap();
+        let fixtures = runtime
+            .block_on(read_league_fixtures_async(&league))
+            .unwrap();
+        let (sync_table, sync_snapshot) = read_league_standings(&league).unwrap();
+        assert_eq!(
+            sync_table.teams().collect::<Vec<_>>(),
+            table.teams().collect::<Vec<_>>()
+        );
+        assert_eq!(sync_snapshot, snapshot);
+        assert_eq!(read_league_fixtures(&league).unwrap(), fixtures);
+
+        let missing = LeagueId::new("scottish-premiership").unwrap();
+        let error = runtime
+            .block_on(read_league_fixtures_async(&missing))
+            .unwrap_err();
+        assert!(matches!(error, LeagueError::Io(_)), "{error}");
+    }
+
+    #[test]
+    fn data_files_at_any_path() {
+        let directory = std::env::temp_dir().join(format!("league-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let standings = directory.join("standings.json");
+        let json = r#"[{"name": "Hearts", "pts": 30, "goal_diff": 5},
+            {"name": "Hibs", "pts": 28, "goal_diff": 2}]"#;
+        std::fs::write(&standings, json).unwrap();
+        let fixtures = directory.join("fixtures.json");
+        std::fs::write(&fixtures, r#"[{"home": "Hibs", "away": "Hearts"}]"#).unwrap();
+        let results = directory.join("results.json");
+        let json = r#"[{"home": "Hearts", "away": "Hibs", "home_goals": 2, "away_goals": 1}]"#;
+        std::fs::write(&results, json).unwrap();
+
+        let (table, snapshot) = read_standings_from(&standings).unwrap();
+        assert_eq!(2, table.len());
+        assert!(matches!(snapshot.source(), DataSource::File(path) if *path == standings));
+        let fixture_list = read_fixtures_from(&fixtures).unwrap();
+        assert_eq!(vec![Match::from("Hibs", "Hearts")], fixture_list.to_vec());
+        let result_list = read_results_from(&results).unwrap();
+        assert_eq!(2, result_list[0].home_goals());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
     #[test]
     fn read_in_fixture_list() {
-        let mut fixtures_list = Vec::<Match>::new();
-        read_fixtures(&mut fixtures_list);
+        let fixtures_list = read_fixtures().unwrap();
         println!("Fixtures\n{fixtures_list:?}");
     }
 
     #[test]
     fn full_threadless_sim_test() {
-        let mut fixtures = Vec::<Match>::new();
-        let mut current_table = LeagueTable::new();
-        read_standings(&mut current_table);
-        read_fixtures(&mut fixtures);
+        let (current_table, _) = read_standings().unwrap();
+        let fixtures = read_fixtures().unwrap();
         let target_team = "Brighton".to_string();
         let rank = 7;
         let mut count = 0.0;
         for _i in 1..50 {
-            if run_simulation(&target_team, &mut current_table, &mut fixtures) <= rank {
+            if run_simulation(&target_team, &current_table, &fixtures, &mut rand::rng())
+                .final_rank()
+                <= rank
+            {
                 count += 1.0;
             }
         }
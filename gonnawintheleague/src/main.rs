@@ -8,38 +8,182 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use askama::Template;
 use gonnawintheleague as league;
-use serde::Deserialize;
-use std::sync::{Arc, Mutex};
-use std::thread;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-const NUM_SIMULATIONS: i32 = 4000;
+const NUM_SIMULATIONS: u32 = 16000;
 const NUM_THREADS: u32 = 4;
+// the most time a request will spend simulating, running fewer than NUM_SIMULATIONS if need be
+const SIMULATION_BUDGET: Duration = Duration::from_millis(500);
+// standings older than this are considered stale and will not be simulated on
+const MAX_DATA_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+// where responses fetched from a web API are kept, relative to the working directory
+const CACHE_DIRECTORY: &str = "cache";
 
-/// This structure holds the current data
+/// This structure holds the current data for one league
 /// which will serve as the starting point
 /// and the basis for the Monte Carlo simulation.
+struct LeagueData {
+    standings: league::LeagueTable,
+    fixtures: league::FixtureList,
+    snapshot: league::StandingsSnapshot,
+    format: league::LeagueFormat,
+}
+
+impl LeagueData {
+    /// gathers a league's data, failing if the fixtures do not fit the
+    /// standings and the length of a season in the league's format
+    fn new(
+        standings: league::LeagueTable,
+        snapshot: league::StandingsSnapshot,
+        fixtures: league::FixtureList,
+        format: league::LeagueFormat,
+    ) -> std::io::Result<Self> {
+        fixtures
+            .validate(&standings)
+            .and_then(|_| format.check_schedule(&standings, &fixtures))
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        Ok(Self {
+            standings,
+            fixtures,
+            snapshot,
+            format,
+        })
+    }
+}
+
+/// Holds the data for every league served, read in once at startup
+/// and swapped whole whenever a watched data file changes.
 ///
 /// Treating it as app state data allows us
 /// to only read the data and construct the structures once
 struct AppStateWithData {
-    standings: league::LeagueTable,
-    fixtures: Vec<league::Match>,
+    leagues: BTreeMap<league::LeagueId, RwLock<Arc<LeagueData>>>,
+    default_league: league::LeagueId,
+    // the threads every request's simulations share
+    pool: league::SimulationPool,
+}
+
+impl AppStateWithData {
+    /// looks up the league with the given id, falling back on the default
+    /// league when the id is empty or not one being served
+    fn league(&self, id: &str) -> (&league::LeagueId, Arc<LeagueData>) {
+        let (id, data) = league::LeagueId::new(id)
+            .and_then(|id| self.leagues.get_key_value(&id))
+            .or_else(|| self.leagues.get_key_value(&self.default_league))
+            .expect("the default league should always be served");
+        (id, data.read().unwrap().clone())
+    }
+
+    /// rereads a league's data from its provider, carrying on with the data
+    /// already served if the files cannot be read or do not agree, as can
+    /// happen while they are part way through being written
+    fn reload(&self, id: &league::LeagueId, provider: &dyn league::DataProvider) {
+        let reloaded = provider
+            .standings()
+            .and_then(|(standings, snapshot)| Ok((standings, snapshot, provider.fixtures()?)))
+            .and_then(|loaded| Ok((loaded, provider.format()?)))
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+            .and_then(|((standings, snapshot, fixtures), format)| {
+                LeagueData::new(standings, snapshot, fixtures, format)
+            });
+        match (reloaded, self.leagues.get(id)) {
+            (Ok(data), Some(served)) => {
+                println!("reloaded data for {id}");
+                *served.write().unwrap() = Arc::new(data);
+            }
+            (Err(error), _) => println!("kept previous data for {id}: {error}"),
+            (_, None) => {}
+        }
+    }
 }
+
 #[derive(Template)]
 #[template(path = "index.html")]
 struct IndexTemplate<'a> {
-    results: Option<&'a (i32, f32, String)>,
+    results: Option<&'a (String, f32, String, Option<&'static str>)>,
+    teams: Vec<&'a str>,
+    league: &'a str,
+    // every league served, each marked with whether it is the one shown
+    leagues: Vec<(&'a str, bool)>,
+    data_age: String,
+    stale: bool,
+}
+
+impl<'a> IndexTemplate<'a> {
+    /// builds the page for the chosen league held in the app state,
+    /// listing its teams alphabetically along with how fresh the standings are
+    fn new(
+        data: &'a AppStateWithData,
+        id: &'a league::LeagueId,
+        league: &'a LeagueData,
+        results: Option<&'a (String, f32, String, Option<&'static str>)>,
+    ) -> Self {
+        let mut teams: Vec<&str> = league.standings.teams().map(|team| team.name()).collect();
+        teams.sort();
+        Self {
+            results,
+            teams,
+            league: id.as_str(),
+            leagues: data
+                .leagues
+                .keys()
+                .map(|other| (other.as_str(), other == id))
+                .collect(),
+            data_age: describe_age(league.snapshot.age()),
+            stale: league.snapshot.is_stale(MAX_DATA_AGE),
+        }
+    }
+}
+
+/// describes how long ago the standings were updated in days or hours
+fn describe_age(age: Duration) -> String {
+    let hours = age.as_secs() / (60 * 60);
+    match hours {
+        0 => "less than an hour ago".to_string(),
+        1 => "1 hour ago".to_string(),
+        2..=47 => format!("{hours} hours ago"),
+        _ => format!("{} days ago", hours / 24),
+    }
+}
+
+#[derive(Deserialize)]
+struct LeagueQuery {
+    // the league to show, the default league when left out
+    #[serde(default)]
+    league: String,
 }
 
 #[derive(Deserialize)]
 struct FormData {
+    #[serde(default)]
+    league: String,
     team: String,
-    rank: i32,
+    // left empty when the team is only aiming to finish above a rival
+    #[serde(default, deserialize_with = "empty_as_none")]
+    rank: Option<i32>,
+    // when chosen, the team is aiming to finish above this rival instead of at a rank
+    #[serde(default)]
+    rival: String,
+}
+
+/// reads a number from a form field, treating an empty field as no number at all
+fn empty_as_none<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i32>, D::Error> {
+    match String::deserialize(deserializer)?.trim() {
+        "" => Ok(None),
+        number => number.parse().map(Some).map_err(serde::de::Error::custom),
+    }
 }
 
 /// implements the landing page before any calculations have been done
-async fn index() -> impl Responder {
-    let blank_template = IndexTemplate { results: None };
+async fn index(
+    query: web::Query<LeagueQuery>,
+    data: web::Data<AppStateWithData>,
+) -> impl Responder {
+    let (id, league) = data.league(&query.league);
+    let blank_template = IndexTemplate::new(&data, id, &league, None);
     HttpResponse::Ok()
         .content_type("text/html")
         .body(blank_template.render().unwrap())
@@ -48,16 +192,49 @@ async fn index() -> impl Responder {
 /// handles form processing, capturing and displaying of results
 async fn submit(form: web::Form<FormData>, data: web::Data<AppStateWithData>) -> impl Responder {
     let team = form.team.clone();
-    let rank = form.rank;
-    let (standings, fixtures) = (&data.standings, &data.fixtures);
-    let computed_results = (
-        rank,
-        calculate_results(&team, rank, standings, fixtures),
-        team,
-    );
-    let results_template = IndexTemplate {
-        results: Some(&computed_results),
+    let (target, goal) = match (form.rival.as_str(), form.rank) {
+        ("", Some(rank)) => (
+            league::Target::Rank(rank.max(0) as u32),
+            format!("in rank {rank} or above"),
+        ),
+        ("", None) => {
+            return HttpResponse::BadRequest()
+                .content_type("text/plain")
+                .body("Choose a rank to finish in or a rival to finish above")
+        }
+        (rival, _) => (
+            league::Target::Above(rival.to_string()),
+            format!("above {rival}"),
+        ),
     };
+    let (id, league) = data.league(&form.league);
+    let (standings, fixtures) = (&league.standings, &league.fixtures);
+    // refuse to simulate on stale standings, leaving the page to show the warning instead
+    let computed_results = (!league.snapshot.is_stale(MAX_DATA_AGE)).then(|| {
+        // a settled outcome is reported as such rather than as 0% or 100%
+        let verdict = match &target {
+            league::Target::Rank(rank) => match standings.rank_status(&team, *rank, fixtures) {
+                Ok(league::RankStatus::Clinched) => Some("mathematically certain to"),
+                Ok(league::RankStatus::Eliminated) => Some("mathematically unable to"),
+                _ => None,
+            },
+            league::Target::Above(_) => None,
+        };
+        (
+            goal,
+            calculate_results(
+                &team,
+                &target,
+                &league.format,
+                standings,
+                fixtures,
+                &data.pool,
+            ),
+            team,
+            verdict,
+        )
+    });
+    let results_template = IndexTemplate::new(&data, id, &league, computed_results.as_ref());
     HttpResponse::Ok()
         .content_type("text/html")
         .body(results_template.render().unwrap())
@@ -65,50 +242,167 @@ async fn submit(form: web::Form<FormData>, data: web::Data<AppStateWithData>) ->
 
 pub fn calculate_results(
     target_team: &str,
-    target_rank: i32,
+    target: &league::Target,
+    format: &league::LeagueFormat,
     standings: &league::LeagueTable,
-    fixtures: &Vec<league::Match>,
+    fixtures: &[league::Match],
+    pool: &league::SimulationPool,
 ) -> f32 {
-    // running tally instantiated as Arc holding Mutex to allow all threads to modify
-    let final_count = Arc::new(Mutex::new(0));
-
-    // spawn threads
-    thread::scope(|s| {
-        for _i in 0..NUM_THREADS {
-            s.spawn(|| {
-                let mut count = 0;
-                for _j in 0..NUM_SIMULATIONS {
-                    // if the target team achieves the target rank or better, add to the success tally
-                    if league::run_simulation(target_team, standings, fixtures) <= target_rank {
-                        count += 1;
-                    }
-                }
-                // access mutex to add this threads' count to the running total
-                let mut final_count = final_count.lock().unwrap();
-                *final_count += count;
-            });
-        }
-    });
+    // a split league's groups are only known once the season reaches the split
+    if let league::LeagueFormat::Split(split) = format {
+        let probability =
+            split.target_probability(standings, fixtures, target_team, target, NUM_SIMULATIONS);
+        return probability as f32 * 100.0;
+    }
 
-    // access final count mutex
-    let useable_count = final_count.lock().unwrap();
+    // a short enough run-in can be worked out exactly, unless a tie on points
+    // could leave goal difference to decide it
+    if let Some(probability) = league::enumerate_target(standings, fixtures, target_team, target) {
+        return probability as f32 * 100.0;
+    }
+
+    let config = league::SimulationConfig::new(NUM_SIMULATIONS)
+        .with_pool(pool.clone())
+        .with_rng_backend(league::RngBackend::Fast)
+        .with_time_budget(SIMULATION_BUDGET);
+    let report = league::run_simulations(&config, standings, fixtures);
+
+    // report the probability of success as a percent
+    report.target_probability(target_team, target) as f32 * 100.0
+}
+
+/// Returns the API key held in the given environment variable, or an error
+/// naming the variable if it is not set
+fn api_key(variable: &str) -> std::io::Result<String> {
+    std::env::var(variable).map_err(|_| {
+        let error = format!("set {variable} to the key for the API");
+        std::io::Error::new(std::io::ErrorKind::NotFound, error)
+    })
+}
 
-    // calculate probability of success as total successes over total number of simulations * 100 to report as percent
-    *useable_count as f32 / (NUM_SIMULATIONS as f32 * NUM_THREADS as f32) * 100.0
+/// Wraps a provider fetching from a web API in a cache under the cache
+/// directory, so that restarts within the hour spend none of the API's quota
+/// and can run offline on what was fetched
+fn cached<P>(provider: P, name: &str) -> Arc<dyn league::DataProvider + Send + Sync>
+where
+    P: league::DataProvider + Send + Sync + 'static,
+{
+    let directory = std::path::Path::new(CACHE_DIRECTORY).join(name);
+    Arc::new(league::CachedProvider::new(provider, directory))
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // read in data
-    let mut fixture_list = Vec::<league::Match>::new();
-    let mut current_table = league::LeagueTable::new();
-    league::read_standings(&mut current_table);
-    league::read_fixtures(&mut fixture_list);
+    // read in a single league fetched from API-Football when asked with
+    // "api-football <league> <season>", using the key in API_FOOTBALL_KEY, or
+    // from its saved responses with "api-football <standings> <fixtures>", or
+    // fetched from football-data.org with "football-data <competition>", using
+    // the token in FOOTBALL_DATA_TOKEN, or from the standings and fixtures
+    // files given as arguments, otherwise every league with a directory under
+    // data, loading the leagues concurrently and refusing to start on missing
+    // or malformed files. A league directory may also hold a format.json
+    // giving how its season is played out. Passing --watch as well reloads a league whenever
+    // its data files change, without restarting the server
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let watch = args.iter().any(|arg| arg == "--watch");
+    args.retain(|arg| arg != "--watch");
+    let invalid_data = |error| std::io::Error::new(std::io::ErrorKind::InvalidData, error);
+    let providers: Vec<(
+        league::LeagueId,
+        Arc<dyn league::DataProvider + Send + Sync>,
+    )> = match args.as_slice() {
+        [source, standings, fixtures] if source == "api-football" => {
+            let provider: Arc<dyn league::DataProvider + Send + Sync> =
+                match (standings.parse(), fixtures.parse()) {
+                    (Ok(league), Ok(season)) => {
+                        let key = api_key("API_FOOTBALL_KEY")?;
+                        let cache = format!("api-football-{league}-{season}");
+                        cached(league::ApiFootball::fetch(&key, league, season), &cache)
+                    }
+                    _ => Arc::new(league::ApiFootball::new(standings, fixtures)),
+                };
+            vec![(league::LeagueId::default(), provider)]
+        }
+        [source, competition] if source == "football-data" => {
+            let token = api_key("FOOTBALL_DATA_TOKEN")?;
+            let provider = league::FootballData::fetch(&token, competition);
+            let cache = format!("football-data-{competition}");
+            vec![(league::LeagueId::default(), cached(provider, &cache))]
+        }
+        [] => league::LeagueId::available()
+            .map_err(invalid_data)?
+            .into_iter()
+            .map(|id| {
+                let files = league::JsonFiles::new().with_league(id.clone());
+                (
+                    id,
+                    Arc::new(files) as Arc<dyn league::DataProvider + Send + Sync>,
+                )
+            })
+            .collect(),
+        _ => {
+            let mut files = league::JsonFiles::new().with_standings(&args[0]);
+            if let Some(fixtures) = args.get(1) {
+                files = files.with_fixtures(fixtures);
+            }
+            vec![(league::LeagueId::default(), Arc::new(files))]
+        }
+    };
+
+    let loads: Vec<_> = providers
+        .iter()
+        .map(|(id, provider)| {
+            (
+                id.clone(),
+                provider.clone(),
+                actix_web::rt::spawn(league::load_async(provider.clone())),
+            )
+        })
+        .collect();
+    let mut leagues = BTreeMap::new();
+    for (id, provider, load) in loads {
+        let (current_table, snapshot, fixture_list) = load
+            .await
+            .map_err(std::io::Error::other)?
+            .map_err(invalid_data)?;
+        println!("loaded {id} standings from {}", snapshot.source());
+        let format = provider.format().map_err(invalid_data)?;
+        let data = LeagueData::new(current_table, snapshot, fixture_list, format)?;
+        leagues.insert(id, RwLock::new(Arc::new(data)));
+    }
+    // the Premier League is shown first if it is served, otherwise the first league found
+    let default_league = if leagues.contains_key(&league::LeagueId::default()) {
+        league::LeagueId::default()
+    } else {
+        match leagues.keys().next() {
+            Some(id) => id.clone(),
+            None => {
+                let error = "no league data found under data";
+                return Err(std::io::Error::new(std::io::ErrorKind::NotFound, error));
+            }
+        }
+    };
     let state_data = web::Data::new(AppStateWithData {
-        standings: current_table,
-        fixtures: fixture_list,
+        leagues,
+        default_league,
+        pool: league::SimulationPool::new(NUM_THREADS),
     });
 
+    // the watchers stop once dropped, so they are held until the server stops
+    let mut watchers = Vec::new();
+    if watch {
+        for (id, provider) in providers {
+            let state = state_data.clone();
+            let reload_id = id.clone();
+            let watcher = league::DataWatcher::new(&provider.files(), move |_| {
+                state.reload(&reload_id, &*provider)
+            })
+            .map_err(std::io::Error::other)?;
+            println!("watching data files for {id}");
+            watchers.push(watcher);
+        }
+    }
+
     HttpServer::new(move || {
         App::new()
             .route("/", web::get().to(index))
@@ -117,5 +411,7 @@ async fn main() -> std::io::Result<()> {
     })
     .bind(("127.0.0.1", 8080))?
     .run()
-    .await
+    .await?;
+    drop(watchers);
+    Ok(())
 }
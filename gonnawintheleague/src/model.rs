@@ -0,0 +1,2312 @@
+//! Models used to sample the score of a single match during a simulation
+//!
+//! Every model implements ScoreModel, so simulations can be run with any of
+//! them in place of the default HistoricalAverageModel
+
+use crate::{
+    parse_json, FormatError, Match, MatchResult, AWAY_WEIGHTS, HOME_WEIGHTS, NEUTRAL_WEIGHTS,
+};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use rand::distr::weighted::WeightedIndex;
+use rand::prelude::*;
+use rand_distr::{Gamma, Poisson};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::ops::RangeInclusive;
+
+// goals per team per match in an average fixture, and the factor by which
+// playing at home raises them (and playing away lowers them), chosen so an
+// average fixture matches the means of the historical home and away weights
+const BASE_GOALS: f64 = 1.405;
+const HOME_ADVANTAGE: f64 = 1.26;
+// rating given to teams the Elo model has not seen before
+const INITIAL_ELO: f64 = 1500.0;
+const ELO_K_FACTOR: f64 = 20.0;
+const ELO_HOME_ADVANTAGE: f64 = 65.0;
+// share of matches between evenly rated sides that end level
+const ELO_DRAW_RATE: f64 = 0.28;
+// attempts at sampling a scoreline with the chosen outcome before settling on a minimal one
+const MAX_SCORE_ATTEMPTS: u32 = 100;
+// scores beyond this many goals a side are ignored when working out outcome
+// probabilities, and the number of rounds used when fitting ratings
+const MAX_OUTCOME_GOALS: u32 = 15;
+const CALIBRATION_ROUNDS: u32 = 200;
+// matches of average play added to every team's record, and to the home and
+// away goals, when fitting ratings, so that a team that never scored or never
+// conceded, or a side that never scored, is fitted a small rate rather than
+// zero; kept small, as a fit to odds has only a match or two per pairing
+const FIT_PRIOR_MATCHES: f64 = 0.1;
+// a team that has already played more than this many matches in the week
+// before a fixture scores this much less for each match over
+const CONGESTION_WINDOW_DAYS: i64 = 7;
+const CONGESTION_ALLOWANCE: u32 = 1;
+const CONGESTION_PENALTY: f64 = 0.05;
+// the league identifier of the historical averages across all four English tiers
+const ENGLISH_FOOTBALL: &str = "english-football";
+// home and away weights for no goals up to seven in each English division,
+// following Poisson distributions around the division's mean goals per game in
+// recent seasons: 1.53 and 1.21 in the Premier League, 1.40 and 1.10 in the
+// Championship, 1.45 and 1.17 in League One, and 1.38 and 1.12 in League Two
+const DIVISION_WEIGHTS: [(&str, [f32; 8], [f32; 8]); 4] = [
+    (
+        "premier-league",
+        [21.7, 33.1, 25.3, 12.9, 4.9, 1.5, 0.4, 0.1],
+        [29.8, 36.1, 21.8, 8.8, 2.7, 0.6, 0.1, 0.0],
+    ),
+    (
+        "championship",
+        [24.7, 34.5, 24.2, 11.3, 3.9, 1.1, 0.3, 0.1],
+        [33.3, 36.6, 20.1, 7.4, 2.0, 0.4, 0.1, 0.0],
+    ),
+    (
+        "league-one",
+        [23.5, 34.0, 24.7, 11.9, 4.3, 1.3, 0.3, 0.1],
+        [31.0, 36.3, 21.2, 8.3, 2.4, 0.6, 0.1, 0.0],
+    ),
+    (
+        "league-two",
+        [25.2, 34.7, 24.0, 11.0, 3.8, 1.0, 0.2, 0.0],
+        [32.6, 36.5, 20.5, 7.6, 2.1, 0.5, 0.1, 0.0],
+    ),
+];
+
+/// Samples the final score of a match from its fixture details
+pub trait ScoreModel {
+    /// Samples the home and away goals scored over the full 90 minutes of the fixture
+    ///
+    /// Forced results and live scores are handled by the simulation, so the
+    /// model only ever needs to sample a whole match
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32);
+
+    /// Called before each simulated season is played, so models that change
+    /// during a season can return to their starting state
+    fn start_season(&self) {}
+
+    /// Called with the final score of every match as a simulated season is played,
+    /// including forced results and completed live matches
+    fn record_result(&self, _fixture: &Match, _home_goals: u32, _away_goals: u32) {}
+}
+
+/// Samples every match from the same league-wide distributions of goals
+/// scored at home, away or at a neutral venue, regardless of the teams playing
+///
+/// The weights were calculated based on data from the following source:
+///    <https://fivethirtyeight.com/features/in-126-years-english-football-has-seen-13475-nil-nil-draws/>
+/// itself based on data collected by James Curley: <https://github.com/jalapic/engsoccerdata>
+///
+/// Other leagues and divisions can be modeled by supplying their own GoalWeights
+#[derive(Debug, Clone)]
+pub struct HistoricalAverageModel {
+    home: WeightedIndex<f32>,
+    away: WeightedIndex<f32>,
+    neutral: WeightedIndex<f32>,
+    // cumulative probabilities of each number of goals, for sampling by quantile
+    home_cumulative: Vec<f64>,
+    away_cumulative: Vec<f64>,
+    neutral_cumulative: Vec<f64>,
+    // alias tables for sampling whole seasons of goals at once
+    home_alias: AliasTable,
+    away_alias: AliasTable,
+    neutral_alias: AliasTable,
+}
+
+impl Default for HistoricalAverageModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoricalAverageModel {
+    /// create the model from the historical averages for English league football
+    pub fn new() -> Self {
+        Self::from_weights(&GoalWeights::default()).unwrap()
+    }
+
+    /// create the model from the built-in weights for the league with the given
+    /// identifier, as in GoalWeights::preset, or None if there are none
+    pub fn for_league(league: &str) -> Option<Self> {
+        GoalWeights::preset(league)
+            .map(|weights| Self::from_weights(&weights).expect("built-in weights should be valid"))
+    }
+
+    /// create the model from the given weights, failing if any list is empty,
+    /// contains a negative weight or has no positive weight
+    pub fn from_weights(weights: &GoalWeights) -> Result<Self, FormatError> {
+        let index = |path: &str, weights: &[f32]| {
+            WeightedIndex::new(weights).map_err(|error| FormatError {
+                path: path.to_string(),
+                message: error.to_string(),
+            })
+        };
+        let neutral = weights.neutral_weights();
+        Ok(Self {
+            home: index("home", &weights.home)?,
+            away: index("away", &weights.away)?,
+            neutral: index("neutral", &neutral)?,
+            home_cumulative: cumulative(&weights.home),
+            away_cumulative: cumulative(&weights.away),
+            neutral_cumulative: cumulative(&neutral),
+            home_alias: AliasTable::new(&weights.home),
+            away_alias: AliasTable::new(&weights.away),
+            neutral_alias: AliasTable::new(&neutral),
+        })
+    }
+
+    /// Returns the cumulative goal distributions of the home and away teams in
+    /// a match where the home team keeps the given share of its home advantage
+    ///
+    /// A partial share mixes the home and away distributions with the neutral
+    /// one, as if the match were at home with that chance and neutral otherwise
+    fn cumulative_at(&self, share: f64) -> (Cow<'_, [f64]>, Cow<'_, [f64]>) {
+        if share >= 1.0 {
+            return (
+                Cow::Borrowed(&self.home_cumulative),
+                Cow::Borrowed(&self.away_cumulative),
+            );
+        }
+        if share <= 0.0 {
+            return (
+                Cow::Borrowed(&self.neutral_cumulative),
+                Cow::Borrowed(&self.neutral_cumulative),
+            );
+        }
+        let mix = |venue: &[f64]| {
+            (0..venue.len().max(self.neutral_cumulative.len()))
+                .map(|goals| {
+                    let at = |cumulative: &[f64]| cumulative.get(goals).copied().unwrap_or(1.0);
+                    share * at(venue) + (1.0 - share) * at(&self.neutral_cumulative)
+                })
+                .collect::<Vec<f64>>()
+        };
+        (
+            Cow::Owned(mix(&self.home_cumulative)),
+            Cow::Owned(mix(&self.away_cumulative)),
+        )
+    }
+
+    /// Returns the probability of each number of goals for the home and away
+    /// teams in a match where the home team keeps the given share of its home
+    /// advantage, starting from no goals
+    pub(crate) fn goal_probabilities(&self, share: f64) -> (Vec<f64>, Vec<f64>) {
+        let (home_cumulative, away_cumulative) = self.cumulative_at(share);
+        let probabilities = |cumulative: &[f64]| {
+            cumulative
+                .iter()
+                .scan(0.0, |previous, &running| {
+                    let probability = running - *previous;
+                    *previous = running;
+                    Some(probability)
+                })
+                .collect()
+        };
+        (
+            probabilities(&home_cumulative),
+            probabilities(&away_cumulative),
+        )
+    }
+
+    /// Returns the home and away goals at the given quantiles, each between 0
+    /// and 1, of the goal distributions for a match where the home team keeps
+    /// the given share of its home advantage
+    pub(crate) fn quantile_at(&self, share: f64, (home, away): (f64, f64)) -> (u32, u32) {
+        let (home_cumulative, away_cumulative) = self.cumulative_at(share);
+        let goals = |cumulative: &[f64], quantile: f64| {
+            cumulative
+                .partition_point(|&probability| probability <= quantile)
+                .min(cumulative.len() - 1) as u32
+        };
+        (goals(&home_cumulative, home), goals(&away_cumulative, away))
+    }
+
+    /// Samples the home and away goals for a match where the home team keeps
+    /// the given share of its home advantage
+    pub(crate) fn sample_at<R: Rng + ?Sized>(&self, share: f64, rng: &mut R) -> (u32, u32) {
+        let (home_dist, away_dist) = if at_home(share, rng) {
+            (&self.home, &self.away)
+        } else {
+            (&self.neutral, &self.neutral)
+        };
+        (home_dist.sample(rng) as u32, away_dist.sample(rng) as u32)
+    }
+
+    /// Samples the home and away goals of a match for each of the given shares
+    /// of home advantage in a single pass, replacing the contents of scores
+    /// with one score per share
+    ///
+    /// Goals are drawn from alias tables, so each side's goals cost one random
+    /// number and a lookup, and the buffer can be reused from season to season
+    pub(crate) fn sample_all<R: Rng + ?Sized>(
+        &self,
+        shares: &[f64],
+        rng: &mut R,
+        scores: &mut Vec<(u32, u32)>,
+    ) {
+        scores.clear();
+        scores.extend(shares.iter().map(|&share| {
+            let (home, away) = if at_home(share, rng) {
+                (&self.home_alias, &self.away_alias)
+            } else {
+                (&self.neutral_alias, &self.neutral_alias)
+            };
+            (home.sample(rng), away.sample(rng))
+        }));
+    }
+}
+
+/// Decides whether a match where the home team keeps the given share of its
+/// home advantage is sampled from the home distributions rather than the
+/// neutral one, only drawing a random number for a partial share
+fn at_home<R: Rng + ?Sized>(share: f64, rng: &mut R) -> bool {
+    share >= 1.0 || (share > 0.0 && rng.random_bool(share))
+}
+
+/// Walker's alias table, for drawing from a discrete distribution in constant
+/// time: each column holds its own outcome up to some probability and another
+/// outcome, its alias, for the rest
+#[derive(Debug, Clone)]
+struct AliasTable {
+    probability: Vec<f64>,
+    alias: Vec<u32>,
+}
+
+impl AliasTable {
+    /// create a table for the given weights, which must be non-negative with
+    /// at least one positive, built by Vose's method
+    fn new(weights: &[f32]) -> Self {
+        let columns = weights.len();
+        let total: f64 = weights.iter().map(|&weight| f64::from(weight)).sum();
+        let mut scaled: Vec<f64> = weights
+            .iter()
+            .map(|&weight| f64::from(weight) * columns as f64 / total)
+            .collect();
+        let mut probability = vec![1.0; columns];
+        let mut alias: Vec<u32> = (0..columns as u32).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..columns).partition(|&column| scaled[column] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            probability[less] = scaled[less];
+            alias[less] = more as u32;
+            scaled[more] += scaled[less] - 1.0;
+            if scaled[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        Self { probability, alias }
+    }
+
+    /// Draws an outcome with a single random number: its whole part picks the
+    /// column and its fraction picks between the column and its alias
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
+        let columns = self.probability.len();
+        let draw = rng.random::<f64>() * columns as f64;
+        let column = (draw as usize).min(columns - 1);
+        if draw - (column as f64) < self.probability[column] {
+            column as u32
+        } else {
+            self.alias[column]
+        }
+    }
+}
+
+/// Returns the running totals of the weights, scaled so that the last is 1.0
+fn cumulative(weights: &[f32]) -> Vec<f64> {
+    let total: f64 = weights.iter().map(|&weight| f64::from(weight)).sum();
+    weights
+        .iter()
+        .scan(0.0, |running, &weight| {
+            *running += f64::from(weight) / total;
+            Some(*running)
+        })
+        .collect()
+}
+
+impl ScoreModel for HistoricalAverageModel {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        self.sample_at(fixture.home_advantage_share(), rng)
+    }
+}
+
+/// Relative frequencies with which a team scores each number of goals, the
+/// first weight being for no goals, at home, away and at a neutral venue
+///
+/// Lists may be of any length. Loaded from json of the form
+/// {"home": [18.8, 30.3, 24.8], "away": [33.8, 36.2, 19.3], "neutral": [26.3, 33.25, 22.05]},
+/// where neutral may be omitted to use the mean of the home and away weights
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GoalWeights {
+    pub home: Vec<f32>,
+    pub away: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub neutral: Option<Vec<f32>>,
+}
+
+impl Default for GoalWeights {
+    /// Defaults to the historical averages for English league football
+    fn default() -> Self {
+        Self {
+            home: HOME_WEIGHTS.to_vec(),
+            away: AWAY_WEIGHTS.to_vec(),
+            neutral: Some(NEUTRAL_WEIGHTS.to_vec()),
+        }
+    }
+}
+
+impl GoalWeights {
+    /// Reads weights from json, in the format described on GoalWeights
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, FormatError> {
+        parse_json(reader)
+    }
+
+    /// Works out weights from a csv of historical results in the format of
+    /// engsoccerdata, <https://github.com/jalapic/engsoccerdata>, counting how
+    /// often home and away sides scored each number of goals in the matches of
+    /// the given tier, 1 being the top flight, played between the given dates
+    ///
+    /// Each weight is the percentage of matches with that many goals, so the
+    /// weights read like the historical averages, and the lists run up to the
+    /// most goals scored. Only the "Date", "tier", "hgoal" and "vgoal" columns
+    /// are read. Fails if a record cannot be read, naming its line as
+    /// parse_xg_csv does, or if no matches fall in the tier and dates
+    pub fn from_engsoccerdata<R: Read>(
+        reader: R,
+        tier: u32,
+        dates: RangeInclusive<NaiveDate>,
+    ) -> Result<Self, FormatError> {
+        let (mut home, mut away) = (Vec::new(), Vec::new());
+        let mut matches = 0;
+        for record in csv::Reader::from_reader(reader).deserialize() {
+            let record: HistoricalResult = record.map_err(|error: csv::Error| FormatError {
+                path: error
+                    .position()
+                    .map(|position| format!("line {}", position.line()))
+                    .unwrap_or_default(),
+                message: error.to_string(),
+            })?;
+            if record.tier != tier || !dates.contains(&record.date) {
+                continue;
+            }
+            for (counts, goals) in [(&mut home, record.hgoal), (&mut away, record.vgoal)] {
+                let goals = goals as usize;
+                if counts.len() <= goals {
+                    counts.resize(goals + 1, 0u32);
+                }
+                counts[goals] += 1;
+            }
+            matches += 1;
+        }
+        if matches == 0 {
+            return Err(FormatError {
+                path: String::new(),
+                message: format!(
+                    "no tier {tier} matches were played from {} to {}",
+                    dates.start(),
+                    dates.end()
+                ),
+            });
+        }
+        let percentages = |counts: Vec<u32>| {
+            counts
+                .into_iter()
+                .map(|count| (f64::from(count) * 100.0 / f64::from(matches)) as f32)
+                .collect()
+        };
+        Ok(Self {
+            home: percentages(home),
+            away: percentages(away),
+            neutral: None,
+        })
+    }
+
+    /// Returns the built-in weights for the league with the given identifier:
+    /// "english-football" for the historical averages across all four English
+    /// tiers, the default, or "premier-league", "championship", "league-one" or
+    /// "league-two" for a single division. None for any other identifier
+    pub fn preset(league: &str) -> Option<Self> {
+        if league == ENGLISH_FOOTBALL {
+            return Some(Self::default());
+        }
+        DIVISION_WEIGHTS
+            .iter()
+            .find(|(id, _, _)| *id == league)
+            .map(|(_, home, away)| Self {
+                home: home.to_vec(),
+                away: away.to_vec(),
+                neutral: None,
+            })
+    }
+
+    /// Returns the neutral weights, or the mean of the home and away weights
+    /// if there are none
+    fn neutral_weights(&self) -> Vec<f32> {
+        if let Some(neutral) = &self.neutral {
+            return neutral.clone();
+        }
+        let weight = |weights: &[f32], goals: usize| weights.get(goals).copied().unwrap_or(0.0);
+        (0..self.home.len().max(self.away.len()))
+            .map(|goals| (weight(&self.home, goals) + weight(&self.away, goals)) / 2.0)
+            .collect()
+    }
+}
+
+/// The columns of an engsoccerdata results csv needed to work out goal weights
+#[derive(Debug, Deserialize)]
+struct HistoricalResult {
+    #[serde(rename = "Date")]
+    date: NaiveDate,
+    tier: u32,
+    hgoal: u32,
+    vgoal: u32,
+}
+
+/// Goal weights looked up by league identifier, holding every built-in preset
+/// of GoalWeights::preset to begin with so that custom leagues can be added
+/// alongside them
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightPresets(BTreeMap<String, GoalWeights>);
+
+impl Default for WeightPresets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WeightPresets {
+    /// create the presets holding every built-in set of weights
+    pub fn new() -> Self {
+        let leagues = DIVISION_WEIGHTS.iter().map(|(id, _, _)| *id);
+        Self(
+            std::iter::once(ENGLISH_FOOTBALL)
+                .chain(leagues)
+                .filter_map(|id| Some((id.to_string(), GoalWeights::preset(id)?)))
+                .collect(),
+        )
+    }
+
+    /// Adds weights under the given league identifier, replacing any already
+    /// held under it, failing as HistoricalAverageModel::from_weights does if
+    /// the weights could not be sampled from
+    pub fn register(&mut self, league: &str, weights: GoalWeights) -> Result<(), FormatError> {
+        HistoricalAverageModel::from_weights(&weights)?;
+        self.0.insert(league.to_string(), weights);
+        Ok(())
+    }
+
+    /// Returns the weights held under the given league identifier
+    pub fn get(&self, league: &str) -> Option<&GoalWeights> {
+        self.0.get(league)
+    }
+
+    /// Returns every league identifier held, in alphabetical order
+    pub fn leagues(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// create a model from the weights held under the given league identifier
+    pub fn model(&self, league: &str) -> Option<HistoricalAverageModel> {
+        self.get(league).map(|weights| {
+            HistoricalAverageModel::from_weights(weights).expect("registered weights are checked")
+        })
+    }
+}
+
+/// Attack and defense strengths of a single team, relative to the league average of 1.0
+///
+/// Attack above 1.0 scores more than an average side; defense above 1.0
+/// concedes more than an average side. A team with its own home advantage
+/// uses it in place of the league's when playing at home
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct TeamRating {
+    pub attack: f64,
+    pub defense: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_advantage: Option<f64>,
+}
+
+impl Default for TeamRating {
+    /// Defaults to an average team
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+impl TeamRating {
+    /// create a rating with the given attack and defense, and the league's home advantage
+    pub fn new(attack: f64, defense: f64) -> Self {
+        Self {
+            attack,
+            defense,
+            home_advantage: None,
+        }
+    }
+
+    /// Sets the factor by which playing at home raises this team's expected
+    /// goals and lowers its opponent's
+    pub fn with_home_advantage(mut self, home_advantage: f64) -> Self {
+        self.home_advantage = Some(home_advantage);
+        self
+    }
+}
+
+/// Expected goals created and conceded by a single team over the matches it
+/// has played, as exported by sites such as Understat or FBref
+///
+/// Read from json as an array of {"team": "Arsenal", "matches": 10, "xg_for": 19.2, "xg_against": 8.1},
+/// or from csv with the header team,matches,xg_for,xg_against
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TeamXg {
+    pub team: String,
+    pub matches: u32,
+    pub xg_for: f64,
+    pub xg_against: f64,
+}
+
+/// Reads per-team expected goals from json, in the format described on TeamXg
+pub fn parse_xg_json<R: Read>(reader: R) -> Result<Vec<TeamXg>, FormatError> {
+    parse_json(reader)
+}
+
+/// Reads per-team expected goals from csv, in the format described on TeamXg
+///
+/// The path of any error is the line of the offending record, such as "line 3"
+pub fn parse_xg_csv<R: Read>(reader: R) -> Result<Vec<TeamXg>, FormatError> {
+    csv::Reader::from_reader(reader)
+        .deserialize()
+        .collect::<Result<_, csv::Error>>()
+        .map_err(|error| FormatError {
+            path: error
+                .position()
+                .map(|position| format!("line {}", position.line()))
+                .unwrap_or_default(),
+            message: error.to_string(),
+        })
+}
+
+/// Decimal 1X2 bookmaker odds for a single match, played at the home team's ground
+///
+/// Read from json as an array of
+/// {"home": "Arsenal", "away": "Chelsea", "home_win": 1.9, "draw": 3.6, "away_win": 4.2}
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MatchOdds {
+    pub home: String,
+    pub away: String,
+    pub home_win: f64,
+    pub draw: f64,
+    pub away_win: f64,
+}
+
+impl MatchOdds {
+    /// Returns the bookmaker's margin, such as 0.05 for a 5% overround
+    pub fn overround(&self) -> f64 {
+        1.0 / self.home_win + 1.0 / self.draw + 1.0 / self.away_win - 1.0
+    }
+
+    /// Returns the probabilities of a home win, draw and away win implied by
+    /// the odds, scaled so that they sum to one
+    pub fn implied_probabilities(&self) -> (f64, f64, f64) {
+        let total = self.overround() + 1.0;
+        (
+            1.0 / self.home_win / total,
+            1.0 / self.draw / total,
+            1.0 / self.away_win / total,
+        )
+    }
+}
+
+/// Reads bookmaker odds from json, in the format described on MatchOdds
+pub fn parse_odds_json<R: Read>(reader: R) -> Result<Vec<MatchOdds>, FormatError> {
+    parse_json(reader)
+}
+
+/// The distribution a parametric model draws each side's goals from, given
+/// the side's expected goals
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum GoalDistribution {
+    /// Variance equal to the mean
+    #[default]
+    Poisson,
+    /// Variance of mean + mean² / dispersion, giving more blowouts than a
+    /// Poisson distribution; the smaller the dispersion the wider the spread
+    NegativeBinomial { dispersion: f64 },
+}
+
+impl GoalDistribution {
+    /// Samples a goal count with the given mean
+    fn sample(self, mean: f64, rng: &mut dyn RngCore) -> u32 {
+        match self {
+            Self::Poisson => sample_poisson(mean, rng),
+            // a Poisson whose mean is itself drawn from a gamma distribution
+            Self::NegativeBinomial { dispersion } => {
+                match Gamma::new(dispersion, mean / dispersion) {
+                    Ok(gamma) => sample_poisson(gamma.sample(rng), rng),
+                    Err(_) => sample_poisson(mean, rng),
+                }
+            }
+        }
+    }
+}
+
+/// Samples each side's goals from a Poisson distribution, or another
+/// GoalDistribution, whose mean is the league's base rate, scaled by the side's
+/// attack, the opponent's defense and home advantage
+///
+/// Teams without a rating are treated as average. Loaded from json of the form
+/// {"base_goals": 1.4, "home_advantage": 1.26, "ratings": {"Arsenal": {"attack": 1.3, "defense": 0.7,
+/// "home_advantage": 1.4}}, "distribution": {"NegativeBinomial": {"dispersion": 5.0}}}, where all
+/// but the ratings, and any team's home advantage, may be omitted
+///
+/// Specific pairings can be nudged by an optional "head_to_head" object such as
+/// {"Spurs": {"Chelsea": 0.8}}, under which Spurs are expected to score 80% of
+/// their usual goals against Chelsea
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PoissonModel {
+    #[serde(default = "default_base_goals")]
+    base_goals: f64,
+    #[serde(default = "default_home_advantage")]
+    home_advantage: f64,
+    #[serde(default)]
+    ratings: HashMap<String, TeamRating>,
+    #[serde(default)]
+    distribution: GoalDistribution,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    head_to_head: HashMap<String, HashMap<String, f64>>,
+}
+
+fn default_base_goals() -> f64 {
+    BASE_GOALS
+}
+
+fn default_home_advantage() -> f64 {
+    HOME_ADVANTAGE
+}
+
+impl Default for PoissonModel {
+    fn default() -> Self {
+        Self::new(BASE_GOALS, HOME_ADVANTAGE)
+    }
+}
+
+impl PoissonModel {
+    /// create a model in which every team is average, given the goals per team
+    /// in an average fixture and the home advantage factor
+    pub fn new(base_goals: f64, home_advantage: f64) -> Self {
+        Self {
+            base_goals,
+            home_advantage,
+            ratings: HashMap::new(),
+            distribution: GoalDistribution::Poisson,
+            head_to_head: HashMap::new(),
+        }
+    }
+
+    /// Sets the head to head adjustment of every pairing that has met before
+    /// from how each side scored against what its rating predicted
+    ///
+    /// Each adjustment is the ratio of goals scored to goals expected in past
+    /// meetings, with the given number of expected goals added to both so that
+    /// a handful of meetings only nudges the pairing
+    pub fn with_head_to_head(mut self, meetings: &[MatchResult], prior_goals: f64) -> Self {
+        // goals scored and expected by each team against each opponent
+        let mut totals: HashMap<(&str, &str), (f64, f64)> = HashMap::new();
+        for meeting in meetings {
+            let fixture = meeting.fixture();
+            let (home, away) = self.rated_goals(fixture);
+            let entry = totals.entry((fixture.home(), fixture.away())).or_default();
+            entry.0 += f64::from(meeting.home_goals());
+            entry.1 += home;
+            let entry = totals.entry((fixture.away(), fixture.home())).or_default();
+            entry.0 += f64::from(meeting.away_goals());
+            entry.1 += away;
+        }
+        for ((team, opponent), (scored, expected)) in totals {
+            self.set_head_to_head(
+                team,
+                opponent,
+                (scored + prior_goals) / (expected + prior_goals),
+            );
+        }
+        self
+    }
+
+    /// Sets the factor by which the team's expected goals against the opponent
+    /// are multiplied
+    pub fn set_head_to_head(&mut self, team: &str, opponent: &str, factor: f64) {
+        self.head_to_head
+            .entry(team.to_string())
+            .or_default()
+            .insert(opponent.to_string(), factor);
+    }
+
+    /// Returns the factor by which the team's expected goals against the
+    /// opponent are multiplied, which is 1.0 for pairings without one
+    pub fn head_to_head(&self, team: &str, opponent: &str) -> f64 {
+        self.head_to_head
+            .get(team)
+            .and_then(|opponents| opponents.get(opponent))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Sets the distribution goals are drawn from
+    pub fn with_distribution(mut self, distribution: GoalDistribution) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    /// create a model whose ratings are each team's expected goals for and
+    /// against per match, relative to the league average, which is taken as the
+    /// base goals. Teams yet to play are left average
+    pub fn from_xg(xg: &[TeamXg]) -> Self {
+        let played = xg.iter().filter(|team| team.matches > 0);
+        let matches: u32 = played.clone().map(|team| team.matches).sum();
+        if matches == 0 {
+            return Self::default();
+        }
+        let mut model = Self::new(
+            played.clone().map(|team| team.xg_for).sum::<f64>() / f64::from(matches),
+            HOME_ADVANTAGE,
+        );
+        for team in played {
+            let per_match = f64::from(team.matches) * model.base_goals;
+            model.set_rating(
+                &team.team,
+                TeamRating::new(team.xg_for / per_match, team.xg_against / per_match),
+            );
+        }
+        model
+    }
+
+    /// create a model whose ratings and home advantage best reproduce the
+    /// probabilities implied by bookmaker odds
+    ///
+    /// Each match's odds are first turned into the expected goals of both
+    /// sides, which are then fitted as in from_results
+    ///
+    /// Fails if any odds are not a finite number above 1, or as from_results does
+    pub fn from_odds(odds: &[MatchOdds]) -> Result<Self, FormatError> {
+        let games = odds
+            .iter()
+            .enumerate()
+            .map(|(index, game)| {
+                let prices = [game.home_win, game.draw, game.away_win];
+                if !prices.iter().all(|price| price.is_finite() && *price > 1.0) {
+                    return Err(FormatError {
+                        path: format!("[{index}]"),
+                        message: "odds must be finite and above 1".to_string(),
+                    });
+                }
+                let (home, away) = goals_from_probabilities(game.implied_probabilities());
+                Ok((game.home.as_str(), game.away.as_str(), home, away))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::fit(&games)
+    }
+
+    /// create a model from the maximum likelihood estimates of every team's
+    /// ratings, the base goals and the home advantage given past results,
+    /// which may span several seasons
+    ///
+    /// Every team's record, and the home and away goals, are padded with a
+    /// tenth of a match of average play, so a team that never scored or never
+    /// conceded is rated low rather than zero. Fails if the fit does not
+    /// settle on finite, positive ratings
+    pub fn from_results(results: &[MatchResult]) -> Result<Self, FormatError> {
+        let games: Vec<(&str, &str, f64, f64)> = results
+            .iter()
+            .map(|result| {
+                (
+                    result.fixture().home(),
+                    result.fixture().away(),
+                    f64::from(result.home_goals()),
+                    f64::from(result.away_goals()),
+                )
+            })
+            .collect();
+        Self::fit(&games)
+    }
+
+    /// Fits a model to the home and away goals of each game by maximum likelihood
+    ///
+    /// Each parameter in turn is set to the value at which the goals it
+    /// governs equal their expectation, which is where the Poisson likelihood
+    /// peaks with the others held fixed, until the whole converges. Each
+    /// estimate is padded with FIT_PRIOR_MATCHES of average play
+    fn fit(games: &[(&str, &str, f64, f64)]) -> Result<Self, FormatError> {
+        if games.is_empty() {
+            return Ok(Self::default());
+        }
+        let mut ratings: HashMap<&str, TeamRating> = HashMap::new();
+        let mut home_rate = BASE_GOALS * HOME_ADVANTAGE;
+        let mut away_rate = BASE_GOALS / HOME_ADVANTAGE;
+        for &(home, away, _, _) in games {
+            ratings.entry(home).or_default();
+            ratings.entry(away).or_default();
+        }
+
+        for _ in 0..CALIBRATION_ROUNDS {
+            // the goals an average side scores in a match at the current rates
+            let prior = FIT_PRIOR_MATCHES * (home_rate + away_rate) / 2.0;
+            let mut scored: HashMap<&str, (f64, f64)> = HashMap::new();
+            for &(home, away, home_goals, away_goals) in games {
+                let entry = scored.entry(home).or_default();
+                entry.0 += home_goals;
+                entry.1 += home_rate * ratings[away].defense;
+                let entry = scored.entry(away).or_default();
+                entry.0 += away_goals;
+                entry.1 += away_rate * ratings[home].defense;
+            }
+            for (team, (goals, expected)) in scored {
+                ratings.entry(team).or_default().attack = (goals + prior) / (expected + prior);
+            }
+
+            let mut conceded: HashMap<&str, (f64, f64)> = HashMap::new();
+            for &(home, away, home_goals, away_goals) in games {
+                let entry = conceded.entry(home).or_default();
+                entry.0 += away_goals;
+                entry.1 += away_rate * ratings[away].attack;
+                let entry = conceded.entry(away).or_default();
+                entry.0 += home_goals;
+                entry.1 += home_rate * ratings[home].attack;
+            }
+            for (team, (goals, expected)) in conceded {
+                ratings.entry(team).or_default().defense = (goals + prior) / (expected + prior);
+            }
+
+            let (mut home_goals, mut home_expected) = (0.0, 0.0);
+            let (mut away_goals, mut away_expected) = (0.0, 0.0);
+            for &(home, away, home_scored, away_scored) in games {
+                home_goals += home_scored;
+                home_expected += ratings[home].attack * ratings[away].defense;
+                away_goals += away_scored;
+                away_expected += ratings[away].attack * ratings[home].defense;
+            }
+            home_rate = (home_goals + FIT_PRIOR_MATCHES * BASE_GOALS * HOME_ADVANTAGE)
+                / (home_expected + FIT_PRIOR_MATCHES);
+            away_rate = (away_goals + FIT_PRIOR_MATCHES * BASE_GOALS / HOME_ADVANTAGE)
+                / (away_expected + FIT_PRIOR_MATCHES);
+
+            // keep the average team at 1.0 by moving the difference into the rates
+            let teams = ratings.len() as f64;
+            let attack = ratings.values().map(|rating| rating.attack).sum::<f64>() / teams;
+            let defense = ratings.values().map(|rating| rating.defense).sum::<f64>() / teams;
+            for rating in ratings.values_mut() {
+                rating.attack /= attack;
+                rating.defense /= defense;
+            }
+            home_rate *= attack * defense;
+            away_rate *= attack * defense;
+        }
+
+        let fitted = |value: f64| value.is_finite() && value > 0.0;
+        let unfitted = |path: &str| FormatError {
+            path: path.to_string(),
+            message: "the fit did not settle on a finite, positive value".to_string(),
+        };
+        if !fitted(home_rate) || !fitted(away_rate) {
+            return Err(unfitted("home_advantage"));
+        }
+        let mut model = Self::new(
+            (home_rate * away_rate).sqrt(),
+            (home_rate / away_rate).sqrt(),
+        );
+        for (team, rating) in ratings {
+            if !fitted(rating.attack) || !fitted(rating.defense) {
+                return Err(unfitted(team));
+            }
+            model.set_rating(team, rating);
+        }
+        Ok(model)
+    }
+
+    /// Reads a model from json, in the format described on PoissonModel
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, FormatError> {
+        parse_json(reader)
+    }
+
+    /// Sets the rating of the named team
+    pub fn set_rating(&mut self, team: &str, rating: TeamRating) {
+        self.ratings.insert(team.to_string(), rating);
+    }
+
+    /// Returns the rating of the named team, or an average rating if it has none
+    pub fn rating(&self, team: &str) -> TeamRating {
+        self.ratings.get(team).copied().unwrap_or_default()
+    }
+
+    /// Returns the goals per team in an average fixture
+    pub fn base_goals(&self) -> f64 {
+        self.base_goals
+    }
+
+    /// Returns the factor by which playing at home raises a team's expected goals
+    pub fn home_advantage(&self) -> f64 {
+        self.home_advantage
+    }
+
+    /// Returns the distribution goals are drawn from
+    pub fn distribution(&self) -> GoalDistribution {
+        self.distribution
+    }
+
+    /// create a model whose ratings are this model's, taken as the prior,
+    /// updated by each result in turn
+    ///
+    /// Each rating is the posterior mean of a gamma prior worth the given
+    /// number of matches against average opposition, so a few early results
+    /// only nudge ratings carried over from last season. The base goals and home
+    /// advantage are kept as they are
+    pub fn updated(&self, results: &[MatchResult], prior_matches: f64) -> Self {
+        let mut model = self.clone();
+        // goals and expected goals against average ratings behind each team's
+        // attack and defense, starting from the prior's pseudo-matches
+        let mut evidence: HashMap<&str, [(f64, f64); 2]> = HashMap::new();
+        for result in results {
+            let fixture = result.fixture();
+            let (home, away) = (model.rating(fixture.home()), model.rating(fixture.away()));
+            let advantage = model.advantage(fixture);
+            let home_exposure = model.base_goals * advantage;
+            let away_exposure = model.base_goals / advantage;
+            let observations = [
+                (
+                    fixture.home(),
+                    0,
+                    result.home_goals(),
+                    home_exposure * away.defense,
+                ),
+                (
+                    fixture.away(),
+                    1,
+                    result.home_goals(),
+                    home_exposure * home.attack,
+                ),
+                (
+                    fixture.away(),
+                    0,
+                    result.away_goals(),
+                    away_exposure * home.defense,
+                ),
+                (
+                    fixture.home(),
+                    1,
+                    result.away_goals(),
+                    away_exposure * away.attack,
+                ),
+            ];
+            for (team, strength, goals, exposure) in observations {
+                let entry = evidence.entry(team).or_insert_with(|| {
+                    let prior = self.rating(team);
+                    let exposure = prior_matches * self.base_goals;
+                    [
+                        (exposure * prior.attack, exposure),
+                        (exposure * prior.defense, exposure),
+                    ]
+                });
+                entry[strength].0 += f64::from(goals);
+                entry[strength].1 += exposure;
+            }
+            for team in [fixture.home(), fixture.away()] {
+                let [attack, defense] = evidence[team];
+                let rating = model.ratings.entry(team.to_string()).or_default();
+                rating.attack = attack.0 / attack.1;
+                rating.defense = defense.0 / defense.1;
+            }
+        }
+        model
+    }
+
+    /// Returns the factor by which the named team's expected goals rise at
+    /// home, which is the league's home advantage unless the team has its own
+    pub fn home_advantage_of(&self, team: &str) -> f64 {
+        self.rating(team)
+            .home_advantage
+            .unwrap_or(self.home_advantage)
+    }
+
+    /// Returns the factor by which the fixture's venue favours the home team,
+    /// shrinking towards 1.0 as the match keeps less of its home advantage
+    fn advantage(&self, fixture: &Match) -> f64 {
+        self.home_advantage_of(fixture.home())
+            .powf(fixture.home_advantage_share())
+    }
+
+    /// Returns the mean number of goals expected from the home and away teams
+    pub fn expected_goals(&self, fixture: &Match) -> (f64, f64) {
+        let (home, away) = self.rated_goals(fixture);
+        (
+            home * self.head_to_head(fixture.home(), fixture.away()),
+            away * self.head_to_head(fixture.away(), fixture.home()),
+        )
+    }
+
+    /// Returns the expected goals from the teams' ratings alone, before any
+    /// head to head adjustment
+    fn rated_goals(&self, fixture: &Match) -> (f64, f64) {
+        let home = self.rating(fixture.home());
+        let away = self.rating(fixture.away());
+        let advantage = self.advantage(fixture);
+        (
+            self.base_goals * home.attack * away.defense * advantage,
+            self.base_goals * away.attack * home.defense / advantage,
+        )
+    }
+
+    /// Returns the probabilities of a home win, draw and away win, treating
+    /// goals as Poisson whatever the model's GoalDistribution
+    pub fn outcome_probabilities(&self, fixture: &Match) -> (f64, f64, f64) {
+        let (home, away) = self.expected_goals(fixture);
+        poisson_outcome_probabilities(home, away)
+    }
+
+    /// Returns the betting markets for the fixture, worked out exactly from
+    /// independent Poisson goals as in outcome_probabilities
+    pub fn markets(&self, fixture: &Match) -> MatchMarkets {
+        let (home, away) = self.expected_goals(fixture);
+        let (home, away) = (poisson_pmf(home), poisson_pmf(away));
+        MatchMarkets {
+            scores: home
+                .iter()
+                .map(|home| away.iter().map(|away| home * away).collect())
+                .collect(),
+        }
+    }
+}
+
+/// Returns the probability of each number of goals up to the cap for
+/// Poisson goals with the given mean
+fn poisson_pmf(mean: f64) -> Vec<f64> {
+    let mut probabilities = vec![(-mean).exp()];
+    for goals in 1..=MAX_OUTCOME_GOALS {
+        probabilities.push(probabilities[goals as usize - 1] * mean / f64::from(goals));
+    }
+    probabilities
+}
+
+/// Returns the probabilities of a home win, draw and away win when each side's
+/// goals are Poisson with the given means
+fn poisson_outcome_probabilities(home: f64, away: f64) -> (f64, f64, f64) {
+    let (home, away) = (poisson_pmf(home), poisson_pmf(away));
+    let mut outcome = (0.0, 0.0, 0.0);
+    for (home_goals, home_probability) in home.iter().enumerate() {
+        for (away_goals, away_probability) in away.iter().enumerate() {
+            let probability = home_probability * away_probability;
+            match home_goals.cmp(&away_goals) {
+                Ordering::Greater => outcome.0 += probability,
+                Ordering::Equal => outcome.1 += probability,
+                Ordering::Less => outcome.2 += probability,
+            }
+        }
+    }
+    outcome
+}
+
+/// The probability of every scoreline of a single match, from which the usual
+/// betting markets are read off
+///
+/// Built exactly by PoissonModel::markets, or for any model by sampling
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchMarkets {
+    // the probability of each score, indexed by home goals then away goals
+    scores: Vec<Vec<f64>>,
+}
+
+impl MatchMarkets {
+    /// create the markets for a fixture by sampling its score from the model
+    /// the given number of times
+    pub fn sampled<M, R>(model: &M, fixture: &Match, samples: u32, rng: &mut R) -> Self
+    where
+        M: ScoreModel + ?Sized,
+        R: Rng,
+    {
+        let mut scores: Vec<Vec<f64>> = Vec::new();
+        let share = 1.0 / f64::from(samples.max(1));
+        for _ in 0..samples {
+            let (home, away) = model.sample_score(fixture, rng);
+            let (home, away) = (home as usize, away as usize);
+            let size = scores.len().max(home + 1).max(away + 1);
+            for row in &mut scores {
+                row.resize(size, 0.0);
+            }
+            scores.resize(size, vec![0.0; size]);
+            scores[home][away] += share;
+        }
+        Self { scores }
+    }
+
+    /// Returns the probability of the match ending with the given score
+    pub fn score(&self, home_goals: u32, away_goals: u32) -> f64 {
+        self.scores
+            .get(home_goals as usize)
+            .and_then(|row| row.get(away_goals as usize))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the grid of scoreline probabilities, indexed by home goals then away goals
+    pub fn scores(&self) -> &[Vec<f64>] {
+        &self.scores
+    }
+
+    /// Returns the probabilities of a home win, draw and away win
+    pub fn outcome_probabilities(&self) -> (f64, f64, f64) {
+        let mut outcome = (0.0, 0.0, 0.0);
+        for (home_goals, away_goals, probability) in self.iter() {
+            match home_goals.cmp(&away_goals) {
+                Ordering::Greater => outcome.0 += probability,
+                Ordering::Equal => outcome.1 += probability,
+                Ordering::Less => outcome.2 += probability,
+            }
+        }
+        outcome
+    }
+
+    /// Returns the probability of more than the given number of goals in
+    /// total, e.g. over 2.5 goals
+    pub fn over(&self, line: f64) -> f64 {
+        self.iter()
+            .filter(|&(home_goals, away_goals, _)| (home_goals + away_goals) as f64 > line)
+            .map(|(_, _, probability)| probability)
+            .sum()
+    }
+
+    /// Returns the probability of fewer than the given number of goals in total
+    pub fn under(&self, line: f64) -> f64 {
+        self.iter()
+            .filter(|&(home_goals, away_goals, _)| ((home_goals + away_goals) as f64) < line)
+            .map(|(_, _, probability)| probability)
+            .sum()
+    }
+
+    /// Returns the probability that both teams score
+    pub fn both_teams_to_score(&self) -> f64 {
+        self.iter()
+            .filter(|&(home_goals, away_goals, _)| home_goals > 0 && away_goals > 0)
+            .map(|(_, _, probability)| probability)
+            .sum()
+    }
+
+    /// Returns every scoreline alongside its probability
+    fn iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.scores
+            .iter()
+            .enumerate()
+            .flat_map(|(home_goals, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(away_goals, &probability)| (home_goals, away_goals, probability))
+            })
+    }
+}
+
+/// Returns the home and away expected goals whose Poisson outcome probabilities
+/// match the given home win, draw and away win probabilities
+///
+/// Fewer total goals make a draw more likely, and for any total the split
+/// between the sides sets the balance of wins, so both are found by bisection
+fn goals_from_probabilities((home_win, _, away_win): (f64, f64, f64)) -> (f64, f64) {
+    let bisect = |mut low: f64, mut high: f64, too_low: &dyn Fn(f64) -> bool| {
+        for _ in 0..60 {
+            let middle = (low + high) / 2.0;
+            if too_low(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        (low + high) / 2.0
+    };
+    let split = |total: f64| {
+        let home = bisect(0.0, total, &|home| {
+            let (win, _, loss) = poisson_outcome_probabilities(home, total - home);
+            win - loss < home_win - away_win
+        });
+        (home, total - home)
+    };
+    let total = bisect(0.05, 10.0, &|total| {
+        let (home, away) = split(total);
+        let (win, _, loss) = poisson_outcome_probabilities(home, away);
+        win + loss < home_win + away_win
+    });
+    split(total)
+}
+
+impl ScoreModel for PoissonModel {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        let (home, away) = self.expected_goals(fixture);
+        (
+            self.distribution.sample(home, rng),
+            self.distribution.sample(away, rng),
+        )
+    }
+}
+
+/// A PoissonModel in which both sides' goals share a common component, so
+/// that home and away goals are positively correlated as in real matches
+///
+/// Each side's goals are the sum of its own Poisson component and a shared
+/// one whose mean is the covariance, so the expected goals of each side are
+/// those of the underlying PoissonModel. The covariance is capped at the
+/// smaller side's expected goals, and every component is Poisson whatever the
+/// underlying model's GoalDistribution. Loaded from the same json as a
+/// PoissonModel with an added "covariance", which defaults to zero
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BivariatePoissonModel {
+    #[serde(flatten)]
+    poisson: PoissonModel,
+    #[serde(default)]
+    covariance: f64,
+}
+
+impl BivariatePoissonModel {
+    /// create a model with the ratings of a PoissonModel and the given covariance
+    pub fn new(poisson: PoissonModel, covariance: f64) -> Self {
+        Self {
+            poisson,
+            covariance,
+        }
+    }
+
+    /// Reads a model from json, in the format described on BivariatePoissonModel
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, FormatError> {
+        parse_json(reader)
+    }
+
+    /// Returns the underlying PoissonModel
+    pub fn poisson(&self) -> &PoissonModel {
+        &self.poisson
+    }
+
+    /// Returns the covariance between home and away goals
+    pub fn covariance(&self) -> f64 {
+        self.covariance
+    }
+}
+
+impl ScoreModel for BivariatePoissonModel {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        let (home, away) = self.poisson.expected_goals(fixture);
+        let shared = self.covariance.clamp(0.0, home.min(away));
+        let common = sample_poisson(shared, rng);
+        (
+            sample_poisson(home - shared, rng) + common,
+            sample_poisson(away - shared, rng) + common,
+        )
+    }
+}
+
+/// Samples a goal count from a Poisson distribution with the given mean,
+/// treating a mean of zero or less as a certain zero
+fn sample_poisson(mean: f64, rng: &mut dyn RngCore) -> u32 {
+    match Poisson::new(mean) {
+        Ok(poisson) => poisson.sample(rng) as u32,
+        Err(_) => 0,
+    }
+}
+
+/// Samples the outcome of each match from the two teams' Elo ratings, then
+/// a scoreline with that outcome from the historical averages
+///
+/// The rating difference, plus home advantage, gives the home team's expected
+/// score, which is split into win, draw and loss probabilities. Ratings move
+/// after every match of a simulated season and return to their starting values
+/// at the start of the next, so the model carries the state of the season
+/// being simulated: give each thread its own clone rather than sharing one
+///
+/// Loaded from json of the form {"ratings": {"Arsenal": 1650.0}, "k_factor": 20.0,
+/// "home_advantage": 65.0, "draw_rate": 0.28}, where all but the ratings may be omitted
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EloModel {
+    #[serde(default)]
+    ratings: HashMap<String, f64>,
+    #[serde(default = "default_k_factor")]
+    k_factor: f64,
+    #[serde(default = "default_elo_home_advantage")]
+    home_advantage: f64,
+    #[serde(default = "default_draw_rate")]
+    draw_rate: f64,
+    #[serde(skip)]
+    season: RefCell<HashMap<String, f64>>,
+    #[serde(skip)]
+    scores: HistoricalAverageModel,
+}
+
+fn default_k_factor() -> f64 {
+    ELO_K_FACTOR
+}
+
+fn default_elo_home_advantage() -> f64 {
+    ELO_HOME_ADVANTAGE
+}
+
+fn default_draw_rate() -> f64 {
+    ELO_DRAW_RATE
+}
+
+impl Default for EloModel {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl EloModel {
+    /// create a model from each team's starting rating, with the default
+    /// K factor, home advantage and draw rate
+    pub fn new(ratings: HashMap<String, f64>) -> Self {
+        Self {
+            ratings,
+            k_factor: ELO_K_FACTOR,
+            home_advantage: ELO_HOME_ADVANTAGE,
+            draw_rate: ELO_DRAW_RATE,
+            season: RefCell::default(),
+            scores: HistoricalAverageModel::new(),
+        }
+    }
+
+    /// create a model by starting every team at 1500 and updating the ratings
+    /// with each result in turn
+    pub fn from_results(results: &[MatchResult]) -> Self {
+        let mut model = Self::default();
+        for result in results {
+            let fixture = result.fixture();
+            let (home, away) =
+                model.updated_ratings(fixture, result.home_goals(), result.away_goals());
+            model.ratings.insert(fixture.home().to_string(), home);
+            model.ratings.insert(fixture.away().to_string(), away);
+        }
+        model
+    }
+
+    /// Reads a model from json, in the format described on EloModel
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, FormatError> {
+        parse_json(reader)
+    }
+
+    /// Returns the named team's rating, as updated by any matches played so
+    /// far in the season being simulated
+    pub fn rating(&self, team: &str) -> f64 {
+        self.season
+            .borrow()
+            .get(team)
+            .or_else(|| self.ratings.get(team))
+            .copied()
+            .unwrap_or(INITIAL_ELO)
+    }
+
+    /// Returns the probabilities of a home win, a draw and an away win
+    pub fn outcome_probabilities(&self, fixture: &Match) -> (f64, f64, f64) {
+        let expected = self.expected_score(fixture);
+        let draw = self.draw_rate.clamp(0.0, 0.5) * 4.0 * expected * (1.0 - expected);
+        (expected - draw / 2.0, draw, 1.0 - expected - draw / 2.0)
+    }
+
+    /// Returns the home team's expected score, counting a draw as half a win
+    fn expected_score(&self, fixture: &Match) -> f64 {
+        let advantage = self.home_advantage * fixture.home_advantage_share();
+        let difference = self.rating(fixture.home()) + advantage - self.rating(fixture.away());
+        1.0 / (1.0 + 10f64.powf(-difference / 400.0))
+    }
+
+    /// Returns both teams' ratings after the given result
+    fn updated_ratings(&self, fixture: &Match, home_goals: u32, away_goals: u32) -> (f64, f64) {
+        let actual = match home_goals.cmp(&away_goals) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        };
+        let change = self.k_factor * (actual - self.expected_score(fixture));
+        (
+            self.rating(fixture.home()) + change,
+            self.rating(fixture.away()) - change,
+        )
+    }
+}
+
+impl ScoreModel for EloModel {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        let (home_win, draw, _) = self.outcome_probabilities(fixture);
+        let roll: f64 = rng.random();
+        let outcome = if roll < home_win {
+            Ordering::Greater
+        } else if roll < home_win + draw {
+            Ordering::Equal
+        } else {
+            Ordering::Less
+        };
+
+        for _ in 0..MAX_SCORE_ATTEMPTS {
+            let (home_goals, away_goals) =
+                self.scores.sample_at(fixture.home_advantage_share(), rng);
+            if home_goals.cmp(&away_goals) == outcome {
+                return (home_goals, away_goals);
+            }
+        }
+        match outcome {
+            Ordering::Greater => (1, 0),
+            Ordering::Equal => (0, 0),
+            Ordering::Less => (0, 1),
+        }
+    }
+
+    fn start_season(&self) {
+        self.season.borrow_mut().clear();
+    }
+
+    fn record_result(&self, fixture: &Match, home_goals: u32, away_goals: u32) {
+        let (home, away) = self.updated_ratings(fixture, home_goals, away_goals);
+        let mut season = self.season.borrow_mut();
+        season.insert(fixture.home().to_string(), home);
+        season.insert(fixture.away().to_string(), away);
+    }
+}
+
+/// Wraps another model to blunt the attack of teams playing many matches in a
+/// short window, such as those on a cup run at the end of the season
+///
+/// The schedule lists every match the teams play, cup ties included, with the
+/// kickoff times that matter. For each match beyond the allowance a team has
+/// played in the window before a fixture kicks off, its expected goals in that
+/// fixture fall by the penalty: every goal the wrapped model samples for the
+/// team is kept with probability (1 - penalty) raised to the number of excess
+/// matches. Fixtures without a kickoff time are sampled unchanged. By default
+/// the window is a week, one match in it is allowed and the penalty is 5%
+#[derive(Debug, Clone)]
+pub struct CongestionModel<M> {
+    model: M,
+    // the kickoff times of every team's matches, earliest first
+    schedule: HashMap<String, Vec<DateTime<Utc>>>,
+    window: TimeDelta,
+    allowance: u32,
+    penalty: f64,
+}
+
+impl<M: ScoreModel> CongestionModel<M> {
+    /// create the model from the one it wraps and every match in the teams'
+    /// schedules, ignoring any without a kickoff time
+    pub fn new(model: M, schedule: &[Match]) -> Self {
+        let mut kickoffs: HashMap<String, Vec<DateTime<Utc>>> = HashMap::new();
+        for fixture in schedule {
+            if let Some(kickoff) = fixture.kickoff() {
+                for team in [fixture.home(), fixture.away()] {
+                    kickoffs.entry(team.to_string()).or_default().push(kickoff);
+                }
+            }
+        }
+        for times in kickoffs.values_mut() {
+            times.sort();
+            times.dedup();
+        }
+        Self {
+            model,
+            schedule: kickoffs,
+            window: TimeDelta::days(CONGESTION_WINDOW_DAYS),
+            allowance: CONGESTION_ALLOWANCE,
+            penalty: CONGESTION_PENALTY,
+        }
+    }
+
+    /// Sets how far back from a kickoff earlier matches are counted
+    pub fn with_window(mut self, window: TimeDelta) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets how many matches a team may play in the window without penalty
+    pub fn with_allowance(mut self, allowance: u32) -> Self {
+        self.allowance = allowance;
+        self
+    }
+
+    /// Sets the share of expected goals a team loses for each match over the allowance
+    pub fn with_penalty(mut self, penalty: f64) -> Self {
+        self.penalty = penalty.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the number of matches the named team plays in the window
+    /// before, and not including, the given kickoff
+    pub fn recent_matches(&self, team: &str, kickoff: DateTime<Utc>) -> u32 {
+        let Some(times) = self.schedule.get(team) else {
+            return 0;
+        };
+        let start = times.partition_point(|&time| time < kickoff - self.window);
+        let end = times.partition_point(|&time| time < kickoff);
+        (end - start) as u32
+    }
+
+    /// Returns the factor the named team's expected goals are scaled by in a
+    /// match at the given kickoff, 1.0 for a rested team
+    pub fn goal_factor(&self, team: &str, kickoff: DateTime<Utc>) -> f64 {
+        let excess = self
+            .recent_matches(team, kickoff)
+            .saturating_sub(self.allowance);
+        (1.0 - self.penalty).powi(excess as i32)
+    }
+}
+
+impl<M: ScoreModel> ScoreModel for CongestionModel<M> {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        let (home_goals, away_goals) = self.model.sample_score(fixture, rng);
+        let Some(kickoff) = fixture.kickoff() else {
+            return (home_goals, away_goals);
+        };
+        let mut thin = |team: &str, goals: u32| {
+            let keep = self.goal_factor(team, kickoff);
+            (0..goals).filter(|_| rng.random_bool(keep)).count() as u32
+        };
+        (
+            thin(fixture.home(), home_goals),
+            thin(fixture.away(), away_goals),
+        )
+    }
+
+    fn start_season(&self) {
+        self.model.start_season();
+    }
+
+    fn record_result(&self, fixture: &Match, home_goals: u32, away_goals: u32) {
+        self.model.record_result(fixture, home_goals, away_goals);
+    }
+}
+
+/// A change in a team's strength from a given time onwards, such as a January
+/// signing or a new manager
+///
+/// A multiplier above 1.0 strengthens the team: it scores that many times the
+/// goals and concedes that fraction of them, so 1.1 scores 10% more and
+/// concedes about 9% less. Read from json as an array of
+/// {"from": "2025-02-01T00:00:00Z", "team": "Leeds", "multiplier": 1.1}
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StrengthAdjustment {
+    pub from: DateTime<Utc>,
+    pub team: String,
+    pub multiplier: f64,
+}
+
+/// Reads strength adjustments from json, in the format described on StrengthAdjustment
+pub fn parse_adjustments_json<R: Read>(reader: R) -> Result<Vec<StrengthAdjustment>, FormatError> {
+    parse_json(reader)
+}
+
+/// Wraps another model to apply a timeline of strength adjustments, so that
+/// changes part way through a season can be reflected without refitting it
+///
+/// Every adjustment whose time has come by a fixture's kickoff applies to it,
+/// several for the same team multiplying together. Goals the wrapped model
+/// samples are scaled to match: a side scoring less has each goal kept with
+/// the scaled chance, and a side scoring more gains extra goals drawn from a
+/// Poisson distribution, so its expected goals scale exactly. Fixtures without
+/// a kickoff time are sampled unchanged
+#[derive(Debug, Clone)]
+pub struct AdjustedModel<M> {
+    model: M,
+    adjustments: Vec<StrengthAdjustment>,
+}
+
+impl<M: ScoreModel> AdjustedModel<M> {
+    /// create the model from the one it wraps and the adjustments to apply
+    pub fn new(model: M, adjustments: &[StrengthAdjustment]) -> Self {
+        Self {
+            model,
+            adjustments: adjustments.to_vec(),
+        }
+    }
+
+    /// Returns the product of every adjustment to the named team in force at the given kickoff
+    pub fn multiplier(&self, team: &str, kickoff: DateTime<Utc>) -> f64 {
+        self.adjustments
+            .iter()
+            .filter(|adjustment| adjustment.team == team && adjustment.from <= kickoff)
+            .map(|adjustment| adjustment.multiplier)
+            .product()
+    }
+}
+
+impl<M: ScoreModel> ScoreModel for AdjustedModel<M> {
+    fn sample_score(&self, fixture: &Match, rng: &mut dyn RngCore) -> (u32, u32) {
+        let (home_goals, away_goals) = self.model.sample_score(fixture, rng);
+        let Some(kickoff) = fixture.kickoff() else {
+            return (home_goals, away_goals);
+        };
+        let home = self.multiplier(fixture.home(), kickoff);
+        let away = self.multiplier(fixture.away(), kickoff);
+        (
+            scale_goals(home_goals, home / away, rng),
+            scale_goals(away_goals, away / home, rng),
+        )
+    }
+
+    fn start_season(&self) {
+        self.model.start_season();
+    }
+
+    fn record_result(&self, fixture: &Match, home_goals: u32, away_goals: u32) {
+        self.model.record_result(fixture, home_goals, away_goals);
+    }
+}
+
+/// Scales a sampled goal count so that its expected value grows by the given
+/// factor, thinning the goals for a factor below one and adding Poisson goals above it
+fn scale_goals(goals: u32, factor: f64, rng: &mut dyn RngCore) -> u32 {
+    if factor < 1.0 {
+        let keep = factor.max(0.0);
+        (0..goals).filter(|_| rng.random_bool(keep)).count() as u32
+    } else {
+        goals + sample_poisson(f64::from(goals) * (factor - 1.0), rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Site;
+
+    /// always predicts the same score, to check models can be swapped in
+    #[derive(Clone)]
+    struct FixedModel(u32, u32);
+
+    impl ScoreModel for FixedModel {
+        fn sample_score(&self, _fixture: &Match, _rng: &mut dyn RngCore) -> (u32, u32) {
+            (self.0, self.1)
+        }
+    }
+
+    #[test]
+    fn simulate_with_any_model() {
+        let mut league_table = crate::LeagueTable::new();
+        league_table
+            .add_team("Arsenal".to_string(), 50, 10)
+            .unwrap();
+        league_table
+            .add_team("Chelsea".to_string(), 52, 12)
+            .unwrap();
+        let fixtures = crate::FixtureList::round_robin(["Arsenal", "Chelsea"], true);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let home_wins = FixedModel(3, 0);
+        let rank = crate::run_simulation_with_model(
+            "Arsenal",
+            &league_table,
+            &fixtures,
+            &home_wins,
+            &mut rng,
+        );
+        assert_eq!(2, rank.final_rank());
+
+        let models: Vec<Box<dyn ScoreModel>> = vec![
+            Box::new(HistoricalAverageModel::new()),
+            Box::new(FixedModel(1, 1)),
+        ];
+        for model in &models {
+            let report = crate::simulate_batch_with_model(
+                &league_table,
+                &fixtures,
+                5,
+                model.as_ref(),
+                &mut rng,
+            );
+            assert_eq!(5, report.simulations());
+        }
+
+        // a configured batch plays every season with the model on every thread,
+        // even one holding state that cannot be shared between threads
+        let config = crate::SimulationConfig::new(40)
+            .with_threads(2)
+            .with_variance_reduction(crate::VarianceReduction::Antithetic);
+        let report =
+            crate::run_simulations_with_model(&config, &league_table, &fixtures, &home_wins);
+        assert_eq!(40, report.simulations());
+        assert_eq!(1.0, report.probability("Chelsea", 1));
+        let elo = EloModel::new(HashMap::new());
+        let report = crate::run_simulations_with_model(&config, &league_table, &fixtures, &elo);
+        assert_eq!(40, report.simulations());
+        let draws = crate::simulate_results_with_model(&fixtures, &FixedModel(1, 1), &mut rng);
+        assert_eq!(2, draws.len());
+        assert!(draws
+            .iter()
+            .all(|result| (result.home_goals(), result.away_goals()) == (1, 1)));
+    }
+
+    #[test]
+    fn poisson_model_ratings() {
+        let json =
+            r#"{"home_advantage": 1.5, "ratings": {"City": {"attack": 2.0, "defense": 0.5}}}"#;
+        let model = PoissonModel::from_json(json.as_bytes()).unwrap();
+        assert_eq!(BASE_GOALS, model.base_goals());
+        assert_eq!(TeamRating::default(), model.rating("Ipswich"));
+
+        let (home, away) = model.expected_goals(&Match::from("City", "Ipswich"));
+        assert!((home - BASE_GOALS * 2.0 * 1.5).abs() < 1e-9);
+        assert!((away - BASE_GOALS * 0.5 / 1.5).abs() < 1e-9);
+        let neutral = Match::from("City", "Ipswich").with_site(Site::Neutral);
+        assert!((model.expected_goals(&neutral).0 - BASE_GOALS * 2.0).abs() < 1e-9);
+
+        let error = PoissonModel::from_json(r#"{"ratings": {"City": {"attack": 2.0}}}"#.as_bytes())
+            .unwrap_err();
+        assert_eq!("ratings.City", error.path());
+
+        // the stronger side scores more over many samples
+        let mut rng = StdRng::seed_from_u64(5);
+        let fixture = Match::from("City", "Ipswich");
+        let (city, ipswich) = (0..500).fold((0, 0), |(city, ipswich), _| {
+            let (home, away) = model.sample_score(&fixture, &mut rng);
+            (city + home, ipswich + away)
+        });
+        assert!(city > 4 * ipswich, "{city} v {ipswich}");
+
+        let mut blank = PoissonModel::new(0.0, 1.0);
+        blank.set_rating("City", TeamRating::new(3.0, 1.0));
+        assert_eq!((0, 0), blank.sample_score(&fixture, &mut rng));
+    }
+
+    #[test]
+    fn elo_model_ratings() {
+        let played = [
+            MatchResult::new(Match::from("Liverpool", "Everton"), 2, 0),
+            MatchResult::new(Match::from("Everton", "Liverpool"), 0, 1),
+        ];
+        let model = EloModel::from_results(&played);
+        assert!(model.rating("Liverpool") > INITIAL_ELO);
+        assert!(
+            (model.rating("Liverpool") + model.rating("Everton") - 2.0 * INITIAL_ELO).abs() < 1e-9
+        );
+
+        let derby = Match::from("Liverpool", "Everton");
+        let (win, draw, loss) = model.outcome_probabilities(&derby);
+        assert!((win + draw + loss - 1.0).abs() < 1e-9);
+        assert!(win > loss && draw > 0.0);
+
+        // ratings move within a season and reset at the start of the next
+        let before = model.rating("Everton");
+        model.start_season();
+        model.record_result(&derby, 0, 3);
+        assert!(model.rating("Everton") > before);
+        model.start_season();
+        assert_eq!(before, model.rating("Everton"));
+
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..50 {
+            let (home, away) = model.sample_score(&derby, &mut rng);
+            assert!(home <= 7 && away <= 7);
+        }
+
+        let json = r#"{"ratings": {"City": 1800.0, "Ipswich": 1300.0}, "draw_rate": 0.0}"#;
+        let model = EloModel::from_json(json.as_bytes()).unwrap();
+        let (win, draw, loss) = model.outcome_probabilities(&Match::from("City", "Ipswich"));
+        assert_eq!(0.0, draw);
+        assert!(win > 0.95 && loss < 0.05);
+    }
+
+    #[test]
+    fn bivariate_poisson_correlation() {
+        let json = r#"{"base_goals": 1.5, "home_advantage": 1.0, "covariance": 0.8}"#;
+        let model = BivariatePoissonModel::from_json(json.as_bytes()).unwrap();
+        assert_eq!(0.8, model.covariance());
+        assert_eq!(1.5, model.poisson().base_goals());
+
+        let fixture = Match::from("Fulham", "Brentford");
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples: Vec<(f64, f64)> = (0..2000)
+            .map(|_| {
+                let (home, away) = model.sample_score(&fixture, &mut rng);
+                (f64::from(home), f64::from(away))
+            })
+            .collect();
+        let count = samples.len() as f64;
+        let mean_home = samples.iter().map(|s| s.0).sum::<f64>() / count;
+        let mean_away = samples.iter().map(|s| s.1).sum::<f64>() / count;
+        let covariance = samples
+            .iter()
+            .map(|s| (s.0 - mean_home) * (s.1 - mean_away))
+            .sum::<f64>()
+            / count;
+        assert!((mean_home - 1.5).abs() < 0.15, "{mean_home}");
+        assert!((covariance - 0.8).abs() < 0.2, "{covariance}");
+
+        let independent = BivariatePoissonModel::new(PoissonModel::default(), 0.0);
+        assert_eq!(0.0, independent.covariance());
+    }
+
+    #[test]
+    fn alias_table_sampling() {
+        let weights = [1.0, 0.0, 3.0, 4.0];
+        let table = AliasTable::new(&weights);
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut counts = [0u32; 4];
+        for _ in 0..80_000 {
+            counts[table.sample(&mut rng) as usize] += 1;
+        }
+        assert_eq!(0, counts[1]);
+        for (count, weight) in counts.iter().zip(weights) {
+            let share = f64::from(*count) / 80_000.0;
+            assert!((share - f64::from(weight) / 8.0).abs() < 0.01);
+        }
+
+        let model = HistoricalAverageModel::new();
+        let shares = [1.0, 0.0, 1.0];
+        let mut scores = vec![(99, 99)];
+        model.sample_all(&shares, &mut rng, &mut scores);
+        assert_eq!(3, scores.len());
+        assert!(scores.iter().all(|&(home, away)| home < 8 && away < 8));
+        let mut total = 0;
+        for _ in 0..20_000 {
+            model.sample_all(&shares[..1], &mut rng, &mut scores);
+            total += scores[0].0;
+        }
+        // the historical mean of home goals is about 1.77
+        assert!((f64::from(total) / 20_000.0 - 1.77).abs() < 0.05);
+    }
+
+    #[test]
+    fn match_markets() {
+        let model = PoissonModel::new(1.5, 1.0);
+        let fixture = Match::from("Arsenal", "Chelsea");
+        let markets = model.markets(&fixture);
+        let (home, draw, away) = markets.outcome_probabilities();
+        let expected = model.outcome_probabilities(&fixture);
+        assert!((home - expected.0).abs() < 1e-12 && (draw - expected.1).abs() < 1e-12);
+        assert!((away - expected.2).abs() < 1e-12);
+        // Poisson(1.5) each way: no goals is e^-3
+        assert!((markets.score(0, 0) - (-3.0f64).exp()).abs() < 1e-12);
+        assert!((markets.over(2.5) + markets.under(2.5) - 1.0).abs() < 1e-9);
+        let both = (1.0 - (-1.5f64).exp()).powi(2);
+        assert!((markets.both_teams_to_score() - both).abs() < 1e-9);
+        assert_eq!(0.0, markets.score(40, 0));
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let sampled = MatchMarkets::sampled(&model, &fixture, 20_000, &mut rng);
+        assert!((sampled.over(2.5) - markets.over(2.5)).abs() < 0.02);
+        assert!((sampled.both_teams_to_score() - both).abs() < 0.02);
+
+        let fixed = MatchMarkets::sampled(&FixedModel(2, 1), &fixture, 10, &mut rng);
+        assert!((fixed.score(2, 1) - 1.0).abs() < 1e-9);
+        assert_eq!(3, fixed.scores().len());
+        assert!((fixed.outcome_probabilities().0 - 1.0).abs() < 1e-9);
+        assert!((fixed.over(2.5) - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, fixed.under(2.5));
+    }
+
+    #[test]
+    fn negative_binomial_spread() {
+        let json =
+            r#"{"home_advantage": 1.0, "distribution": {"NegativeBinomial": {"dispersion": 1.5}}}"#;
+        let wide = PoissonModel::from_json(json.as_bytes()).unwrap();
+        assert_eq!(
+            GoalDistribution::NegativeBinomial { dispersion: 1.5 },
+            wide.distribution()
+        );
+        let narrow = PoissonModel::new(BASE_GOALS, 1.0);
+        assert_eq!(GoalDistribution::Poisson, narrow.distribution());
+
+        let fixture = Match::from("Spurs", "West Ham");
+        let mut rng = StdRng::seed_from_u64(12);
+        let moments = |model: &PoissonModel, rng: &mut StdRng| {
+            let goals: Vec<f64> = (0..4000)
+                .map(|_| f64::from(model.sample_score(&fixture, rng).0))
+                .collect();
+            let mean = goals.iter().sum::<f64>() / goals.len() as f64;
+            let variance =
+                goals.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / goals.len() as f64;
+            (mean, variance)
+        };
+        let (mean, variance) = moments(&wide, &mut rng);
+        assert!((mean - BASE_GOALS).abs() < 0.1, "{mean}");
+        assert!(
+            (variance - (BASE_GOALS + BASE_GOALS.powi(2) / 1.5)).abs() < 0.3,
+            "{variance}"
+        );
+        let (_, poisson_variance) = moments(&narrow, &mut rng);
+        assert!(variance > poisson_variance + 0.8);
+    }
+
+    #[test]
+    fn xg_model_ratings() {
+        let csv = "team,matches,xg_for,xg_against\n\
+                   Arsenal,10,20.0,8.0\n\
+                   Everton,10,10.0,16.0\n\
+                   Ipswich,0,0.0,0.0\n";
+        let json = r#"[
+            {"team": "Arsenal", "matches": 10, "xg_for": 20.0, "xg_against": 8.0},
+            {"team": "Everton", "matches": 10, "xg_for": 10.0, "xg_against": 16.0},
+            {"team": "Ipswich", "matches": 0, "xg_for": 0.0, "xg_against": 0.0}
+        ]"#;
+        let xg = parse_xg_csv(csv.as_bytes()).unwrap();
+        assert_eq!(parse_xg_json(json.as_bytes()).unwrap(), xg);
+
+        let model = PoissonModel::from_xg(&xg);
+        assert!((model.base_goals() - 1.5).abs() < 1e-9);
+        let arsenal = model.rating("Arsenal");
+        assert!((arsenal.attack - 4.0 / 3.0).abs() < 1e-9);
+        assert!((arsenal.defense - 8.0 / 15.0).abs() < 1e-9);
+        assert_eq!(TeamRating::default(), model.rating("Ipswich"));
+        let (home, away) = model.expected_goals(&Match::from("Arsenal", "Everton"));
+        assert!(home > 2.0 * away);
+
+        let error =
+            parse_xg_csv("team,matches,xg_for,xg_against\nArsenal,ten,20.0,8.0\n".as_bytes())
+                .unwrap_err();
+        assert_eq!("line 2", error.path());
+    }
+
+    #[test]
+    fn calibrate_from_odds() {
+        let mut truth = PoissonModel::default();
+        truth.set_rating("Liverpool", TeamRating::new(1.5, 0.6));
+        truth.set_rating("Brighton", TeamRating::new(1.0, 1.0));
+        truth.set_rating("Wolves", TeamRating::new(0.7, 1.4));
+        let teams = ["Liverpool", "Brighton", "Wolves"];
+
+        // price every fixture with a 6% margin spread evenly over the outcomes
+        let mut odds = Vec::new();
+        for home in teams {
+            for away in teams.iter().filter(|away| **away != home) {
+                let (win, draw, loss) = truth.outcome_probabilities(&Match::from(home, away));
+                odds.push(MatchOdds {
+                    home: home.to_string(),
+                    away: away.to_string(),
+                    home_win: 1.0 / (win * 1.06),
+                    draw: 1.0 / (draw * 1.06),
+                    away_win: 1.0 / (loss * 1.06),
+                });
+            }
+        }
+        assert!((odds[0].overround() - 0.06).abs() < 1e-4);
+        let json = r#"[{"home": "Arsenal", "away": "Chelsea", "home_win": 2.0, "draw": 4.0, "away_win": 4.0}]"#;
+        let even = parse_odds_json(json.as_bytes()).unwrap();
+        assert_eq!((0.5, 0.25, 0.25), even[0].implied_probabilities());
+
+        let model = PoissonModel::from_odds(&odds).unwrap();
+        for home in teams {
+            for away in teams.iter().filter(|away| **away != home) {
+                let fixture = Match::from(home, away);
+                let (expected, actual) = (
+                    truth.expected_goals(&fixture),
+                    model.expected_goals(&fixture),
+                );
+                assert!((expected.0 - actual.0).abs() < 0.05, "{home} v {away}");
+                assert!((expected.1 - actual.1).abs() < 0.05, "{home} v {away}");
+            }
+        }
+        assert!(model.rating("Liverpool").attack > model.rating("Wolves").attack);
+    }
+
+    #[test]
+    fn fit_from_results() {
+        let mut truth = PoissonModel::new(1.3, 1.3);
+        truth.set_rating("Newcastle", TeamRating::new(1.4, 0.8));
+        truth.set_rating("Fulham", TeamRating::new(1.0, 1.0));
+        truth.set_rating("Burnley", TeamRating::new(0.6, 1.2));
+        let mut rng = StdRng::seed_from_u64(45);
+        let season = crate::FixtureList::round_robin(["Newcastle", "Fulham", "Burnley"], true);
+        let results: Vec<MatchResult> = (0..200)
+            .flat_map(|_| season.iter())
+            .map(|fixture| {
+                let (home, away) = truth.sample_score(fixture, &mut rng);
+                MatchResult::new(fixture.clone(), home, away)
+            })
+            .collect();
+
+        let model = PoissonModel::from_results(&results).unwrap();
+        assert!((model.home_advantage() - 1.3).abs() < 0.1);
+        assert!((model.base_goals() - 1.3).abs() < 0.1);
+        for team in ["Newcastle", "Fulham", "Burnley"] {
+            let (expected, actual) = (truth.rating(team), model.rating(team));
+            assert!((expected.attack - actual.attack).abs() < 0.1, "{team}");
+            assert!((expected.defense - actual.defense).abs() < 0.1, "{team}");
+        }
+        assert_eq!(Ok(PoissonModel::default()), PoissonModel::from_results(&[]));
+
+        // a team that never scores, one that never concedes and no away goals
+        // at all still give finite ratings and home advantage
+        let shutouts: Vec<MatchResult> = season
+            .iter()
+            .map(|fixture| {
+                let home_goals = if fixture.home() == "Burnley" { 0 } else { 2 };
+                MatchResult::new(fixture.clone(), home_goals, 0)
+            })
+            .collect();
+        let model = PoissonModel::from_results(&shutouts).unwrap();
+        assert!(model.home_advantage().is_finite() && model.home_advantage() > 1.0);
+        let burnley = model.rating("Burnley");
+        assert!(burnley.attack > 0.0 && burnley.attack < model.rating("Fulham").attack);
+        assert!(model.rating("Newcastle").defense > 0.0);
+
+        let odds = MatchOdds {
+            home: "Newcastle".to_string(),
+            away: "Fulham".to_string(),
+            home_win: 0.0,
+            draw: 3.5,
+            away_win: 4.0,
+        };
+        let error = PoissonModel::from_odds(&[odds]).unwrap_err();
+        assert_eq!("[0]", error.path());
+    }
+
+    #[test]
+    fn bayesian_updates() {
+        let mut last_season = PoissonModel::new(1.5, 1.0);
+        last_season.set_rating("Leeds", TeamRating::new(0.8, 1.2));
+        let thrashing = MatchResult::new(Match::from("Leeds", "Villa"), 4, 0);
+        let results = vec![thrashing; 3];
+
+        let sceptical = last_season.updated(&results, 10.0);
+        let leeds = sceptical.rating("Leeds");
+        assert!(leeds.attack > 0.8 && leeds.attack < 2.0, "{leeds:?}");
+        assert!(leeds.defense < 1.2);
+        assert!(sceptical.rating("Villa").defense > 1.0);
+
+        // with no prior the attack is simply goals over expected goals
+        let credulous = last_season.updated(&results[..1], 0.0);
+        assert!((credulous.rating("Leeds").attack - 4.0 / 1.5).abs() < 1e-9);
+        assert!(credulous.rating("Leeds").attack > leeds.attack);
+        assert!(
+            sceptical.rating("Leeds").attack
+                > last_season
+                    .updated(&results[..1], 10.0)
+                    .rating("Leeds")
+                    .attack
+        );
+        assert_eq!(last_season, last_season.updated(&[], 10.0));
+    }
+
+    #[test]
+    fn configured_goal_weights() {
+        let english = GoalWeights {
+            neutral: None,
+            ..GoalWeights::default()
+        };
+        for (expected, actual) in NEUTRAL_WEIGHTS.iter().zip(english.neutral_weights()) {
+            assert!((expected - actual).abs() < 1e-4);
+        }
+
+        // a high scoring league where every score from 0 to 11 is equally likely
+        let json = r#"{"home": [1,1,1,1,1,1,1,1,1,1,1,1], "away": [1,1,1,1,1,1,1,1,1,1,1,1]}"#;
+        let weights = GoalWeights::from_json(json.as_bytes()).unwrap();
+        assert_eq!(12, weights.neutral_weights().len());
+        let model = HistoricalAverageModel::from_weights(&weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(47);
+        let fixture = Match::from("Forest", "Leicester");
+        let scores: Vec<(u32, u32)> = (0..2000)
+            .map(|_| model.sample_score(&fixture, &mut rng))
+            .collect();
+        assert!(scores.iter().all(|&(home, away)| home < 12 && away < 12));
+        assert!(scores.iter().any(|&(home, _)| home >= 8));
+
+        let empty = GoalWeights {
+            away: vec![],
+            ..GoalWeights::default()
+        };
+        let error = HistoricalAverageModel::from_weights(&empty).unwrap_err();
+        assert_eq!("away", error.path());
+    }
+
+    #[test]
+    fn weights_from_engsoccerdata() {
+        let csv = "\
+Date,Season,home,visitor,FT,hgoal,vgoal,division,tier,totgoal,goaldif,result
+1992-08-15,1992,Arsenal,Norwich City,2-4,2,4,1,1,6,-2,A
+1992-08-15,1992,Chelsea,Oldham Athletic,1-1,1,1,1,1,2,0,D
+1992-08-16,1992,Nottingham Forest,Liverpool,1-0,1,0,1,1,1,1,H
+1992-08-15,1992,Barnsley,West Ham United,0-1,0,1,2,2,1,-1,A
+2020-09-12,2020,Fulham,Arsenal,0-3,0,3,1,1,3,-3,A
+";
+        let date = |text: &str| text.parse::<NaiveDate>().unwrap();
+        let season = date("1992-08-01")..=date("1993-05-31");
+        let weights = GoalWeights::from_engsoccerdata(csv.as_bytes(), 1, season.clone()).unwrap();
+        let third = 100.0 / 3.0;
+        assert_eq!(vec![0.0, 2.0 * third, third], weights.home);
+        assert_eq!(vec![third, third, 0.0, 0.0, third], weights.away);
+        assert_eq!(None, weights.neutral);
+        assert!(HistoricalAverageModel::from_weights(&weights).is_ok());
+
+        // nothing in the range leaves nothing to calibrate from
+        let later = date("2021-08-01")..=date("2022-05-31");
+        assert!(GoalWeights::from_engsoccerdata(csv.as_bytes(), 1, later).is_err());
+        let broken = "Date,tier,hgoal,vgoal\n1992-08-15,1,two,0\n";
+        let error = GoalWeights::from_engsoccerdata(broken.as_bytes(), 1, season).unwrap_err();
+        assert_eq!("line 2", error.path());
+    }
+
+    #[test]
+    fn fixture_congestion() {
+        let day = |day: u32| {
+            NaiveDate::from_ymd_opt(2025, 4, day)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        // Chelsea play a cup tie midweek between two league matches
+        let schedule = [
+            Match::from("Chelsea", "Spurs").with_kickoff(day(5)),
+            Match::from("Chelsea", "Leeds").with_kickoff(day(9)),
+            Match::from("Wolves", "Chelsea").with_kickoff(day(12)),
+            Match::from("Wolves", "Spurs").with_kickoff(day(19)),
+        ];
+        let model = CongestionModel::new(FixedModel(10, 10), &schedule).with_penalty(0.5);
+        assert_eq!(2, model.recent_matches("Chelsea", day(12)));
+        assert_eq!(0, model.recent_matches("Wolves", day(12)));
+        assert_eq!(0, model.recent_matches("Barnet", day(12)));
+        assert_eq!(0.5, model.goal_factor("Chelsea", day(12)));
+        assert_eq!(1.0, model.goal_factor("Wolves", day(12)));
+        assert_eq!(1.0, model.goal_factor("Spurs", day(19)));
+
+        let mut rng = StdRng::seed_from_u64(12);
+        let (mut wolves, mut chelsea) = (0, 0);
+        for _ in 0..200 {
+            let (home, away) = model.sample_score(&schedule[2], &mut rng);
+            (wolves, chelsea) = (wolves + home, chelsea + away);
+        }
+        assert_eq!(2000, wolves);
+        assert!(chelsea > 800 && chelsea < 1200);
+
+        // without a kickoff the wrapped model's score stands
+        let undated = Match::from("Wolves", "Chelsea");
+        assert_eq!((10, 10), model.sample_score(&undated, &mut rng));
+    }
+
+    #[test]
+    fn strength_adjustments() {
+        let json = r#"[
+            {"from": "2025-02-01T00:00:00Z", "team": "Leeds", "multiplier": 2.0},
+            {"from": "2025-03-01T00:00:00Z", "team": "Leeds", "multiplier": 1.5},
+            {"from": "2025-02-01T00:00:00Z", "team": "Hull", "multiplier": 0.5}
+        ]"#;
+        let adjustments = parse_adjustments_json(json.as_bytes()).unwrap();
+        assert_eq!(3, adjustments.len());
+        let model = AdjustedModel::new(FixedModel(4, 4), &adjustments);
+        let date = |text: &str| text.parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(1.0, model.multiplier("Leeds", date("2025-01-15T15:00:00Z")));
+        assert_eq!(2.0, model.multiplier("Leeds", date("2025-02-15T15:00:00Z")));
+        assert_eq!(3.0, model.multiplier("Leeds", date("2025-03-15T15:00:00Z")));
+        assert_eq!(1.0, model.multiplier("Stoke", date("2025-03-15T15:00:00Z")));
+
+        // before the window nothing changes, after it Leeds score more and concede less
+        let mut rng = StdRng::seed_from_u64(21);
+        let january = Match::from("Leeds", "Stoke").with_kickoff(date("2025-01-15T15:00:00Z"));
+        assert_eq!((4, 4), model.sample_score(&january, &mut rng));
+        let february = Match::from("Leeds", "Stoke").with_kickoff(date("2025-02-15T15:00:00Z"));
+        let (mut leeds, mut stoke) = (0, 0);
+        for _ in 0..500 {
+            let (home, away) = model.sample_score(&february, &mut rng);
+            (leeds, stoke) = (leeds + home, stoke + away);
+        }
+        assert!(leeds > 3600 && leeds < 4400);
+        assert!(stoke > 800 && stoke < 1200);
+        assert_eq!(
+            (4, 4),
+            model.sample_score(&Match::from("Leeds", "Stoke"), &mut rng)
+        );
+        assert!(parse_adjustments_json(r#"[{"team": "Leeds"}]"#.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn division_weight_presets() {
+        assert_eq!(
+            Some(GoalWeights::default()),
+            GoalWeights::preset("english-football")
+        );
+        assert_eq!(None, GoalWeights::preset("serie-a"));
+        // the top flight scores more than the Championship, at home and away
+        let mean = |weights: &[f32]| -> f32 {
+            weights
+                .iter()
+                .zip(0..)
+                .map(|(w, goals)| w * goals as f32)
+                .sum::<f32>()
+                / weights.iter().sum::<f32>()
+        };
+        let premier = GoalWeights::preset("premier-league").unwrap();
+        let championship = GoalWeights::preset("championship").unwrap();
+        assert!(mean(&premier.home) > mean(&championship.home));
+        assert!(mean(&premier.away) > mean(&championship.away));
+        assert!((mean(&premier.home) - 1.53).abs() < 0.05);
+        assert!(HistoricalAverageModel::for_league("league-two").is_some());
+        assert!(HistoricalAverageModel::for_league("serie-a").is_none());
+
+        let mut presets = WeightPresets::new();
+        assert_eq!(
+            vec![
+                "championship",
+                "english-football",
+                "league-one",
+                "league-two",
+                "premier-league"
+            ],
+            presets.leagues().collect::<Vec<_>>()
+        );
+        let goalfest = GoalWeights {
+            home: vec![1.0; 10],
+            away: vec![1.0; 10],
+            neutral: None,
+        };
+        presets.register("goalfest", goalfest.clone()).unwrap();
+        assert_eq!(Some(&goalfest), presets.get("goalfest"));
+        assert!(presets.model("goalfest").is_some());
+        let broken = GoalWeights {
+            home: vec![],
+            ..goalfest
+        };
+        assert_eq!(
+            "home",
+            presets.register("broken", broken).unwrap_err().path()
+        );
+        assert!(presets.get("broken").is_none());
+    }
+
+    #[test]
+    fn per_team_home_advantage() {
+        let json = r#"{"home_advantage": 1.2, "ratings": {
+            "Burnley": {"attack": 1.0, "defense": 1.0, "home_advantage": 1.5},
+            "Luton": {"attack": 1.0, "defense": 1.0}
+        }}"#;
+        let mut model = PoissonModel::from_json(json.as_bytes()).unwrap();
+        assert_eq!(1.5, model.home_advantage_of("Burnley"));
+        assert_eq!(1.2, model.home_advantage_of("Luton"));
+        assert_eq!(1.2, model.home_advantage_of("Sheffield United"));
+
+        let (home, away) = model.expected_goals(&Match::from("Burnley", "Luton"));
+        assert!((home - BASE_GOALS * 1.5).abs() < 1e-9);
+        assert!((away - BASE_GOALS / 1.5).abs() < 1e-9);
+        let (home, _) = model.expected_goals(&Match::from("Luton", "Burnley"));
+        assert!((home - BASE_GOALS * 1.2).abs() < 1e-9);
+        let neutral = Match::from("Burnley", "Luton").with_site(Site::Neutral);
+        assert_eq!((BASE_GOALS, BASE_GOALS), model.expected_goals(&neutral));
+
+        // in-season updates keep a team's own home advantage
+        model.set_rating("Luton", TeamRating::new(0.9, 1.1).with_home_advantage(1.1));
+        let result = MatchResult::new(Match::from("Luton", "Burnley"), 2, 1);
+        assert_eq!(
+            1.1,
+            model.updated(&[result], 5.0).home_advantage_of("Luton")
+        );
+    }
+
+    #[test]
+    fn head_to_head_adjustments() {
+        // Spurs have failed to score in three trips to an average Chelsea side
+        let meetings = vec![MatchResult::new(Match::from("Chelsea", "Spurs"), 2, 0); 3];
+        let model = PoissonModel::new(1.5, 1.0).with_head_to_head(&meetings, 3.0);
+        let spurs = model.head_to_head("Spurs", "Chelsea");
+        assert!((spurs - 3.0 / 7.5).abs() < 1e-9);
+        assert!((model.head_to_head("Chelsea", "Spurs") - 9.0 / 7.5).abs() < 1e-9);
+        assert_eq!(1.0, model.head_to_head("Spurs", "Arsenal"));
+
+        // the nudge applies whichever side is at home
+        let (_, away) = model.expected_goals(&Match::from("Chelsea", "Spurs"));
+        let (home, _) = model.expected_goals(&Match::from("Spurs", "Chelsea"));
+        assert!((away - 1.5 * spurs).abs() < 1e-9);
+        assert_eq!(away, home);
+        assert_eq!(
+            (1.5, 1.5),
+            model.expected_goals(&Match::from("Spurs", "Arsenal"))
+        );
+
+        let json = r#"{"head_to_head": {"Spurs": {"Chelsea": 0.8}}}"#;
+        let loaded = PoissonModel::from_json(json.as_bytes()).unwrap();
+        assert_eq!(0.8, loaded.head_to_head("Spurs", "Chelsea"));
+    }
+}
@@ -0,0 +1,1304 @@
+//! Sources of the standings and fixtures a season is simulated from
+//!
+//! Each source implements DataProvider, so the web app and anything else
+//! built on the library can be pointed at a different one without the
+//! simulation code knowing where the data came from
+
+use crate::{
+    read_fixtures_from, read_json, read_league_fixtures, read_league_format, read_league_standings,
+    read_standings_from, DataSource, FixtureList, LeagueError, LeagueFormat, LeagueId, LeagueTable,
+    LiveScore, Match, StandingsSnapshot, Team, FIXTURES_FILE, FORMAT_FILE, STANDINGS_FILE,
+};
+use chrono::{DateTime, Utc};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const API_FOOTBALL_URL: &str = "https://v3.football.api-sports.io";
+const FOOTBALL_DATA_URL: &str = "https://api.football-data.org/v4";
+// API-Football statuses of fixtures still to be played: not yet scheduled,
+// not started and postponed
+const UNPLAYED_STATUSES: [&str; 3] = ["TBD", "NS", "PST"];
+// API-Football statuses of fixtures under way: either half, half time, extra
+// time, the break before it, penalties, and suspended or interrupted play
+const IN_PLAY_STATUSES: [&str; 8] = ["1H", "HT", "2H", "ET", "BT", "P", "SUSP", "INT"];
+// football-data.org statuses of matches still to be played: without and with
+// a kickoff time, and postponed
+const UNPLAYED_MATCHES: [&str; 3] = ["SCHEDULED", "TIMED", "POSTPONED"];
+// football-data.org statuses of matches under way, at half time or suspended
+const IN_PLAY_MATCHES: [&str; 3] = ["IN_PLAY", "PAUSED", "SUSPENDED"];
+// the minutes from kickoff to the end of the first half and of the break,
+// used to tell how far a match has gone from its kickoff time alone
+const FIRST_HALF_MINUTES: i64 = 45;
+const HALF_TIME_MINUTES: i64 = 15;
+// how long cached data is served before it is fetched again, unless set otherwise
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const STANDINGS_CACHE: &str = "standings.json";
+const FIXTURES_CACHE: &str = "fixtures.json";
+
+/// A source of the current standings and the fixtures left to play
+pub trait DataProvider {
+    /// Returns the current standings alongside a snapshot recording where
+    /// they were read from and when they were last updated
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError>;
+
+    /// Returns the fixtures left to play
+    fn fixtures(&self) -> Result<FixtureList, LeagueError>;
+
+    /// Returns how the league's season is played out, a double round robin
+    /// unless the source says otherwise
+    fn format(&self) -> Result<LeagueFormat, LeagueError> {
+        Ok(LeagueFormat::default())
+    }
+
+    /// Returns the files on disk the data is read from, so that they can be
+    /// watched for changes, or nothing if it does not come from files
+    fn files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+impl<P: DataProvider + ?Sized> DataProvider for Box<P> {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        (**self).standings()
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        (**self).fixtures()
+    }
+
+    fn format(&self) -> Result<LeagueFormat, LeagueError> {
+        (**self).format()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        (**self).files()
+    }
+}
+
+impl<P: DataProvider + ?Sized> DataProvider for Arc<P> {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        (**self).standings()
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        (**self).fixtures()
+    }
+
+    fn format(&self) -> Result<LeagueFormat, LeagueError> {
+        (**self).format()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        (**self).files()
+    }
+}
+
+/// Reads the standings and then the fixtures from a provider on the tokio
+/// runtime's pool of blocking threads, so that a slow source never holds up
+/// the task awaiting it, and several can be loaded at once
+///
+/// This is how the providers fetching from web APIs are made async: their
+/// requests are made with a blocking http client, so each load holds one of
+/// the pool's threads, not a worker of the runtime, for as long as it waits
+///
+/// Must be awaited within a tokio runtime, such as the web server's
+pub async fn load_async<P: DataProvider + Send + 'static>(
+    provider: P,
+) -> Result<(LeagueTable, StandingsSnapshot, FixtureList), LeagueError> {
+    tokio::task::spawn_blocking(move || {
+        let (table, snapshot) = provider.standings()?;
+        Ok((table, snapshot, provider.fixtures()?))
+    })
+    .await
+    .map_err(|error| LeagueError::Io(std::io::Error::other(error)))?
+}
+
+/// Reads the crate's own json files, in the formats described on
+/// read_standings_from and read_fixtures_from
+///
+/// Either file not set is read from its league's directory under the working
+/// directory, the Premier League's unless another league is chosen. The
+/// league's format is read from the format.json in its directory, if it has one
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct JsonFiles {
+    league: LeagueId,
+    standings: Option<PathBuf>,
+    fixtures: Option<PathBuf>,
+}
+
+impl JsonFiles {
+    /// create a provider reading both files from their default paths
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads any file not given a path of its own from the given league's directory
+    pub fn with_league(mut self, league: LeagueId) -> Self {
+        self.league = league;
+        self
+    }
+
+    /// Returns the league whose directory files are read from by default
+    pub fn league(&self) -> &LeagueId {
+        &self.league
+    }
+
+    /// Reads the standings from the given path
+    pub fn with_standings<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.standings = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Reads the fixtures from the given path
+    pub fn with_fixtures<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.fixtures = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl DataProvider for JsonFiles {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        match &self.standings {
+            Some(path) => read_standings_from(path),
+            None => read_league_standings(&self.league),
+        }
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        match &self.fixtures {
+            Some(path) => read_fixtures_from(path),
+            None => read_league_fixtures(&self.league),
+        }
+    }
+
+    fn format(&self) -> Result<LeagueFormat, LeagueError> {
+        read_league_format(&self.league)
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let standings = self
+            .standings
+            .clone()
+            .or_else(|| self.league.path(STANDINGS_FILE).ok());
+        let fixtures = self
+            .fixtures
+            .clone()
+            .or_else(|| self.league.path(FIXTURES_FILE).ok());
+        let format = self
+            .league
+            .path(FORMAT_FILE)
+            .ok()
+            .filter(|path| path.is_file());
+        standings
+            .into_iter()
+            .chain(fixtures)
+            .chain(format)
+            .collect()
+    }
+}
+
+/// Reads the standings and fixtures from API-Football's v3 endpoints,
+/// <https://www.api-football.com/documentation-v3>, either fetched from the
+/// API or from responses saved to disk
+///
+/// The standings are taken from every group of the first league in the
+/// response, and dated by the latest "update" time the response gives. Only
+/// fixtures still to be played or under way are kept, each with its kickoff
+/// time, for rounds named like "Regular Season - 30" its matchday, and for
+/// one under way its live score
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiFootball {
+    responses: Responses,
+}
+
+impl ApiFootball {
+    /// create a provider reading the saved standings and fixtures responses at the given paths
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(standings: P, fixtures: Q) -> Self {
+        Self {
+            responses: Responses::saved(standings, fixtures),
+        }
+    }
+
+    /// create a provider fetching the given league's season, such as 39 and
+    /// 2024 for the Premier League's 2024-25 season, from the API with the
+    /// given key
+    ///
+    /// Each fetch of the standings or the fixtures is one request against the
+    /// key's daily quota, so a provider that is loaded often is best wrapped
+    /// in a CachedProvider
+    pub fn fetch(key: &str, league: u32, season: u32) -> Self {
+        let query = format!("league={league}&season={season}");
+        Self {
+            responses: Responses::Live {
+                standings: format!("{API_FOOTBALL_URL}/standings?{query}"),
+                fixtures: format!("{API_FOOTBALL_URL}/fixtures?{query}"),
+                header: "x-apisports-key",
+                key: key.to_string(),
+            },
+        }
+    }
+}
+
+impl DataProvider for ApiFootball {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        let (response, source, fetched): (Response<StandingsEntry>, _, _) =
+            self.responses.standings()?;
+        response.check()?;
+        let rows: Vec<&StandingsRow> = response
+            .response
+            .iter()
+            .take(1)
+            .flat_map(|entry| entry.league.standings.iter().flatten())
+            .collect();
+        let updated_at = rows
+            .iter()
+            .filter_map(|row| row.update)
+            .max()
+            .map(SystemTime::from)
+            .unwrap_or(fetched);
+        let table = rows
+            .into_iter()
+            .map(StandingsRow::team)
+            .collect::<LeagueTable>();
+        Ok((table, StandingsSnapshot::new(source, updated_at)))
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        let response: Response<FixtureEntry> = self.responses.fixtures()?;
+        response.check()?;
+        Ok(response
+            .response
+            .iter()
+            .filter(|entry| {
+                let status = entry.fixture.status.short.as_str();
+                UNPLAYED_STATUSES.contains(&status) || IN_PLAY_STATUSES.contains(&status)
+            })
+            .map(FixtureEntry::fixture)
+            .collect())
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.responses.files()
+    }
+}
+
+/// Reads the standings and fixtures from football-data.org's v4 API,
+/// <https://docs.football-data.org/general/v4/index.html>, either fetched
+/// from the API or from responses saved to disk
+///
+/// The standings are taken from the competition's overall tables, every
+/// group's where it has more than one, and dated by when they were fetched or
+/// saved. Only matches still to be played or under way are kept, each with
+/// its kickoff time, matchday and, for one under way, its live score. Any
+/// whose teams are not yet known are left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootballData {
+    responses: Responses,
+}
+
+impl FootballData {
+    /// create a provider reading the saved standings and matches responses at the given paths
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(standings: P, matches: Q) -> Self {
+        Self {
+            responses: Responses::saved(standings, matches),
+        }
+    }
+
+    /// create a provider fetching the current season of the competition with
+    /// the given code, such as "PL" for the Premier League, from the API with
+    /// the given token
+    ///
+    /// The free tier allows ten requests a minute, and each fetch of the
+    /// standings or the matches is one, so a provider that is loaded often
+    /// is best wrapped in a CachedProvider
+    pub fn fetch(token: &str, competition: &str) -> Self {
+        let competition = format!("{FOOTBALL_DATA_URL}/competitions/{competition}");
+        Self {
+            responses: Responses::Live {
+                standings: format!("{competition}/standings"),
+                fixtures: format!("{competition}/matches"),
+                header: "X-Auth-Token",
+                key: token.to_string(),
+            },
+        }
+    }
+}
+
+impl DataProvider for FootballData {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        let (response, source, fetched): (CompetitionStandings, _, _) =
+            self.responses.standings()?;
+        let table = response
+            .standings
+            .iter()
+            .filter(|standing| standing.kind == "TOTAL")
+            .flat_map(|standing| standing.table.iter().map(TableRow::team))
+            .collect::<LeagueTable>();
+        Ok((table, StandingsSnapshot::new(source, fetched)))
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        let response: CompetitionMatches = self.responses.fixtures()?;
+        Ok(response
+            .matches
+            .iter()
+            .filter(|entry| {
+                let status = entry.status.as_str();
+                UNPLAYED_MATCHES.contains(&status) || IN_PLAY_MATCHES.contains(&status)
+            })
+            .filter_map(|entry| entry.fixture(Utc::now()))
+            .collect())
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.responses.files()
+    }
+}
+
+/// Where a web API provider gets its standings and fixtures responses from
+#[derive(Clone, PartialEq, Eq)]
+enum Responses {
+    /// Responses saved to disk, read in place of the API
+    Saved {
+        standings: PathBuf,
+        fixtures: PathBuf,
+    },
+    /// The API itself, sending the key in the given header with each request
+    Live {
+        standings: String,
+        fixtures: String,
+        header: &'static str,
+        key: String,
+    },
+}
+
+impl Responses {
+    /// create a source reading the saved responses at the given paths
+    fn saved<P: AsRef<Path>, Q: AsRef<Path>>(standings: P, fixtures: Q) -> Self {
+        Self::Saved {
+            standings: standings.as_ref().to_path_buf(),
+            fixtures: fixtures.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the standings response, along with where it came from and
+    /// when it was saved or fetched
+    fn standings<T: DeserializeOwned>(&self) -> Result<(T, DataSource, SystemTime), LeagueError> {
+        match self {
+            Self::Saved { standings, .. } => {
+                let file = File::open(standings)?;
+                let modified = file.metadata().and_then(|metadata| metadata.modified());
+                let response = read_json(BufReader::new(file))?;
+                let saved_at = modified.unwrap_or_else(|_| SystemTime::now());
+                Ok((response, DataSource::File(standings.clone()), saved_at))
+            }
+            Self::Live {
+                standings,
+                header,
+                key,
+                ..
+            } => {
+                let response = fetch(standings, header, key)?;
+                Ok((
+                    response,
+                    DataSource::Api(standings.clone()),
+                    SystemTime::now(),
+                ))
+            }
+        }
+    }
+
+    /// Returns the fixtures response
+    fn fixtures<T: DeserializeOwned>(&self) -> Result<T, LeagueError> {
+        match self {
+            Self::Saved { fixtures, .. } => read_json(BufReader::new(File::open(fixtures)?)),
+            Self::Live {
+                fixtures,
+                header,
+                key,
+                ..
+            } => fetch(fixtures, header, key),
+        }
+    }
+
+    /// Returns the saved responses' paths, or nothing for the API
+    fn files(&self) -> Vec<PathBuf> {
+        match self {
+            Self::Saved {
+                standings,
+                fixtures,
+            } => vec![standings.clone(), fixtures.clone()],
+            Self::Live { .. } => Vec::new(),
+        }
+    }
+}
+
+// written by hand so that the key is never printed
+impl std::fmt::Debug for Responses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Saved {
+                standings,
+                fixtures,
+            } => f
+                .debug_struct("Saved")
+                .field("standings", standings)
+                .field("fixtures", fixtures)
+                .finish(),
+            Self::Live {
+                standings,
+                fixtures,
+                ..
+            } => f
+                .debug_struct("Live")
+                .field("standings", standings)
+                .field("fixtures", fixtures)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Fetches and deserializes the response from the given url, sending the key
+/// in the given header
+///
+/// A failed request, including one answered with an error status such as 401
+/// for a bad key or 429 for too many requests, is reported as an io error
+fn fetch<T: DeserializeOwned>(url: &str, header: &str, key: &str) -> Result<T, LeagueError> {
+    let mut response = ureq::get(url)
+        .header(header, key)
+        .call()
+        .map_err(|error| LeagueError::Io(http_error(url, error)))?;
+    read_json(response.body_mut().as_reader())
+}
+
+/// Converts an error from an http request into an io error naming the url
+fn http_error(url: &str, error: ureq::Error) -> std::io::Error {
+    match error {
+        ureq::Error::Io(error) => std::io::Error::new(error.kind(), format!("{url}: {error}")),
+        error => std::io::Error::other(format!("{url}: {error}")),
+    }
+}
+
+/// Wraps another provider to keep what it returns in a directory on disk,
+/// serving the saved copy until it is older than the time to live
+///
+/// The standings and fixtures are cached separately, each with the time it
+/// was fetched, so a provider behind a rate limit, such as ApiFootball or
+/// FootballData fetching from their APIs, is asked at most once per time to
+/// live and the app keeps running offline on recently cached data. A
+/// cache file that is missing, expired or unreadable is fetched again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedProvider<P> {
+    provider: P,
+    directory: PathBuf,
+    ttl: Duration,
+}
+
+impl<P: DataProvider> CachedProvider<P> {
+    /// create a cache of the given provider in the given directory, which is
+    /// created on the first fetch, with a time to live of an hour
+    pub fn new<D: AsRef<Path>>(provider: P, directory: D) -> Self {
+        Self {
+            provider,
+            directory: directory.as_ref().to_path_buf(),
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Sets how long cached data is served before it is fetched again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns how long cached data is served before it is fetched again
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the directory the cache files are kept in
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the data cached under the given file name, if it is there and
+    /// no older than the time to live
+    fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let file = File::open(self.directory.join(name)).ok()?;
+        let entry: CacheEntry<T> = read_json(BufReader::new(file)).ok()?;
+        let age = (Utc::now() - entry.cached_at).to_std().unwrap_or_default();
+        (age <= self.ttl).then_some(entry.data)
+    }
+
+    /// Saves the data under the given file name, stamped with the current time
+    fn store<T: Serialize>(&self, name: &str, data: T) -> Result<(), LeagueError> {
+        std::fs::create_dir_all(&self.directory)?;
+        let file = File::create(self.directory.join(name))?;
+        let entry = CacheEntry {
+            cached_at: Utc::now(),
+            data,
+        };
+        serde_json::to_writer(BufWriter::new(file), &entry).map_err(std::io::Error::from)?;
+        Ok(())
+    }
+}
+
+impl<P: DataProvider> DataProvider for CachedProvider<P> {
+    fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+        if let Some(cached) = self.load::<CachedStandings>(STANDINGS_CACHE) {
+            let snapshot = StandingsSnapshot::new(cached.source, cached.updated_at.into());
+            return Ok((cached.teams.into_iter().collect(), snapshot));
+        }
+        let (table, snapshot) = self.provider.standings()?;
+        let cached = CachedStandings {
+            source: snapshot.source().clone(),
+            updated_at: snapshot.updated_at().into(),
+            teams: table.teams().cloned().collect(),
+        };
+        self.store(STANDINGS_CACHE, cached)?;
+        Ok((table, snapshot))
+    }
+
+    fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+        if let Some(fixtures) = self.load(FIXTURES_CACHE) {
+            return Ok(fixtures);
+        }
+        let fixtures = self.provider.fixtures()?;
+        self.store(FIXTURES_CACHE, &fixtures)?;
+        Ok(fixtures)
+    }
+
+    fn format(&self) -> Result<LeagueFormat, LeagueError> {
+        self.provider.format()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.provider.files()
+    }
+}
+
+/// Watches data files for changes, calling back with the path of each file
+/// changed so that the data can be reloaded without a restart
+///
+/// The directories holding the files are watched rather than the files
+/// themselves, so a file replaced whole, as many editors and sync tools do,
+/// is still noticed. A single save may be reported more than once. Watching
+/// stops when the watcher is dropped
+pub struct DataWatcher {
+    // kept only to keep the watch running
+    _watcher: RecommendedWatcher,
+}
+
+impl DataWatcher {
+    /// create a watcher on the given files, calling on_change from a thread
+    /// of its own each time one of them is written, created or replaced
+    pub fn new<F>(files: &[PathBuf], on_change: F) -> Result<Self, LeagueError>
+    where
+        F: Fn(&Path) + Send + 'static,
+    {
+        let files = files
+            .iter()
+            .map(std::path::absolute)
+            .collect::<Result<Vec<_>, _>>()?;
+        let watched = files.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                if !(event.kind.is_modify() || event.kind.is_create()) {
+                    return;
+                }
+                for path in event.paths.iter().filter(|path| watched.contains(path)) {
+                    on_change(path);
+                }
+            })
+            .map_err(watch_error)?;
+        let mut directories: Vec<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        directories.sort();
+        directories.dedup();
+        for directory in directories {
+            watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .map_err(watch_error)?;
+        }
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+impl std::fmt::Debug for DataWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataWatcher").finish_non_exhaustive()
+    }
+}
+
+/// Converts an error from the file watcher into the crate's error type
+fn watch_error(error: notify::Error) -> LeagueError {
+    match error.kind {
+        notify::ErrorKind::Io(error) => LeagueError::Io(error),
+        _ => LeagueError::Io(std::io::Error::other(error)),
+    }
+}
+
+/// Data kept in a cache file along with when it was fetched
+#[derive(Deserialize, Serialize)]
+struct CacheEntry<T> {
+    cached_at: DateTime<Utc>,
+    data: T,
+}
+
+/// Standings as kept in the cache, with the snapshot they were fetched with
+#[derive(Deserialize, Serialize)]
+struct CachedStandings {
+    source: DataSource,
+    updated_at: DateTime<Utc>,
+    teams: Vec<Team>,
+}
+
+/// The envelope every API-Football response comes in
+#[derive(Deserialize)]
+struct Response<T> {
+    response: Vec<T>,
+    // a request refused, such as for a bad key or an exhausted quota, is
+    // answered with a 200 and the reasons here, either in a list or by name
+    #[serde(default)]
+    errors: serde_json::Value,
+}
+
+impl<T> Response<T> {
+    /// Returns an error holding the reasons given if the request was refused
+    fn check(&self) -> Result<(), LeagueError> {
+        let refused = match &self.errors {
+            serde_json::Value::Array(errors) => !errors.is_empty(),
+            serde_json::Value::Object(errors) => !errors.is_empty(),
+            _ => false,
+        };
+        if refused {
+            let error = format!("API-Football refused the request: {}", self.errors);
+            return Err(LeagueError::Io(std::io::Error::other(error)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct StandingsEntry {
+    league: StandingsLeague,
+}
+
+#[derive(Deserialize)]
+struct StandingsLeague {
+    standings: Vec<Vec<StandingsRow>>,
+}
+
+#[derive(Deserialize)]
+struct StandingsRow {
+    team: NamedTeam,
+    points: u32,
+    #[serde(rename = "goalsDiff")]
+    goals_diff: i32,
+    all: Record,
+    #[serde(default)]
+    update: Option<DateTime<Utc>>,
+}
+
+impl StandingsRow {
+    /// Returns the row as a Team with its full record
+    fn team(&self) -> Team {
+        Team {
+            name: self.team.name.clone(),
+            pts: self.points,
+            goal_diff: self.goals_diff,
+            games_played: self.all.played,
+            wins: self.all.win,
+            draws: self.all.draw,
+            losses: self.all.lose,
+            goals_for: self.all.goals.goals_for,
+            goals_against: self.all.goals.against,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NamedTeam {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    played: u32,
+    win: u32,
+    draw: u32,
+    lose: u32,
+    goals: Goals,
+}
+
+#[derive(Deserialize)]
+struct Goals {
+    #[serde(rename = "for")]
+    goals_for: u32,
+    against: u32,
+}
+
+#[derive(Deserialize)]
+struct FixtureEntry {
+    fixture: FixtureDetails,
+    league: FixtureLeague,
+    teams: FixtureTeams,
+    #[serde(default)]
+    goals: FixtureGoals,
+}
+
+impl FixtureEntry {
+    /// Returns the entry as a Match, with its kickoff, any matchday in its
+    /// round and, for a fixture under way, its live score
+    fn fixture(&self) -> Match {
+        let mut fixture = Match::from(&self.teams.home.name, &self.teams.away.name)
+            .with_kickoff(self.fixture.date);
+        if IN_PLAY_STATUSES.contains(&self.fixture.status.short.as_str()) {
+            let live = LiveScore::new(
+                self.goals.home.unwrap_or(0),
+                self.goals.away.unwrap_or(0),
+                self.fixture.status.elapsed.unwrap_or(0),
+            );
+            fixture = fixture.with_live_score(live);
+        }
+        let matchday = self
+            .league
+            .round
+            .rsplit_once(" - ")
+            .and_then(|(_, number)| number.parse().ok());
+        match matchday {
+            Some(matchday) => fixture.with_matchday(matchday),
+            None => fixture,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FixtureDetails {
+    date: DateTime<Utc>,
+    status: FixtureStatus,
+}
+
+#[derive(Deserialize)]
+struct FixtureStatus {
+    short: String,
+    // minutes played, null before kickoff
+    #[serde(default)]
+    elapsed: Option<u32>,
+}
+
+/// The goals scored so far, null for both sides before kickoff
+#[derive(Default, Deserialize)]
+struct FixtureGoals {
+    home: Option<u32>,
+    away: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FixtureLeague {
+    round: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureTeams {
+    home: NamedTeam,
+    away: NamedTeam,
+}
+
+/// football-data.org's response to a competition's standings
+#[derive(Deserialize)]
+struct CompetitionStandings {
+    standings: Vec<Standing>,
+}
+
+#[derive(Deserialize)]
+struct Standing {
+    // TOTAL for the overall table, HOME or AWAY for those counting only one
+    #[serde(rename = "type")]
+    kind: String,
+    table: Vec<TableRow>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TableRow {
+    team: NamedTeam,
+    played_games: u32,
+    won: u32,
+    draw: u32,
+    lost: u32,
+    points: u32,
+    goals_for: u32,
+    goals_against: u32,
+    goal_difference: i32,
+}
+
+impl TableRow {
+    /// Returns the row as a Team with its full record
+    fn team(&self) -> Team {
+        Team {
+            name: self.team.name.clone(),
+            pts: self.points,
+            goal_diff: self.goal_difference,
+            games_played: self.played_games,
+            wins: self.won,
+            draws: self.draw,
+            losses: self.lost,
+            goals_for: self.goals_for,
+            goals_against: self.goals_against,
+        }
+    }
+}
+
+/// football-data.org's response to a competition's matches
+#[derive(Deserialize)]
+struct CompetitionMatches {
+    matches: Vec<MatchEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchEntry {
+    utc_date: DateTime<Utc>,
+    status: String,
+    #[serde(default)]
+    matchday: Option<u32>,
+    home_team: MatchTeam,
+    away_team: MatchTeam,
+    #[serde(default)]
+    score: MatchScore,
+}
+
+impl MatchEntry {
+    /// Returns the entry as a Match, with its kickoff, any matchday and, for
+    /// a match under way, its live score, or nothing if either team is still
+    /// to be decided
+    ///
+    /// The API does not give the minute, so a match under way is taken to
+    /// have run from its kickoff to now, less the break once past half time
+    fn fixture(&self, now: DateTime<Utc>) -> Option<Match> {
+        let home = self.home_team.name.as_deref()?;
+        let away = self.away_team.name.as_deref()?;
+        let mut fixture = Match::from(home, away).with_kickoff(self.utc_date);
+        if IN_PLAY_MATCHES.contains(&self.status.as_str()) {
+            let minute = if self.status == "PAUSED" {
+                FIRST_HALF_MINUTES
+            } else {
+                let since_kickoff = (now - self.utc_date).num_minutes().max(0);
+                if since_kickoff > FIRST_HALF_MINUTES + HALF_TIME_MINUTES {
+                    since_kickoff - HALF_TIME_MINUTES
+                } else {
+                    since_kickoff.min(FIRST_HALF_MINUTES)
+                }
+            };
+            let live = LiveScore::new(
+                self.score.full_time.home.unwrap_or(0),
+                self.score.full_time.away.unwrap_or(0),
+                u32::try_from(minute).unwrap_or(u32::MAX),
+            );
+            fixture = fixture.with_live_score(live);
+        }
+        Some(match self.matchday {
+            Some(matchday) => fixture.with_matchday(matchday),
+            None => fixture,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct MatchTeam {
+    // null for a knockout tie still waiting on an earlier round
+    name: Option<String>,
+}
+
+/// The score of a match, whose full time score is the score so far while it
+/// is under way
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchScore {
+    #[serde(default)]
+    full_time: FixtureGoals,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// serves a fixed table and fixture list, counting the fetches and
+    /// failing once it is taken offline
+    struct CountingProvider {
+        fetches: Cell<u32>,
+        offline: Cell<bool>,
+    }
+
+    impl DataProvider for CountingProvider {
+        fn standings(&self) -> Result<(LeagueTable, StandingsSnapshot), LeagueError> {
+            if self.offline.get() {
+                return Err(std::io::Error::other("offline").into());
+            }
+            self.fetches.set(self.fetches.get() + 1);
+            let table = [Team::new("Celtic".to_string(), 80, 60)]
+                .into_iter()
+                .collect();
+            let source = DataSource::Api("https://example.com/standings".to_string());
+            Ok((
+                table,
+                StandingsSnapshot::new(source, SystemTime::UNIX_EPOCH),
+            ))
+        }
+
+        fn fixtures(&self) -> Result<FixtureList, LeagueError> {
+            if self.offline.get() {
+                return Err(std::io::Error::other("offline").into());
+            }
+            self.fetches.set(self.fetches.get() + 1);
+            Ok([Match::from("Celtic", "Rangers")].into_iter().collect())
+        }
+    }
+
+    #[test]
+    fn provider_loaded_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let provider: Box<dyn DataProvider + Send> = Box::new(JsonFiles::new());
+        let (table, _, fixtures) = runtime.block_on(load_async(provider)).unwrap();
+        assert_eq!(Ok(()), table.validate_fixtures(&fixtures));
+        let missing = JsonFiles::new().with_standings("data/missing.json");
+        assert!(matches!(
+            runtime.block_on(load_async(missing)),
+            Err(LeagueError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn fetched_async() {
+        // a stand-in for football-data.org, answering each request that
+        // carries the token with the canned response for its path
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = std::io::BufReader::new(&stream);
+                let mut request = Vec::new();
+                let mut line = String::new();
+                while std::io::BufRead::read_line(&mut reader, &mut line).unwrap() > 2 {
+                    request.push(std::mem::take(&mut line));
+                }
+                assert!(request.iter().any(|line| line.contains("token")));
+                let body = if request[0].contains("/standings") {
+                    r#"{"standings": [{"type": "TOTAL", "table": [{"team": {"name": "Celtic"},
+                    "playedGames": 1, "won": 1, "draw": 0, "lost": 0, "points": 3,
+                    "goalsFor": 2, "goalsAgainst": 0, "goalDifference": 2}]}]}"#
+                } else {
+                    r#"{"matches": [{"utcDate": "2025-04-02T18:45:00Z", "status": "TIMED",
+                    "matchday": 2, "homeTeam": {"name": "Rangers"},
+                    "awayTeam": {"name": "Celtic"}}]}"#
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                    content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                std::io::Write::write_all(&mut &stream, response.as_bytes()).unwrap();
+            }
+        });
+        let url = format!("http://{address}/competitions/SPL");
+        let provider = FootballData {
+            responses: Responses::Live {
+                standings: format!("{url}/standings"),
+                fixtures: format!("{url}/matches"),
+                header: "X-Auth-Token",
+                key: "token".to_string(),
+            },
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (table, snapshot, fixtures) = runtime.block_on(load_async(provider)).unwrap();
+        server.join().unwrap();
+        assert_eq!(3, table.get_team("Celtic").unwrap().points());
+        let source = DataSource::Api(format!("{url}/standings"));
+        assert_eq!(&source, snapshot.source());
+        assert_eq!(
+            vec![Match::from("Rangers", "Celtic")
+                .with_kickoff("2025-04-02T18:45:00Z".parse().unwrap())
+                .with_matchday(2)],
+            fixtures.to_vec()
+        );
+    }
+
+    #[test]
+    fn watched_files() {
+        let directory = std::env::temp_dir().join(format!("league-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let standings = directory.join("standings.json");
+        let fixtures = directory.join("fixtures.json");
+        std::fs::write(&standings, "[]").unwrap();
+        std::fs::write(&fixtures, "[]").unwrap();
+        let files = JsonFiles::new()
+            .with_standings(&standings)
+            .with_fixtures(&fixtures);
+        assert_eq!(vec![standings.clone(), fixtures.clone()], files.files());
+        let league = JsonFiles::new().files();
+        assert!(league[0].ends_with("data/premier-league/standings.json"));
+        // the Premier League has no format.json, so it is a double round robin
+        assert_eq!(2, league.len());
+        assert_eq!(LeagueFormat::RoundRobin, JsonFiles::new().format().unwrap());
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let watcher = DataWatcher::new(std::slice::from_ref(&standings), move |path| {
+            let _ = sender.send(path.to_path_buf());
+        })
+        .unwrap();
+        // changes to files not being watched are ignored
+        std::fs::write(&fixtures, "[{}]").unwrap();
+        std::fs::write(
+            &standings,
+            r#"[{"name": "Celtic", "pts": 3, "goal_diff": 2}]"#,
+        )
+        .unwrap();
+        let changed = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(standings, changed);
+        assert!(receiver.try_iter().all(|path| path == standings));
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn cached_provider() {
+        let directory = std::env::temp_dir().join(format!("league-cache-{}", std::process::id()));
+        let provider = CountingProvider {
+            fetches: Cell::new(0),
+            offline: Cell::new(false),
+        };
+        let cache = CachedProvider::new(provider, &directory);
+        assert_eq!(Duration::from_secs(3600), cache.ttl());
+        let (table, snapshot) = cache.standings().unwrap();
+        let fixtures = cache.fixtures().unwrap();
+        assert_eq!(2, cache.provider.fetches.get());
+
+        // within the time to live the cache answers, even with the provider offline
+        cache.provider.offline.set(true);
+        let (cached_table, cached_snapshot) = cache.standings().unwrap();
+        assert_eq!(
+            table.teams().collect::<Vec<_>>(),
+            cached_table.teams().collect::<Vec<_>>()
+        );
+        assert_eq!(snapshot, cached_snapshot);
+        assert_eq!(fixtures, cache.fixtures().unwrap());
+        assert_eq!(2, cache.provider.fetches.get());
+
+        // once expired the data is fetched again
+        let cache = cache.with_ttl(Duration::ZERO);
+        assert!(cache.standings().is_err());
+        cache.provider.offline.set(false);
+        cache.fixtures().unwrap();
+        assert_eq!(3, cache.provider.fetches.get());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn api_football_responses() {
+        let directory = std::env::temp_dir().join(format!("api-football-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let row = |name: &str, points: u32, diff: i32, record: [u32; 6], update: &str| {
+            let [played, win, draw, lose, scored, against] = record;
+            format!(
+                r#"{{"rank": 1, "team": {{"id": 1, "name": "{name}"}}, "points": {points},
+                "goalsDiff": {diff}, "all": {{"played": {played}, "win": {win}, "draw": {draw},
+                "lose": {lose}, "goals": {{"for": {scored}, "against": {against}}}}},
+                "update": "{update}"}}"#
+            )
+        };
+        let standings = directory.join("standings.json");
+        let json = format!(
+            r#"{{"get": "standings", "response": [{{"league": {{"id": 39, "standings": [[{}, {}]]}}}}]}}"#,
+            row(
+                "Liverpool",
+                70,
+                42,
+                [29, 21, 7, 1, 69, 27],
+                "2025-03-10T00:00:00+00:00"
+            ),
+            row(
+                "Arsenal",
+                58,
+                29,
+                [29, 16, 10, 3, 53, 24],
+                "2025-03-09T00:00:00+00:00"
+            ),
+        );
+        std::fs::write(&standings, json).unwrap();
+        let fixtures = directory.join("fixtures.json");
+        let entry = |home: &str, away: &str, status: &str, round: &str| {
+            format!(
+                r#"{{"fixture": {{"id": 1, "date": "2025-04-02T19:45:00+01:00",
+                "status": {{"short": "{status}"}}}}, "league": {{"round": "{round}"}},
+                "teams": {{"home": {{"name": "{home}"}}, "away": {{"name": "{away}"}}}}}}"#
+            )
+        };
+        // a fixture under way is still to be finished, from its live score
+        let in_play = r#"{"fixture": {"id": 2, "date": "2025-04-02T19:45:00+01:00",
+            "status": {"short": "2H", "elapsed": 67}}, "league": {"round": "Regular Season - 30"},
+            "teams": {"home": {"name": "Fulham"}, "away": {"name": "Liverpool"}},
+            "goals": {"home": 1, "away": 2}}"#;
+        let json = format!(
+            r#"{{"response": [{}, {}, {}, {in_play}]}}"#,
+            entry("Arsenal", "Liverpool", "FT", "Regular Season - 29"),
+            entry("Liverpool", "Arsenal", "NS", "Regular Season - 30"),
+            entry("Arsenal", "Fulham", "PST", "Quarter-finals"),
+        );
+        std::fs::write(&fixtures, json).unwrap();
+
+        let provider: Box<dyn DataProvider> = Box::new(ApiFootball::new(&standings, &fixtures));
+        let (table, snapshot) = provider.standings().unwrap();
+        assert_eq!(2, table.len());
+        let liverpool = table.get_team("Liverpool").unwrap();
+        assert_eq!(
+            (70, 42, 29, 1),
+            (
+                liverpool.points(),
+                liverpool.goal_diff(),
+                liverpool.games_played(),
+                liverpool.losses()
+            )
+        );
+        let update = "2025-03-10T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(SystemTime::from(update), snapshot.updated_at());
+
+        let fixture_list = provider.fixtures().unwrap();
+        let kickoff = "2025-04-02T18:45:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            vec![
+                Match::from("Liverpool", "Arsenal")
+                    .with_kickoff(kickoff)
+                    .with_matchday(30),
+                Match::from("Arsenal", "Fulham").with_kickoff(kickoff),
+                Match::from("Fulham", "Liverpool")
+                    .with_kickoff(kickoff)
+                    .with_matchday(30)
+                    .with_live_score(LiveScore::new(1, 2, 67)),
+            ],
+            fixture_list.to_vec()
+        );
+        std::fs::write(&fixtures, r#"{"response": [{"fixture": {}}]}"#).unwrap();
+        assert!(matches!(provider.fixtures(), Err(LeagueError::Schema(_))));
+        let refused = r#"{"errors": {"token": "Error/Missing application key"}, "response": []}"#;
+        std::fs::write(&fixtures, refused).unwrap();
+        assert!(matches!(provider.fixtures(), Err(LeagueError::Io(_))));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn football_data_responses() {
+        let directory = std::env::temp_dir().join(format!("football-data-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let row = |name: &str, record: [u32; 7], diff: i32| {
+            let [played, won, draw, lost, points, scored, against] = record;
+            format!(
+                r#"{{"position": 1, "team": {{"id": 1, "name": "{name}", "shortName": "{name}"}},
+                "playedGames": {played}, "form": null, "won": {won}, "draw": {draw},
+                "lost": {lost}, "points": {points}, "goalsFor": {scored},
+                "goalsAgainst": {against}, "goalDifference": {diff}}}"#
+            )
+        };
+        let standings = directory.join("standings.json");
+        let json = format!(
+            r#"{{"competition": {{"code": "PL"}}, "standings": [
+            {{"stage": "REGULAR_SEASON", "type": "TOTAL", "group": null, "table": [{}, {}]}},
+            {{"stage": "REGULAR_SEASON", "type": "HOME", "group": null, "table": [{}]}}]}}"#,
+            row("Liverpool FC", [29, 21, 7, 1, 70, 69, 27], 42),
+            row("Arsenal FC", [29, 16, 10, 3, 58, 53, 24], 29),
+            row("Liverpool FC", [15, 12, 3, 0, 39, 38, 11], 27),
+        );
+        std::fs::write(&standings, json).unwrap();
+        let matches = directory.join("matches.json");
+        let entry = |home: &str, away: &str, status: &str, matchday: &str| {
+            format!(
+                r#"{{"id": 1, "utcDate": "2025-04-02T18:45:00Z", "status": "{status}",
+                "matchday": {matchday}, "homeTeam": {{"name": {home}}},
+                "awayTeam": {{"name": {away}}}}}"#
+            )
+        };
+        // a match at half time is still to be finished, from its score so far
+        let paused = r#"{"id": 2, "utcDate": "2025-04-02T18:45:00Z", "status": "PAUSED",
+            "matchday": 30, "homeTeam": {"name": "Fulham FC"},
+            "awayTeam": {"name": "Liverpool FC"},
+            "score": {"fullTime": {"home": 0, "away": 1}}}"#;
+        let json = format!(
+            r#"{{"matches": [{}, {}, {}, {}, {paused}]}}"#,
+            entry(r#""Arsenal FC""#, r#""Liverpool FC""#, "FINISHED", "29"),
+            entry(r#""Liverpool FC""#, r#""Arsenal FC""#, "TIMED", "30"),
+            entry(r#""Arsenal FC""#, r#""Fulham FC""#, "POSTPONED", "null"),
+            entry("null", r#""Arsenal FC""#, "SCHEDULED", "null"),
+        );
+        std::fs::write(&matches, json).unwrap();
+
+        let provider: Box<dyn DataProvider> = Box::new(FootballData::new(&standings, &matches));
+        assert_eq!(vec![standings.clone(), matches.clone()], provider.files());
+        let (table, snapshot) = provider.standings().unwrap();
+        assert_eq!(2, table.len());
+        let liverpool = table.get_team("Liverpool FC").unwrap();
+        assert_eq!(
+            (70, 42, 29, 1),
+            (
+                liverpool.points(),
+                liverpool.goal_diff(),
+                liverpool.games_played(),
+                liverpool.losses()
+            )
+        );
+        assert_eq!(&DataSource::File(standings.clone()), snapshot.source());
+
+        let kickoff = "2025-04-02T18:45:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            vec![
+                Match::from("Liverpool FC", "Arsenal FC")
+                    .with_kickoff(kickoff)
+                    .with_matchday(30),
+                Match::from("Arsenal FC", "Fulham FC").with_kickoff(kickoff),
+                Match::from("Fulham FC", "Liverpool FC")
+                    .with_kickoff(kickoff)
+                    .with_matchday(30)
+                    .with_live_score(LiveScore::new(0, 1, 45)),
+            ],
+            provider.fixtures().unwrap().to_vec()
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // a match in play is timed from its kickoff, less the break after 45 minutes
+        let mut in_play: MatchEntry =
+            serde_json::from_str(&paused.replace("PAUSED", "IN_PLAY")).unwrap();
+        let minute = |entry: &MatchEntry, since: i64| {
+            let now = kickoff + chrono::Duration::minutes(since);
+            entry.fixture(now).unwrap().live_score().unwrap().minute()
+        };
+        assert_eq!(
+            (30, 45, 45, 70),
+            (
+                minute(&in_play, 30),
+                minute(&in_play, 50),
+                minute(&in_play, 60),
+                minute(&in_play, 85)
+            )
+        );
+        in_play.status = "SCHEDULED".to_string();
+        assert_eq!(None, in_play.fixture(kickoff).unwrap().live_score());
+
+        // fetching from the API reads no files, and never prints its token
+        let live = FootballData::fetch("secret-token", "PL");
+        assert!(live.files().is_empty());
+        assert!(!format!("{live:?}").contains("secret-token"));
+    }
+}